@@ -1,20 +1,93 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use anyhow::{bail, Error};
 
-use clap::Subcommand;
+use clap::{Args, Subcommand};
 use fehler::throws;
-use trident_client::___private::{Commander, TestGenerator};
+use trident_client::___private::{Commander, FuzzTestSelection, TestGenerator};
 
-use crate::{_discover, show_howto};
+use crate::{_absolute_paths, _discover, show_howto};
 
 pub const TRIDENT_TOML: &str = "Trident.toml";
 
+#[derive(Args)]
+pub struct SelectionArgs {
+    #[arg(
+        long,
+        required = false,
+        value_delimiter = ',',
+        help = "Generate only the instructions of the specified programs, separated by commas."
+    )]
+    program: Vec<String>,
+    #[arg(
+        long,
+        required = false,
+        value_delimiter = ',',
+        help = "Generate only the specified instructions, separated by commas."
+    )]
+    include: Vec<String>,
+    #[arg(
+        long,
+        required = false,
+        value_delimiter = ',',
+        help = "Do not generate the specified instructions, separated by commas."
+    )]
+    exclude: Vec<String>,
+}
+
+impl From<SelectionArgs> for FuzzTestSelection {
+    fn from(args: SelectionArgs) -> Self {
+        Self {
+            programs: args.program,
+            include: args.include,
+            exclude: args.exclude,
+        }
+    }
+}
+
 #[derive(Subcommand)]
 #[allow(non_camel_case_types)]
 pub enum FuzzCommand {
-    #[command(about = "Generate new Fuzz Test template.")]
-    Add,
+    #[command(
+        about = "Generate new Fuzz Test template.",
+        override_usage = "Optionally specify the \x1b[92m<IDL>\x1b[0m files or directories to generate the Fuzz Test from.\
+            \n      \x1b[1m\x1b[4m<IDL>:\x1b[0m Anchor, Shank or Codama IDL, the programs are not built if specified.\
+            \n\n\x1b[1m\x1b[4mEXAMPLE:\x1b[0m\
+            \n      trident fuzz add\
+            \n      trident fuzz add --idl target/idl/my_program.json --idl idl/\
+            \n      trident fuzz add --program my_program --include initialize,deposit\
+            \n      trident fuzz add --exclude close_account"
+    )]
+    Add {
+        #[arg(
+            long,
+            required = false,
+            value_name = "IDL",
+            help = "Generate the fuzz test from the IDL file or directory with IDLs, without building the programs. Can be repeated."
+        )]
+        idl: Vec<PathBuf>,
+        #[command(flatten)]
+        selection: SelectionArgs,
+    },
+    #[command(
+        about = "Regenerate the desired fuzz test from the current IDLs, preserving the user code.",
+        override_usage = "Specify the desired fuzz \x1b[92m<TARGET>\x1b[0m.\
+            \n      \x1b[1m\x1b[4m<TARGET>:\x1b[0m Name of the desired fuzz template to refresh (for example fuzz_0).\
+            \n\n\x1b[1m\x1b[4mHINT:\x1b[0m Conflicting changes are stored in the following file:\
+            \n      trident-tests/<TARGET>/fuzz_instructions.diff\
+            \n\n\x1b[1m\x1b[4mEXAMPLE:\x1b[0m\
+            \n      trident fuzz refresh fuzz_0\
+            \n      trident fuzz refresh fuzz_0 --program my_program --include initialize,deposit"
+    )]
+    Refresh {
+        #[arg(
+            required = true,
+            help = "Name of the desired fuzz template to refresh (for example fuzz_0)."
+        )]
+        target: String,
+        #[command(flatten)]
+        selection: SelectionArgs,
+    },
     #[command(
         about = "Run the AFL on desired fuzz test.",
         override_usage = "Specify the desired fuzz \x1b[92m<TARGET>\x1b[0m.\
@@ -55,10 +128,11 @@ pub enum FuzzCommand {
         about = "Debug found crash using the AFL on desired fuzz test.",
         override_usage = "Specify the desired fuzz \x1b[92m<TARGET>\x1b[0m and \x1b[92m<PATH_TO_CRASHFILE>\x1b[0m.\
             \n      \x1b[1m\x1b[4m<TARGET>:\x1b[0m Name of the desired fuzz template to debug (for example fuzz_0).\
-            \n      \x1b[1m\x1b[4m<PATH_TO_CRASHFILE>:\x1b[0m Path to the crash found during fuzzing.\
+            \n      \x1b[1m\x1b[4m<PATH_TO_CRASHFILE>:\x1b[0m Path to the crash found during fuzzing, or to a crash chain directory of the stateful mode.\
             \n\n\x1b[1m\x1b[4mHINT:\x1b[0m By default crashfiles will be stored in the following folders:\
             \n      \x1b[1m\x1b[4mHonggfuzz:\x1b[0m trident-tests/fuzzing/honggfuzz/hfuzz_workspace/<TARGET>\
             \n      \x1b[1m\x1b[4mAFL:\x1b[0m trident-tests/fuzzing/afl/afl_workspace/out/default/crashes\
+            \n      \x1b[1m\x1b[4mCrash chains:\x1b[0m trident-tests/fuzzing/crash_chains\
             \n\n\x1b[1m\x1b[4mEXAMPLE:\x1b[0m\
             \n      trident fuzz debug-afl fuzz_0 trident-tests/fuzzing/afl/afl_workspace/out/default/crashes/id...\
             \n\n\x1b[1m\x1b[33mWarning\x1b[0m:\
@@ -70,16 +144,20 @@ pub enum FuzzCommand {
             help = "Name of the desired fuzz template to execute (for example fuzz_0)"
         )]
         target: String,
-        #[arg(required = true, help = "Path to the crash found during fuzzing")]
+        #[arg(
+            required = true,
+            help = "Path to the crash found during fuzzing, or to a crash chain directory"
+        )]
         crash_file_path: String,
     },
     #[command(
         about = "Debug found crash using the Honggfuzz on desired fuzz test.",
         override_usage = "Specify the desired fuzz \x1b[92m<TARGET>\x1b[0m and \x1b[92m<PATH_TO_CRASHFILE>\x1b[0m.\
             \n      \x1b[1m\x1b[4m<TARGET>:\x1b[0m Name of the desired fuzz template to debug (for example fuzz_0).\
-            \n      \x1b[1m\x1b[4m<PATH_TO_CRASHFILE>:\x1b[0m Path to the crash found during fuzzing.\
+            \n      \x1b[1m\x1b[4m<PATH_TO_CRASHFILE>:\x1b[0m Path to the crash found during fuzzing, or to a crash chain directory of the stateful mode.\
             \n\n\x1b[1m\x1b[4mHINT:\x1b[0m By default crashfiles will be stored in the following folders:\
             \n      \x1b[1m\x1b[4mHonggfuzz:\x1b[0m trident-tests/fuzzing/honggfuzz/hfuzz_workspace/<TARGET>\
+            \n      \x1b[1m\x1b[4mCrash chains:\x1b[0m trident-tests/fuzzing/crash_chains\
             \n\n\x1b[1m\x1b[4mEXAMPLE:\x1b[0m\
             \n      trident fuzz debug-hfuzz fuzz_0 trident-tests/fuzzing/honggfuzz/hfuzz_workspace/fuzz_0/SIGAR...\
             \n\n\x1b[1m\x1b[33mWarning\x1b[0m:\
@@ -91,7 +169,10 @@ pub enum FuzzCommand {
             help = "Name of the desired fuzz template to execute (for example fuzz_0)"
         )]
         target: String,
-        #[arg(required = true, help = "Path to the crash found during fuzzing")]
+        #[arg(
+            required = true,
+            help = "Path to the crash found during fuzzing, or to a crash chain directory"
+        )]
         crash_file_path: String,
     },
 }
@@ -134,10 +215,17 @@ pub async fn fuzz(subcmd: FuzzCommand) {
             commander.run_hfuzz_debug(target, crash_file_path).await?;
         }
 
-        FuzzCommand::Add => {
-            let mut generator = TestGenerator::new_with_root(&root)?;
+        FuzzCommand::Add { idl, selection } => {
+            let mut generator = TestGenerator::new_with_root(&root)?
+                .with_idl_paths(_absolute_paths(idl)?)
+                .with_selection(selection.into());
             generator.add_fuzz_test().await?;
             show_howto();
         }
+        FuzzCommand::Refresh { target, selection } => {
+            let mut generator =
+                TestGenerator::new_with_root(&root)?.with_selection(selection.into());
+            generator.refresh_fuzz_test(target).await?;
+        }
     };
 }
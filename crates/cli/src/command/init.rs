@@ -1,25 +1,33 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use anyhow::{bail, Error};
 use fehler::throws;
 use trident_client::___private::TestGenerator;
 
-use crate::{_discover, show_howto};
+use crate::{_absolute_paths, _discover, show_howto};
 
 pub const ANCHOR_TOML: &str = "Anchor.toml";
+pub const IDL_DIRECTORY: &str = "idl";
 pub const TRIDENT_TOML: &str = "Trident.toml";
 pub const SKIP: &str = "\x1b[33mSkip\x1b[0m";
 
 #[throws]
-pub async fn init(force: bool) {
-    // look for Anchor.toml
+pub async fn init(force: bool, idl: Vec<PathBuf>) {
+    // look for Anchor.toml, or the idl directory with IDLs of non-Anchor programs
     let root = if let Some(r) = _discover(ANCHOR_TOML)? {
         r
+    } else if let Some(r) = _discover(IDL_DIRECTORY)? {
+        r
+    } else if !idl.is_empty() {
+        // with explicit IDLs, the current directory is the root of the project
+        std::env::current_dir()?.to_string_lossy().to_string()
     } else {
-        bail!("It does not seem that Anchor is initialized because the Anchor.toml file was not found in any parent directory!");
+        bail!("It does not seem that Anchor is initialized because the Anchor.toml file was not found in any parent directory!\n\
+        For non-Anchor programs, place their Shank or Codama IDLs in the idl directory in the root of the project.");
     };
 
-    let mut generator: TestGenerator = TestGenerator::new_with_root(&root)?;
+    let mut generator: TestGenerator =
+        TestGenerator::new_with_root(&root)?.with_idl_paths(_absolute_paths(idl)?);
 
     if force {
         generator.initialize().await?;
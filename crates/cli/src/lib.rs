@@ -2,6 +2,7 @@ use anyhow::Error;
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
 use fehler::throws;
+use std::path::PathBuf;
 
 // subcommand functions to call and nested subcommands
 mod command;
@@ -42,6 +43,13 @@ enum Command {
             help = "Force Trident initialization. Trident dependencies will be updated based on the version of Trident CLI."
         )]
         force: bool,
+        #[arg(
+            long,
+            required = false,
+            value_name = "IDL",
+            help = "Generate the fuzz test from the IDL file or directory with IDLs, without building the programs. Can be repeated."
+        )]
+        idl: Vec<PathBuf>,
     },
     #[command(
         about = "Run fuzz subcommands.",
@@ -67,11 +75,17 @@ pub async fn start() {
     match cli.command {
         Command::How => command::howto()?,
         Command::Fuzz { subcmd } => command::fuzz(subcmd).await?,
-        Command::Init { force } => command::init(force).await?,
+        Command::Init { force, idl } => command::init(force, idl).await?,
         Command::Clean => command::clean().await?,
     }
 }
 
+// Paths given on the command line are relative to the current directory, not to the root.
+fn _absolute_paths(paths: Vec<PathBuf>) -> Result<Vec<PathBuf>> {
+    let cwd = std::env::current_dir()?;
+    Ok(paths.into_iter().map(|path| cwd.join(path)).collect())
+}
+
 // Climbs each parent directory until we find target.
 fn _discover(target: &str) -> Result<Option<String>> {
     let _cwd = std::env::current_dir()?;
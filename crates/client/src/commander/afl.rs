@@ -6,9 +6,10 @@ use std::{fs::File, path::Path};
 use tokio::{io::AsyncWriteExt, process::Command};
 
 use trident_config::afl::AflSeed;
+use trident_config::constants::CRASH_CHAIN_ENV;
 use trident_config::TridentConfig;
 
-use super::{Commander, Error};
+use super::{get_dictionary, Commander, Error};
 use rand::RngCore;
 
 impl Commander {
@@ -20,7 +21,14 @@ impl Commander {
         // build args without cargo target dir
         let build_args = config.get_afl_build_args();
         // fuzz args without afl workspace in and out
-        let fuzz_args = config.get_afl_fuzz_args();
+        let mut fuzz_args = config.get_afl_fuzz_args();
+
+        // pass the generated dictionary unless a dictionary is already specified
+        if !fuzz_args.iter().any(|arg| arg == "-x") {
+            if let Some(dictionary) = get_dictionary(&self.root, &target) {
+                fuzz_args.extend(["-x".to_string(), dictionary.display().to_string()]);
+            }
+        }
 
         // cargo target directory
         let cargo_target_dir = config.get_afl_target_dir();
@@ -101,12 +109,19 @@ impl Commander {
 
         rustflags.push_str("--cfg afl");
 
-        let mut file = File::open(crash_file)?;
+        let mut command = Command::new("cargo");
+
+        // crash chains of the stateful mode are directories with inputs, replayed by the fuzz test
         let mut file_contents = Vec::new();
-        file.read_to_end(&mut file_contents)?;
+        if crash_file.is_dir() {
+            command.env(CRASH_CHAIN_ENV, crash_file);
+        } else {
+            let mut file = File::open(crash_file)?;
+            file.read_to_end(&mut file_contents)?;
+        }
 
         // using exec rather than spawn and replacing current process to avoid unflushed terminal output after ctrl+c signal
-        let mut child = Command::new("cargo")
+        let mut child = command
             .env("RUSTFLAGS", rustflags)
             .arg("afl")
             .arg("run")
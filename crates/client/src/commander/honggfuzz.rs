@@ -4,11 +4,14 @@ use std::process;
 use std::{os::unix::process::CommandExt, process::Stdio};
 use tokio::process::Command;
 
+use trident_config::constants::CRASH_CHAIN_ENV;
 use trident_config::TridentConfig;
 
 use crate::constants::*;
 
-use super::{get_crash_dir_and_ext, get_crash_files, Commander, Error};
+use super::{
+    get_cmd_option_value, get_crash_dir_and_ext, get_crash_files, get_dictionary, Commander, Error,
+};
 
 impl Commander {
     /// Runs fuzzer on the given target with exit code option.
@@ -26,6 +29,13 @@ impl Commander {
 
         let mut fuzz_args = config.get_honggfuzz_args(hfuzz_run_args);
 
+        // pass the generated dictionary unless a dictionary is already specified
+        if get_cmd_option_value(fuzz_args.split_whitespace(), "-w", "--dict").is_none() {
+            if let Some(dictionary) = get_dictionary(&self.root, &target) {
+                fuzz_args.push_str(&format!(" --dict {} ", dictionary.display()));
+            }
+        }
+
         let cargo_target_dir =
             std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| config.get_honggfuzz_target_dir());
 
@@ -97,6 +107,13 @@ impl Commander {
 
         let mut fuzz_args = config.get_honggfuzz_args(hfuzz_run_args);
 
+        // pass the generated dictionary unless a dictionary is already specified
+        if get_cmd_option_value(fuzz_args.split_whitespace(), "-w", "--dict").is_none() {
+            if let Some(dictionary) = get_dictionary(&self.root, &target) {
+                fuzz_args.push_str(&format!(" --dict {} ", dictionary.display()));
+            }
+        }
+
         let mut rustflags = std::env::var("RUSTFLAGS").unwrap_or_default();
 
         rustflags.push_str("--cfg honggfuzz");
@@ -158,8 +175,15 @@ impl Commander {
 
         rustflags.push_str("--cfg honggfuzz");
 
+        let mut command = std::process::Command::new("cargo");
+
+        // crash chains of the stateful mode are directories with inputs, replayed by the fuzz test
+        if crash_file.is_dir() {
+            command.env(CRASH_CHAIN_ENV, crash_file);
+        }
+
         // using exec rather than spawn and replacing current process to avoid unflushed terminal output after ctrl+c signal
-        std::process::Command::new("cargo")
+        command
             .env("CARGO_TARGET_DIR", cargo_target_dir)
             .env("RUSTFLAGS", rustflags)
             .arg("hfuzz")
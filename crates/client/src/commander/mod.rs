@@ -14,6 +14,8 @@ mod honggfuzz;
 use tokio::io::AsyncBufReadExt;
 use trident_fuzz::fuzz_stats::FuzzingStatistics;
 
+use crate::constants::*;
+
 #[derive(Error, Debug)]
 pub enum Error {
     #[error("{0:?}")]
@@ -217,6 +219,15 @@ impl Commander {
     }
 }
 
+// The dictionary generated together with the fuzz test, if it was not removed
+fn get_dictionary(root: &Path, target: &str) -> Option<PathBuf> {
+    let dictionary = root
+        .join(TESTS_WORKSPACE_DIRECTORY)
+        .join(target)
+        .join(FUZZ_DICTIONARY);
+    dictionary.exists().then_some(dictionary)
+}
+
 fn get_crash_dir_and_ext(
     root: &Path,
     target: &str,
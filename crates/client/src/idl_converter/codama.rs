@@ -0,0 +1,337 @@
+use serde_json::{json, Value};
+use trident_template::idl_origin::CODAMA_IDL_SPEC;
+
+use super::{
+    array, defined, number_to_le_bytes, pascal_case, snake_case, str_field, tuple_struct, Error,
+    TypeDefs,
+};
+
+// Codama IDLs describe the program as a tree of nodes, identified by their `kind`
+pub(super) fn convert(root: &Value) -> Result<Value, Error> {
+    let program = &root["program"];
+    let address = str_field(program, "publicKey")?;
+
+    let declared_names = array(&program["accounts"])
+        .iter()
+        .chain(array(&program["definedTypes"]))
+        .filter_map(|node| node["name"].as_str().map(pascal_case));
+    let mut type_defs = TypeDefs::new(declared_names);
+
+    let instructions = array(&program["instructions"])
+        .iter()
+        .map(|instruction| convert_instruction(instruction, address, &mut type_defs))
+        .collect::<Result<Vec<Value>, Error>>()?;
+
+    let mut accounts = Vec::new();
+    for account in array(&program["accounts"]) {
+        let name = pascal_case(str_field(account, "name")?);
+        let (fields, discriminator) = split_discriminator(
+            array(&account["data"]["fields"]),
+            &account["discriminators"],
+        )?;
+        let ty = convert_struct(&fields, &name, &mut type_defs)?;
+        type_defs.push(&name, ty);
+        accounts.push(json!({ "name": name, "discriminator": discriminator }));
+    }
+
+    for defined_type in array(&program["definedTypes"]) {
+        let name = pascal_case(str_field(defined_type, "name")?);
+        let ty = convert_type_def_ty(&defined_type["type"], &name, &mut type_defs)?;
+        type_defs.push(&name, ty);
+    }
+
+    let errors: Vec<Value> = array(&program["errors"])
+        .iter()
+        .map(|error| {
+            Ok(json!({
+                "code": error["code"],
+                "name": pascal_case(str_field(error, "name")?),
+                "msg": error["message"],
+            }))
+        })
+        .collect::<Result<_, Error>>()?;
+
+    Ok(json!({
+        "address": address,
+        "metadata": {
+            "name": snake_case(str_field(program, "name")?),
+            "version": program["version"].as_str().unwrap_or("0.1.0"),
+            "spec": CODAMA_IDL_SPEC,
+        },
+        "instructions": instructions,
+        "accounts": accounts,
+        "errors": errors,
+        "types": type_defs.into_types(),
+    }))
+}
+
+fn convert_instruction(
+    instruction: &Value,
+    program_address: &str,
+    type_defs: &mut TypeDefs,
+) -> Result<Value, Error> {
+    let name = str_field(instruction, "name")?;
+
+    let accounts: Vec<Value> = array(&instruction["accounts"])
+        .iter()
+        .map(|account| {
+            // accounts with a known address are constant accounts
+            let address = match account["defaultValue"]["kind"].as_str() {
+                Some("publicKeyValueNode") => account["defaultValue"]["publicKey"].as_str(),
+                Some("programIdValueNode") => Some(program_address),
+                _ => None,
+            };
+            Ok(json!({
+                "name": snake_case(str_field(account, "name")?),
+                "writable": account["isWritable"] == true,
+                "signer": account["isSigner"] == true,
+                "optional": account["isOptional"] == true,
+                "address": address,
+            }))
+        })
+        .collect::<Result<_, Error>>()?;
+
+    let (arguments, discriminator) = split_discriminator(
+        array(&instruction["arguments"]),
+        &instruction["discriminators"],
+    )?;
+    let args = convert_fields(&arguments, name, type_defs)?;
+
+    Ok(json!({
+        "name": snake_case(name),
+        "discriminator": discriminator,
+        "accounts": accounts,
+        "args": args,
+    }))
+}
+
+// Anchor IDLs keep the discriminator apart from the data, so the discriminator field
+// is removed from the fields, and its default value is used as the discriminator
+fn split_discriminator<'a>(
+    fields: &'a [Value],
+    discriminators: &Value,
+) -> Result<(Vec<&'a Value>, Vec<u8>), Error> {
+    let mut fields: Vec<&Value> = fields.iter().collect();
+
+    for discriminator in array(discriminators) {
+        if discriminator["offset"].as_u64().unwrap_or_default() != 0 {
+            continue;
+        }
+        match discriminator["kind"].as_str() {
+            Some("fieldDiscriminatorNode") => {
+                let position = fields.iter().position(|field| {
+                    field["name"] == discriminator["name"] && field.get("defaultValue").is_some()
+                });
+                if let Some(position) = position {
+                    let field = fields.remove(position);
+                    let bytes = value_to_bytes(&field["type"], &field["defaultValue"])?;
+                    return Ok((fields, bytes));
+                }
+            }
+            Some("constantDiscriminatorNode") => {
+                let constant = &discriminator["constant"];
+                let bytes = value_to_bytes(&constant["type"], &constant["value"])?;
+                return Ok((fields, bytes));
+            }
+            _ => {}
+        }
+    }
+
+    Ok((fields, Vec::new()))
+}
+
+fn value_to_bytes(ty: &Value, value: &Value) -> Result<Vec<u8>, Error> {
+    match value["kind"].as_str() {
+        Some("numberValueNode") => number_to_le_bytes(str_field(ty, "format")?, &value["number"]),
+        Some("bytesValueNode") => {
+            let data = str_field(value, "data")?;
+            match value["encoding"].as_str() {
+                Some("base16") => (0..data.len())
+                    .step_by(2)
+                    .map(|i| {
+                        data.get(i..i + 2)
+                            .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                            .ok_or_else(|| Error::InvalidValue(format!("discriminator {data}")))
+                    })
+                    .collect(),
+                Some("utf8") => Ok(data.as_bytes().to_vec()),
+                encoding => Err(Error::UnsupportedType(format!(
+                    "discriminator encoding {}",
+                    encoding.unwrap_or_default()
+                ))),
+            }
+        }
+        _ => Err(Error::UnsupportedType(format!(
+            "discriminator value {value}"
+        ))),
+    }
+}
+
+fn convert_type_def_ty(ty: &Value, name: &str, type_defs: &mut TypeDefs) -> Result<Value, Error> {
+    match ty["kind"].as_str() {
+        Some("structTypeNode") => {
+            let fields: Vec<&Value> = array(&ty["fields"]).iter().collect();
+            convert_struct(&fields, name, type_defs)
+        }
+        Some("enumTypeNode") => {
+            check_number_prefix(&ty["size"], name)?;
+            let variants = array(&ty["variants"])
+                .iter()
+                .map(|variant| {
+                    let variant_name = pascal_case(str_field(variant, "name")?);
+                    let name_hint = format!("{name}{variant_name}");
+                    match variant["kind"].as_str() {
+                        Some("enumStructVariantTypeNode") => {
+                            let fields: Vec<&Value> =
+                                array(&variant["struct"]["fields"]).iter().collect();
+                            let fields = convert_fields(&fields, &name_hint, type_defs)?;
+                            Ok(json!({ "name": variant_name, "fields": fields }))
+                        }
+                        Some("enumTupleVariantTypeNode") => {
+                            let items = convert_items(&variant["tuple"], &name_hint, type_defs)?;
+                            Ok(json!({ "name": variant_name, "fields": items }))
+                        }
+                        _ => Ok(json!({ "name": variant_name })),
+                    }
+                })
+                .collect::<Result<Vec<Value>, Error>>()?;
+            Ok(json!({ "kind": "enum", "variants": variants }))
+        }
+        Some("tupleTypeNode") => {
+            let items = convert_items(ty, name, type_defs)?;
+            Ok(tuple_struct(items))
+        }
+        _ => {
+            let alias = convert_type(ty, name, type_defs)?;
+            Ok(json!({ "kind": "type", "alias": alias }))
+        }
+    }
+}
+
+fn convert_struct(fields: &[&Value], name: &str, type_defs: &mut TypeDefs) -> Result<Value, Error> {
+    let fields = convert_fields(fields, name, type_defs)?;
+    Ok(json!({ "kind": "struct", "fields": fields }))
+}
+
+fn convert_fields(
+    fields: &[&Value],
+    name_hint: &str,
+    type_defs: &mut TypeDefs,
+) -> Result<Vec<Value>, Error> {
+    fields
+        .iter()
+        .map(|field| {
+            let name = str_field(field, "name")?;
+            let ty = convert_type(&field["type"], &format!("{name_hint}_{name}"), type_defs)?;
+            Ok(json!({ "name": snake_case(name), "type": ty }))
+        })
+        .collect()
+}
+
+fn convert_items(
+    tuple: &Value,
+    name_hint: &str,
+    type_defs: &mut TypeDefs,
+) -> Result<Vec<Value>, Error> {
+    array(&tuple["items"])
+        .iter()
+        .map(|item| convert_type(item, name_hint, type_defs))
+        .collect()
+}
+
+fn convert_type(ty: &Value, name_hint: &str, type_defs: &mut TypeDefs) -> Result<Value, Error> {
+    let unsupported = || {
+        Error::UnsupportedType(format!(
+            "{} of {name_hint}",
+            ty["kind"].as_str().unwrap_or("type")
+        ))
+    };
+
+    match ty["kind"].as_str() {
+        Some("numberTypeNode") => {
+            let format = str_field(ty, "format")?;
+            if ty["endian"] == "be" || format == "shortU16" {
+                return Err(unsupported());
+            }
+            Ok(json!(format))
+        }
+        Some("amountTypeNode" | "dateTimeTypeNode" | "solAmountTypeNode") => {
+            convert_type(&ty["number"], name_hint, type_defs)
+        }
+        Some("booleanTypeNode") => Ok(json!("bool")),
+        Some("publicKeyTypeNode") => Ok(json!("pubkey")),
+        Some("sizePrefixTypeNode") => {
+            check_number_prefix_format(&ty["prefix"], "u32", name_hint)?;
+            match ty["type"]["kind"].as_str() {
+                Some("stringTypeNode") => Ok(json!("string")),
+                Some("bytesTypeNode") => Ok(json!("bytes")),
+                _ => Err(unsupported()),
+            }
+        }
+        Some("fixedSizeTypeNode") => match ty["type"]["kind"].as_str() {
+            Some("stringTypeNode" | "bytesTypeNode") => Ok(json!({ "array": ["u8", ty["size"]] })),
+            _ => Err(unsupported()),
+        },
+        Some("arrayTypeNode" | "setTypeNode") => {
+            let item = convert_type(&ty["item"], name_hint, type_defs)?;
+            convert_count(&ty["count"], item, name_hint)
+        }
+        // maps are serialized as a vector of key-value pairs
+        Some("mapTypeNode") => {
+            let key = convert_type(&ty["key"], name_hint, type_defs)?;
+            let value = convert_type(&ty["value"], name_hint, type_defs)?;
+            let entry = type_defs.synthesize(
+                &format!("{name_hint}_entry"),
+                tuple_struct(vec![key, value]),
+            );
+            convert_count(&ty["count"], entry, name_hint)
+        }
+        Some("optionTypeNode") => {
+            if ty["fixed"] == true {
+                return Err(unsupported());
+            }
+            check_number_prefix(&ty["prefix"], name_hint)?;
+            let item = convert_type(&ty["item"], name_hint, type_defs)?;
+            Ok(json!({ "option": item }))
+        }
+        Some("definedTypeLinkNode") => Ok(defined(&pascal_case(str_field(ty, "name")?))),
+        Some("structTypeNode" | "enumTypeNode" | "tupleTypeNode") => {
+            let type_def_ty = convert_type_def_ty(ty, name_hint, type_defs)?;
+            Ok(type_defs.synthesize(name_hint, type_def_ty))
+        }
+        // other nodes (zeroable options, hidden prefixes, offsets, ...) are not Borsh compatible
+        _ => Err(unsupported()),
+    }
+}
+
+fn convert_count(count: &Value, item: Value, name_hint: &str) -> Result<Value, Error> {
+    match count["kind"].as_str() {
+        Some("fixedCountNode") => Ok(json!({ "array": [item, count["value"]] })),
+        Some("prefixedCountNode") => {
+            check_number_prefix_format(&count["prefix"], "u32", name_hint)?;
+            Ok(json!({ "vec": item }))
+        }
+        _ => Err(Error::UnsupportedType(format!(
+            "remainder count of {name_hint}"
+        ))),
+    }
+}
+
+// Borsh prefixes options and enums with a single byte
+fn check_number_prefix(prefix: &Value, name_hint: &str) -> Result<(), Error> {
+    if prefix.is_null() {
+        return Ok(());
+    }
+    check_number_prefix_format(prefix, "u8", name_hint)
+}
+
+fn check_number_prefix_format(prefix: &Value, format: &str, name_hint: &str) -> Result<(), Error> {
+    if prefix["format"] == format && prefix["endian"] != "be" {
+        Ok(())
+    } else {
+        Err(Error::UnsupportedType(format!(
+            "{} prefix of {name_hint}",
+            prefix["format"].as_str().unwrap_or("unknown")
+        )))
+    }
+}
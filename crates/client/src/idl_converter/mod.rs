@@ -0,0 +1,142 @@
+//! Converts IDLs of non-Anchor programs into the Anchor IDL format, so the
+//! fuzz test generators work with Anchor, Shank and Codama IDLs alike.
+
+use std::collections::HashSet;
+
+use convert_case::{Case, Casing};
+use serde_json::{json, Value};
+use thiserror::Error;
+use trident_idl_spec::Idl;
+
+mod codama;
+mod shank;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("{0}")]
+    Json(#[from] serde_json::Error),
+    #[error("the field `{0}` is missing in the IDL")]
+    MissingField(String),
+    #[error("invalid {0} in the IDL")]
+    InvalidValue(String),
+    #[error("{0} is not supported, its serialization does not match Borsh")]
+    UnsupportedType(String),
+}
+
+enum IdlFormat {
+    Anchor,
+    Shank,
+    Codama,
+}
+
+impl IdlFormat {
+    fn detect(idl: &Value) -> Self {
+        if idl["standard"] == "codama" || idl["kind"] == "rootNode" {
+            IdlFormat::Codama
+        } else if idl["metadata"]["origin"] == "shank" {
+            IdlFormat::Shank
+        } else {
+            IdlFormat::Anchor
+        }
+    }
+}
+
+/// Parses an Anchor, Shank or Codama IDL, the format is detected from the content
+pub fn parse_idl(json_content: &str) -> Result<Idl, Error> {
+    let idl: Value = serde_json::from_str(json_content)?;
+
+    let anchor_idl = match IdlFormat::detect(&idl) {
+        IdlFormat::Anchor => idl,
+        IdlFormat::Shank => shank::convert(&idl)?,
+        IdlFormat::Codama => codama::convert(&idl)?,
+    };
+
+    Ok(serde_json::from_value(anchor_idl)?)
+}
+
+// Type definitions of the converted IDL. Tuples, maps and inline structs cannot be
+// expressed inline in the Anchor IDL, so they are added as new defined types.
+struct TypeDefs {
+    names: HashSet<String>,
+    types: Vec<Value>,
+}
+
+impl TypeDefs {
+    fn new(declared_names: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            names: declared_names.into_iter().collect(),
+            types: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, name: &str, ty: Value) {
+        self.names.insert(name.to_string());
+        self.types.push(json!({ "name": name, "type": ty }));
+    }
+
+    // Adds the type under a unique name derived from where it is used
+    fn synthesize(&mut self, name_hint: &str, ty: Value) -> Value {
+        let base_name = pascal_case(name_hint);
+        let mut name = base_name.clone();
+        let mut suffix = 1;
+        while self.names.contains(&name) {
+            name = format!("{base_name}{suffix}");
+            suffix += 1;
+        }
+        self.push(&name, ty);
+        defined(&name)
+    }
+
+    fn into_types(self) -> Vec<Value> {
+        self.types
+    }
+}
+
+fn defined(name: &str) -> Value {
+    json!({ "defined": { "name": name } })
+}
+
+fn tuple_struct(types: Vec<Value>) -> Value {
+    json!({ "kind": "struct", "fields": types })
+}
+
+fn array(value: &Value) -> &[Value] {
+    value.as_array().map(Vec::as_slice).unwrap_or_default()
+}
+
+fn str_field<'a>(value: &'a Value, field: &str) -> Result<&'a str, Error> {
+    value[field]
+        .as_str()
+        .ok_or_else(|| Error::MissingField(field.to_string()))
+}
+
+fn snake_case(name: &str) -> String {
+    name.to_case(Case::Snake)
+}
+
+fn pascal_case(name: &str) -> String {
+    name.to_case(Case::Pascal)
+}
+
+// Little endian bytes of the number, as serialized by Borsh
+fn number_to_le_bytes(format: &str, number: &Value) -> Result<Vec<u8>, Error> {
+    let size = match format {
+        "u8" | "i8" => 1,
+        "u16" | "i16" => 2,
+        "u32" | "i32" => 4,
+        "u64" | "i64" => 8,
+        "u128" | "i128" => 16,
+        _ => {
+            return Err(Error::UnsupportedType(format!(
+                "discriminator of type {format}"
+            )))
+        }
+    };
+    let value = number
+        .as_i64()
+        .map(i128::from)
+        .or_else(|| number.as_u64().map(i128::from))
+        .ok_or_else(|| Error::InvalidValue(format!("discriminator {number}")))?;
+
+    Ok(value.to_le_bytes()[..size].to_vec())
+}
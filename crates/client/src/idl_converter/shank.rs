@@ -0,0 +1,193 @@
+use serde_json::{json, Value};
+use trident_template::idl_origin::SHANK_IDL_SPEC;
+
+use super::{
+    array, defined, number_to_le_bytes, snake_case, str_field, tuple_struct, Error, TypeDefs,
+};
+
+// Shank IDLs follow the legacy Anchor IDL format, with the program address in the metadata
+// and explicit instruction discriminants
+pub(super) fn convert(idl: &Value) -> Result<Value, Error> {
+    let declared_names = array(&idl["accounts"])
+        .iter()
+        .chain(array(&idl["types"]))
+        .filter_map(|type_def| type_def["name"].as_str().map(String::from));
+    let mut type_defs = TypeDefs::new(declared_names);
+
+    let instructions = array(&idl["instructions"])
+        .iter()
+        .map(|instruction| convert_instruction(instruction, &mut type_defs))
+        .collect::<Result<Vec<Value>, Error>>()?;
+
+    // Shank accounts are not prefixed with a discriminator
+    let mut accounts = Vec::new();
+    for account in array(&idl["accounts"]) {
+        let name = str_field(account, "name")?;
+        let ty = convert_type_def_ty(&account["type"], name, &mut type_defs)?;
+        type_defs.push(name, ty);
+        accounts.push(json!({ "name": name, "discriminator": [] }));
+    }
+
+    for type_def in array(&idl["types"]) {
+        let name = str_field(type_def, "name")?;
+        let ty = convert_type_def_ty(&type_def["type"], name, &mut type_defs)?;
+        type_defs.push(name, ty);
+    }
+
+    let errors: Vec<Value> = array(&idl["errors"])
+        .iter()
+        .map(|error| json!({ "code": error["code"], "name": error["name"], "msg": error["msg"] }))
+        .collect();
+
+    Ok(json!({
+        "address": idl["metadata"]["address"].as_str().unwrap_or_default(),
+        "metadata": {
+            "name": snake_case(str_field(idl, "name")?),
+            "version": idl["version"].as_str().unwrap_or("0.1.0"),
+            "spec": SHANK_IDL_SPEC,
+        },
+        "instructions": instructions,
+        "accounts": accounts,
+        "errors": errors,
+        "types": type_defs.into_types(),
+    }))
+}
+
+fn convert_instruction(instruction: &Value, type_defs: &mut TypeDefs) -> Result<Value, Error> {
+    let name = str_field(instruction, "name")?;
+
+    let discriminator = match instruction.get("discriminant") {
+        Some(discriminant) => {
+            number_to_le_bytes(str_field(discriminant, "type")?, &discriminant["value"])?
+        }
+        None => Vec::new(),
+    };
+
+    let accounts: Vec<Value> = array(&instruction["accounts"])
+        .iter()
+        .map(|account| {
+            Ok(json!({
+                "name": snake_case(str_field(account, "name")?),
+                "writable": account["isMut"].as_bool().unwrap_or_default(),
+                "signer": account["isSigner"].as_bool().unwrap_or_default(),
+                "optional": account["isOptional"].as_bool().unwrap_or_default(),
+            }))
+        })
+        .collect::<Result<_, Error>>()?;
+
+    let args = convert_fields(array(&instruction["args"]), name, type_defs)?;
+
+    Ok(json!({
+        "name": snake_case(name),
+        "discriminator": discriminator,
+        "accounts": accounts,
+        "args": args,
+    }))
+}
+
+fn convert_type_def_ty(ty: &Value, name: &str, type_defs: &mut TypeDefs) -> Result<Value, Error> {
+    match ty["kind"].as_str() {
+        Some("struct") => {
+            let fields = convert_defined_fields(array(&ty["fields"]), name, type_defs)?;
+            Ok(json!({ "kind": "struct", "fields": fields }))
+        }
+        Some("enum") => {
+            let variants = array(&ty["variants"])
+                .iter()
+                .map(|variant| {
+                    let variant_name = str_field(variant, "name")?;
+                    match variant.get("fields") {
+                        Some(fields) => {
+                            let fields = convert_defined_fields(
+                                array(fields),
+                                &format!("{name}{variant_name}"),
+                                type_defs,
+                            )?;
+                            Ok(json!({ "name": variant_name, "fields": fields }))
+                        }
+                        None => Ok(json!({ "name": variant_name })),
+                    }
+                })
+                .collect::<Result<Vec<Value>, Error>>()?;
+            Ok(json!({ "kind": "enum", "variants": variants }))
+        }
+        _ => Err(Error::UnsupportedType(format!("type definition {name}"))),
+    }
+}
+
+// Fields are either named, or a list of types for tuple structs and variants
+fn convert_defined_fields(
+    fields: &[Value],
+    name_hint: &str,
+    type_defs: &mut TypeDefs,
+) -> Result<Vec<Value>, Error> {
+    let named = fields
+        .iter()
+        .all(|field| field.get("name").is_some() && field.get("type").is_some());
+
+    if named {
+        convert_fields(fields, name_hint, type_defs)
+    } else {
+        fields
+            .iter()
+            .map(|ty| convert_type(ty, name_hint, type_defs))
+            .collect()
+    }
+}
+
+fn convert_fields(
+    fields: &[Value],
+    name_hint: &str,
+    type_defs: &mut TypeDefs,
+) -> Result<Vec<Value>, Error> {
+    fields
+        .iter()
+        .map(|field| {
+            let name = str_field(field, "name")?;
+            let ty = convert_type(&field["type"], &format!("{name_hint}_{name}"), type_defs)?;
+            Ok(json!({ "name": snake_case(name), "type": ty }))
+        })
+        .collect()
+}
+
+fn convert_type(ty: &Value, name_hint: &str, type_defs: &mut TypeDefs) -> Result<Value, Error> {
+    if let Some(primitive) = ty.as_str() {
+        return match primitive {
+            "publicKey" => Ok(json!("pubkey")),
+            _ => Ok(json!(primitive)),
+        };
+    }
+
+    let unsupported = || Error::UnsupportedType(format!("type {ty} of {name_hint}"));
+    let (kind, inner) = ty
+        .as_object()
+        .and_then(|ty| ty.iter().next())
+        .ok_or_else(unsupported)?;
+
+    match kind.as_str() {
+        "defined" => match inner.as_str() {
+            Some(name) => Ok(defined(name)),
+            None => Ok(ty.clone()),
+        },
+        "vec" | "hashSet" | "bTreeSet" => {
+            Ok(json!({ "vec": convert_type(inner, name_hint, type_defs)? }))
+        }
+        "option" => Ok(json!({ "option": convert_type(inner, name_hint, type_defs)? })),
+        "array" => {
+            let item = convert_type(&inner[0], name_hint, type_defs)?;
+            Ok(json!({ "array": [item, inner[1]] }))
+        }
+        "tuple" => {
+            let types = convert_defined_fields(array(inner), name_hint, type_defs)?;
+            Ok(type_defs.synthesize(name_hint, tuple_struct(types)))
+        }
+        // maps are serialized as a vector of key-value pairs
+        "hashMap" | "bTreeMap" => {
+            let types = convert_defined_fields(array(inner), name_hint, type_defs)?;
+            let entry = type_defs.synthesize(&format!("{name_hint}_entry"), tuple_struct(types));
+            Ok(json!({ "vec": entry }))
+        }
+        // COption is prefixed with 4 bytes instead of the 1 byte of Option
+        _ => Err(unsupported()),
+    }
+}
@@ -1,11 +1,14 @@
-use std::error::Error;
-use std::fs::{self, File};
-use std::io::Read;
-use std::path::PathBuf;
+use fehler::throws;
+use std::fs;
+use std::path::{Path, PathBuf};
 
 use trident_idl_spec::Idl;
 
-pub fn load_idls(dir_path: PathBuf) -> Result<Vec<Idl>, Box<dyn Error>> {
+use crate::idl_converter::parse_idl;
+use crate::test_generator::Error;
+
+#[throws]
+pub fn load_idls(dir_path: PathBuf) -> Vec<Idl> {
     let mut idls = Vec::new();
 
     // Read the directory and iterate over each entry
@@ -13,32 +16,20 @@ pub fn load_idls(dir_path: PathBuf) -> Result<Vec<Idl>, Box<dyn Error>> {
         let entry = entry?;
         let path = entry.path();
 
-        // Only process .json files
+        // Only process .json files, an IDL that cannot be parsed fails the whole load
         if path.is_file() && path.extension().and_then(|ext| ext.to_str()) == Some("json") {
-            // Remove the .json extension to get the package name
-            // let package_name = idl_name_str.trim_end_matches(".json");
-
-            // Check if the package name is in the list of known packages
-            // if package_names.iter().any(|name| name == package_name) {
-            // Open the file in read-only mode
-            let mut file = File::open(&path)?;
-
-            // Read the file contents into a string
-            let mut json_content = String::new();
-            file.read_to_string(&mut json_content)?;
-
-            // Parse the string of data into an Idl struct
-            match serde_json::from_str::<Idl>(&json_content) {
-                Ok(parsed_idl) => {
-                    idls.push(parsed_idl);
-                }
-                Err(e) => {
-                    eprintln!("Failed to parse {}: {}", path.display(), e);
-                    // Continue to the next file on failure
-                }
-            }
+            idls.push(load_idl(&path)?);
         }
     }
 
-    Ok(idls)
+    idls
+}
+
+#[throws]
+pub fn load_idl(idl_path: &Path) -> Idl {
+    let json_content = fs::read_to_string(idl_path)?;
+
+    // Parse the string of data into an Idl struct, Shank and Codama IDLs are converted
+    parse_idl(&json_content)
+        .map_err(|e| Error::CannotParseIdl(idl_path.display().to_string(), e.to_string()))?
 }
@@ -5,6 +5,7 @@
 
 mod cleaner;
 mod commander;
+mod idl_converter;
 mod idl_loader;
 // mod source_code_generators;
 mod test_generator;
@@ -15,9 +16,10 @@ pub mod ___private {
     pub use super::cleaner::*;
     pub use super::commander::Commander;
     pub use super::commander::Error;
+    pub use super::idl_converter::parse_idl;
     pub use super::idl_loader::*;
     // pub use super::source_code_generators::*;
-    pub use super::test_generator::TestGenerator;
+    pub use super::test_generator::{FuzzTestSelection, TestGenerator};
 }
 
 mod constants {
@@ -26,12 +28,19 @@ mod constants {
     pub const TRIDENT_TOML: &str = "Trident.toml";
     pub const ANCHOR_TOML: &str = "Anchor.toml";
 
+    // idls
+    pub const ANCHOR_IDL_DIRECTORY: &str = "target/idl";
+    pub const IDL_DIRECTORY: &str = "idl";
+
     // tests
     pub const TESTS_WORKSPACE_DIRECTORY: &str = "trident-tests";
 
     // fuzz
     pub const FUZZ_INSTRUCTIONS_FILE_NAME: &str = "fuzz_instructions.rs";
     pub const FUZZ_TEST: &str = "test_fuzz.rs";
+    pub const FUZZ_DICTIONARY: &str = "fuzz.dict";
+    pub const FUZZ_INSTRUCTIONS_CONFLICTS: &str = "fuzz_instructions.diff";
+    pub const FUZZ_SELECTION: &str = "fuzz_selection.toml";
 
     // honggfuzz
     pub const CARGO_TARGET_DIR_DEFAULT_HFUZZ: &str = "trident-tests/fuzzing/honggfuzz/hfuzz_target";
@@ -46,4 +55,5 @@ mod constants {
     pub const SKIP: &str = "\x1b[33mSkip\x1b[0m";
     pub const FINISH: &str = "\x1b[92mFinished\x1b[0m";
     pub const ERROR: &str = "\x1b[31mError\x1b[0m";
+    pub const CONFLICT: &str = "\x1b[33mConflict\x1b[0m";
 }
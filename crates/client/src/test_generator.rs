@@ -3,7 +3,8 @@ use crate::constants::*;
 use crate::versions_config::TridentVersionsConfig;
 use crate::{construct_path, load_template, utils::*};
 use cargo_metadata::Package;
-use fehler::throws;
+use fehler::{throw, throws};
+use serde::{Deserialize, Serialize};
 use std::num::ParseIntError;
 use std::path::StripPrefixError;
 use std::{
@@ -11,8 +12,11 @@ use std::{
     path::{Path, PathBuf},
 };
 use thiserror::Error;
+use tokio::fs;
 use trident_idl_spec::Idl;
+use trident_template::dictionary_generator;
 use trident_template::fuzz_instructions_generator;
+use trident_template::fuzz_instructions_refresh;
 use trident_template::test_fuzz_generator;
 
 #[derive(Error, Debug)]
@@ -30,19 +34,59 @@ pub enum Error {
     #[error("{0:?}")]
     Toml(#[from] toml::de::Error),
     #[error("{0:?}")]
+    TomlSerialize(#[from] toml::ser::Error),
+    #[error("{0:?}")]
     Commander(#[from] CommanderError),
-    #[error("The Anchor project does not contain any programs")]
+    #[error("The Solana project does not contain any programs")]
     NoProgramsFound,
     #[error("parsing Cargo.toml dependencies failed")]
     ParsingCargoTomlDependenciesFailed,
+    #[error("the fuzz test {0} does not exist")]
+    FuzzTestNotFound(String),
+    #[error("cannot parse fuzz_instructions.rs: {0}")]
+    CannotParseFuzzInstructions(String),
+    #[error("the IDL {0} does not exist")]
+    IdlNotFound(String),
+    #[error("cannot parse the IDL {0}: {1}")]
+    CannotParseIdl(String, String),
+    #[error("the program {0} was not found in the IDLs")]
+    ProgramNotFound(String),
+    #[error("the instruction {0} was not found in the selected programs")]
+    InstructionNotFound(String),
+    #[error("no instructions are selected for the fuzz test")]
+    NoInstructionsSelected,
+    #[error("cannot find the program ID of {0}, specify the address in the IDL or the program in the [programs.localnet] section of Anchor.toml")]
+    ProgramIdNotFound(String),
+}
+
+/// Programs and instructions the fuzz test is generated for, all are selected by default.
+/// The selection is stored in the fuzz test directory and reused by `trident fuzz refresh`.
+#[derive(Default, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FuzzTestSelection {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub programs: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+}
+
+impl FuzzTestSelection {
+    pub fn is_empty(&self) -> bool {
+        self.programs.is_empty() && self.include.is_empty() && self.exclude.is_empty()
+    }
 }
 
 pub struct TestGenerator {
     pub root: PathBuf,
     pub program_packages: Vec<Package>,
     pub anchor_idls: Vec<Idl>,
+    pub idl_paths: Vec<PathBuf>,
+    pub selection: FuzzTestSelection,
     pub test_fuzz: String,
     pub fuzz_instructions: String,
+    pub fuzz_dictionary: String,
     pub versions_config: TridentVersionsConfig,
 }
 impl TestGenerator {
@@ -55,14 +99,30 @@ impl TestGenerator {
             root: Path::new(&root).to_path_buf(),
             program_packages: Vec::default(),
             anchor_idls: Vec::default(),
+            idl_paths: Vec::default(),
+            selection: FuzzTestSelection::default(),
             fuzz_instructions: String::default(),
             test_fuzz: String::default(),
+            fuzz_dictionary: String::default(),
             versions_config,
         }
     }
+    /// Generates the fuzz test from the given IDL files or directories with IDLs,
+    /// instead of building the programs and reading their IDLs
+    pub fn with_idl_paths(mut self, idl_paths: Vec<PathBuf>) -> Self {
+        self.idl_paths = idl_paths;
+        self
+    }
+
+    /// Generates only the selected programs and instructions into `FuzzInstruction`
+    pub fn with_selection(mut self, selection: FuzzTestSelection) -> Self {
+        self.selection = selection;
+        self
+    }
+
     #[throws]
     pub async fn initialize(&mut self) {
-        Commander::build_anchor_project().await?;
+        self.build_programs().await?;
 
         self.get_program_packages().await?;
         self.load_programs_idl()?;
@@ -75,7 +135,7 @@ impl TestGenerator {
 
     #[throws]
     pub async fn add_fuzz_test(&mut self) {
-        Commander::build_anchor_project().await?;
+        self.build_programs().await?;
 
         self.get_program_packages().await?;
         self.load_programs_idl()?;
@@ -85,45 +145,104 @@ impl TestGenerator {
         // update_package_metadata(&self.program_packages, &self.versions_config).await?;
     }
 
+    #[throws]
+    pub async fn refresh_fuzz_test(&mut self, target: String) {
+        let fuzz_test_dir = construct_path!(self.root, TESTS_WORKSPACE_DIRECTORY, &target);
+        if !fuzz_test_dir.exists() {
+            throw!(Error::FuzzTestNotFound(target));
+        }
+
+        // the selection the fuzz test was added with is reused, unless a new one is specified
+        let selection_path = fuzz_test_dir.join(FUZZ_SELECTION);
+        if self.selection.is_empty() && selection_path.exists() {
+            self.selection = toml::from_str(&fs::read_to_string(&selection_path).await?)?;
+        }
+
+        self.build_programs().await?;
+
+        self.get_program_packages().await?;
+        self.load_programs_idl()?;
+        self.generate_source_codes().await?;
+        self.refresh_existing_fuzz_test(&fuzz_test_dir).await?;
+    }
+
+    // Only Anchor programs need to be built to obtain their IDLs,
+    // IDLs of other programs are expected in the idl directory
+    #[throws]
+    async fn build_programs(&self) {
+        // explicitly provided IDLs are used as they are
+        if self.idl_paths.is_empty() && construct_path!(self.root, ANCHOR_TOML).exists() {
+            Commander::build_anchor_project().await?;
+        }
+    }
+
+    // Programs of explicitly provided IDLs are not looked up in the workspace,
+    // as the IDLs might come from outside of it
     #[throws]
     async fn get_program_packages(&mut self) {
-        // TODO consider optionally excluding packages
-        self.program_packages = collect_program_packages().await?;
+        if self.idl_paths.is_empty() {
+            self.program_packages = collect_program_packages().await?;
+        }
     }
 
     #[throws]
     async fn generate_source_codes(&mut self) {
         // Obtain lib names so we can generate entries in the test_fuzz.rs file
-        let lib_names = self
-            .program_packages
-            .iter()
-            .map(|p| {
-                // This is little dirty
-                // We check if there is any target, if so we check only the first one and check if it is lib
-                // if so we take its name.
-                // Otherwise we take the package name.
-                if !p.targets.is_empty() && p.targets[0].kind.iter().any(|k| k == "lib") {
-                    p.targets[0].name.clone()
-                } else {
-                    p.name.clone()
-                }
-            })
-            .collect::<Vec<String>>();
-
-        let test_fuzz = test_fuzz_generator::generate_source_code(&self.anchor_idls, &lib_names);
-        let fuzz_instructions =
-            fuzz_instructions_generator::generate_source_code(&self.anchor_idls);
+        let lib_names = match self.idl_paths.is_empty() {
+            true => self
+                .program_packages
+                .iter()
+                .map(|p| {
+                    // This is little dirty
+                    // We check if there is any target, if so we check only the first one and check if it is lib
+                    // if so we take its name.
+                    // Otherwise we take the package name.
+                    if !p.targets.is_empty() && p.targets[0].kind.iter().any(|k| k == "lib") {
+                        p.targets[0].name.clone()
+                    } else {
+                        p.name.clone()
+                    }
+                })
+                .collect::<Vec<String>>(),
+            // lib names of programs with explicitly provided IDLs are derived from the IDLs
+            false => self
+                .anchor_idls
+                .iter()
+                .map(|idl| idl.metadata.name.replace('-', "_"))
+                .collect::<Vec<String>>(),
+        };
+
+        let programs = resolve_program_ids(&self.root, &self.anchor_idls, &lib_names).await?;
+
+        // all programs are kept in test_fuzz.rs, so that the selected instructions can CPI into them
+        let selected_idls = select_instructions(&self.anchor_idls, &self.selection)?;
+
+        let test_fuzz = test_fuzz_generator::generate_source_code(&self.anchor_idls, &programs);
+        let fuzz_instructions = fuzz_instructions_generator::generate_source_code(&selected_idls);
 
         self.test_fuzz = Commander::format_program_code_nightly(&test_fuzz).await?;
         self.fuzz_instructions = Commander::format_program_code_nightly(&fuzz_instructions).await?;
+        self.fuzz_dictionary = dictionary_generator::generate_dictionary(&selected_idls);
     }
 
     #[throws]
     fn load_programs_idl(&mut self) {
-        let target_path = construct_path!(self.root, "target/idl/");
+        if !self.idl_paths.is_empty() {
+            self.anchor_idls = load_idl_paths(&self.idl_paths)?;
+            if self.anchor_idls.is_empty() {
+                throw!(Error::NoProgramsFound);
+            }
+            return;
+        }
 
-        // TODO consider optionally excluding packages
-        self.anchor_idls = crate::idl_loader::load_idls(target_path).unwrap();
+        self.anchor_idls = Vec::new();
+        for idl_directory in [ANCHOR_IDL_DIRECTORY, IDL_DIRECTORY] {
+            let idl_path = construct_path!(self.root, idl_directory);
+            if idl_path.is_dir() {
+                self.anchor_idls
+                    .extend(crate::idl_loader::load_idls(idl_path)?);
+            }
+        }
     }
 
     #[throws]
@@ -142,12 +261,15 @@ impl TestGenerator {
 
         let fuzz_test_path = new_fuzz_test_dir.join(FUZZ_TEST);
         let fuzz_instructions_path = new_fuzz_test_dir.join(FUZZ_INSTRUCTIONS_FILE_NAME);
+        let fuzz_dictionary_path = new_fuzz_test_dir.join(FUZZ_DICTIONARY);
 
         let cargo_toml_content = load_template!("/src/template/Cargo_fuzz.toml.tmpl");
 
         create_file(&self.root, &fuzz_test_path, &self.test_fuzz).await?;
         create_file(&self.root, &fuzz_instructions_path, &self.fuzz_instructions).await?;
+        create_file(&self.root, &fuzz_dictionary_path, &self.fuzz_dictionary).await?;
         create_file(&self.root, &fuzz_tests_manifest_path, cargo_toml_content).await?;
+        self.store_selection(&new_fuzz_test_dir).await?;
 
         add_bin_target(&fuzz_tests_manifest_path, &new_fuzz_test, &new_bin_target).await?;
 
@@ -160,6 +282,81 @@ impl TestGenerator {
 
         // add_workspace_member(&self.root, &format!("{TESTS_WORKSPACE_DIRECTORY}",)).await?;
     }
+    #[throws]
+    pub async fn refresh_existing_fuzz_test(&self, fuzz_test_dir: &Path) {
+        let fuzz_instructions_path = fuzz_test_dir.join(FUZZ_INSTRUCTIONS_FILE_NAME);
+        let fuzz_dictionary_path = fuzz_test_dir.join(FUZZ_DICTIONARY);
+        let conflicts_path = fuzz_test_dir.join(FUZZ_INSTRUCTIONS_CONFLICTS);
+
+        let current_fuzz_instructions = fs::read_to_string(&fuzz_instructions_path).await?;
+
+        // derived parts are regenerated while the user code is kept
+        let refreshed = fuzz_instructions_refresh::refresh_source_code(
+            &current_fuzz_instructions,
+            &self.fuzz_instructions,
+        )
+        .map_err(|e| Error::CannotParseFuzzInstructions(e.to_string()))?;
+        let fuzz_instructions =
+            Commander::format_program_code_nightly(&refreshed.source_code).await?;
+
+        update_file(&self.root, &fuzz_instructions_path, &fuzz_instructions).await?;
+        update_file(&self.root, &fuzz_dictionary_path, &self.fuzz_dictionary).await?;
+        self.store_selection(fuzz_test_dir).await?;
+
+        let conflicts_file = conflicts_path.strip_prefix(&self.root)?.display();
+        if refreshed.conflicts.is_empty() && refreshed.removed_instructions.is_empty() {
+            if conflicts_path.exists() {
+                fs::remove_file(&conflicts_path).await?;
+            }
+        } else {
+            let diffs: Vec<String> = refreshed
+                .conflicts
+                .iter()
+                .map(|conflict| conflict.diff())
+                .chain(
+                    refreshed
+                        .removed_instructions
+                        .iter()
+                        .map(|instruction| instruction.diff()),
+                )
+                .collect();
+            fs::write(&conflicts_path, diffs.join("\n")).await?;
+        }
+
+        for instruction in &refreshed.removed_instructions {
+            println!(
+                "{FINISH} [{}] removed, the instruction is no longer in the IDL or was not selected, its code is in [{conflicts_file}]",
+                instruction.name
+            );
+        }
+        for conflict in &refreshed.conflicts {
+            match conflict.regenerated {
+                true => println!(
+                    "{CONFLICT} [{}] regenerated, as it contained generated todo!(), the previous code is in [{conflicts_file}]",
+                    conflict.item
+                ),
+                false => println!(
+                    "{CONFLICT} [{}] kept, review the regenerated code in [{conflicts_file}]",
+                    conflict.item
+                ),
+            }
+        }
+    }
+
+    // the selection is stored only if the fuzz test is not generated for all instructions
+    #[throws]
+    async fn store_selection(&self, fuzz_test_dir: &Path) {
+        if !self.selection.is_empty() {
+            let selection_path = fuzz_test_dir.join(FUZZ_SELECTION);
+            update_file(
+                &self.root,
+                &selection_path,
+                &toml::to_string(&self.selection)?,
+            )
+            .await?;
+        }
+    }
+
     #[throws]
     pub async fn initialize_new_fuzz_test(&self) {
         let fuzz_dir_path = construct_path!(self.root, TESTS_WORKSPACE_DIRECTORY);
@@ -177,6 +374,7 @@ impl TestGenerator {
 
         let fuzz_test_path = new_fuzz_test_dir.join(FUZZ_TEST);
         let fuzz_instructions_path = new_fuzz_test_dir.join(FUZZ_INSTRUCTIONS_FILE_NAME);
+        let fuzz_dictionary_path = new_fuzz_test_dir.join(FUZZ_DICTIONARY);
 
         let cargo_toml_content = load_template!("/src/template/Cargo_fuzz.toml.tmpl");
 
@@ -184,8 +382,10 @@ impl TestGenerator {
 
         create_file(&self.root, &fuzz_test_path, &self.test_fuzz).await?;
         create_file(&self.root, &fuzz_instructions_path, &self.fuzz_instructions).await?;
+        create_file(&self.root, &fuzz_dictionary_path, &self.fuzz_dictionary).await?;
         create_file(&self.root, &fuzz_tests_manifest_path, cargo_toml_content).await?;
         create_file(&self.root, &trident_toml_path, trident_toml_content).await?;
+        self.store_selection(&new_fuzz_test_dir).await?;
 
         add_bin_target(&fuzz_tests_manifest_path, &new_fuzz_test, &new_bin_target).await?;
         update_fuzz_tests_manifest(
@@ -202,3 +402,56 @@ impl TestGenerator {
         // .await?;
     }
 }
+
+#[throws]
+fn load_idl_paths(idl_paths: &[PathBuf]) -> Vec<Idl> {
+    let mut idls = Vec::new();
+    for idl_path in idl_paths {
+        if idl_path.is_dir() {
+            idls.extend(crate::idl_loader::load_idls(idl_path.clone())?);
+        } else if idl_path.is_file() {
+            idls.push(crate::idl_loader::load_idl(idl_path)?);
+        } else {
+            throw!(Error::IdlNotFound(idl_path.display().to_string()));
+        }
+    }
+    idls
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_idl_paths() {
+        let tests_dir = construct_path!(PathBuf::from(env!("CARGO_MANIFEST_DIR")), "tests");
+
+        // IDL files and directories with IDLs can be combined
+        let idls = load_idl_paths(&[
+            construct_path!(tests_dir, "anchor_idl", "idl_test.json"),
+            construct_path!(tests_dir, "shank_idl"),
+        ])
+        .unwrap();
+        let names: Vec<&str> = idls.iter().map(|idl| idl.metadata.name.as_str()).collect();
+        assert_eq!(names, ["idl_test", "counter"]);
+
+        let missing_path = construct_path!(tests_dir, "missing_idl.json");
+        let error = load_idl_paths(&[missing_path.clone()]).unwrap_err();
+        assert!(
+            matches!(error, Error::IdlNotFound(idl_file) if idl_file == missing_path.display().to_string())
+        );
+    }
+
+    #[test]
+    fn test_load_idl_paths_invalid_idl() {
+        let idl_dir = tempfile::tempdir().unwrap();
+        let invalid_idl = idl_dir.path().join("invalid.json");
+        std::fs::write(&invalid_idl, "{").unwrap();
+
+        // an IDL that cannot be parsed fails the load instead of being skipped
+        let error = load_idl_paths(&[idl_dir.path().to_path_buf()]).unwrap_err();
+        assert!(
+            matches!(error, Error::CannotParseIdl(idl_file, _) if idl_file == invalid_idl.display().to_string())
+        );
+    }
+}
@@ -1,14 +1,16 @@
-use crate::test_generator::Error;
+use crate::test_generator::{Error, FuzzTestSelection};
 use crate::versions_config::TridentVersionsConfig;
 
 use crate::constants::*;
 use cargo_metadata::Package;
+use convert_case::{Case, Casing};
 use fehler::{throw, throws};
 use std::path::Path;
 use std::{fs::File, io::prelude::*};
 use std::{fs::OpenOptions, io, path::PathBuf};
 use tokio::fs;
 use toml::{value::Table, Value};
+use trident_idl_spec::Idl;
 
 #[macro_export]
 macro_rules! construct_path {
@@ -62,6 +64,14 @@ pub async fn create_file(root: &PathBuf, path: &PathBuf, content: &str) {
     };
 }
 
+#[throws]
+pub async fn update_file(root: &Path, path: &Path, content: &str) {
+    let file = path.strip_prefix(root)?.to_str().unwrap_or_default();
+
+    fs::write(path, content).await?;
+    println!("{FINISH} [{file}] updated");
+}
+
 #[throws]
 pub fn get_fuzz_id(fuzz_dir_path: &Path) -> i32 {
     if fuzz_dir_path.read_dir()?.next().is_none() {
@@ -105,6 +115,130 @@ pub fn program_packages() -> impl Iterator<Item = cargo_metadata::Package> {
     })
 }
 
+/// Resolves the program ID of every program, from the address in the IDL of the program,
+/// or from the `[programs.localnet]` (or any other cluster) section of Anchor.toml
+#[throws]
+pub async fn resolve_program_ids(
+    root: &Path,
+    idls: &[Idl],
+    lib_names: &[String],
+) -> Vec<(String, String)> {
+    let anchor_toml_path = construct_path!(root, ANCHOR_TOML);
+    let anchor_toml: Value = match anchor_toml_path.exists() {
+        true => toml::from_str(&fs::read_to_string(&anchor_toml_path).await?)?,
+        false => Value::Table(Table::new()),
+    };
+
+    let mut programs = vec![];
+    for lib_name in lib_names {
+        let idl_address = idls
+            .iter()
+            .find(|idl| same_program_name(&idl.metadata.name, lib_name))
+            .map(|idl| idl.address.clone())
+            .filter(|address| !address.is_empty());
+
+        let program_id = match idl_address {
+            Some(address) => address,
+            None => match anchor_toml_program_id(&anchor_toml, lib_name) {
+                Some(program_id) => program_id,
+                None => throw!(Error::ProgramIdNotFound(lib_name.clone())),
+            },
+        };
+        programs.push((lib_name.clone(), program_id));
+    }
+    programs
+}
+
+// Program ID from the `[programs.<cluster>]` sections of Anchor.toml, localnet is preferred
+fn anchor_toml_program_id(anchor_toml: &Value, lib_name: &str) -> Option<String> {
+    let clusters = anchor_toml.get("programs")?.as_table()?;
+    let program_id = |cluster: &Value| {
+        cluster
+            .as_table()?
+            .iter()
+            .find(|(program_name, _)| same_program_name(program_name, lib_name))
+            .and_then(|(_, program_id)| program_id.as_str())
+            .map(|program_id| program_id.to_string())
+    };
+
+    clusters
+        .get("localnet")
+        .and_then(program_id)
+        .or_else(|| clusters.values().find_map(program_id))
+}
+
+/// Selects the programs and instructions the fuzz test is generated for,
+/// programs without any selected instruction are left out
+#[throws]
+pub fn select_instructions(idls: &[Idl], selection: &FuzzTestSelection) -> Vec<Idl> {
+    if selection.is_empty() {
+        return idls.to_vec();
+    }
+
+    if let Some(program) = selection.programs.iter().find(|program| {
+        !idls
+            .iter()
+            .any(|idl| same_program_name(&idl.metadata.name, program))
+    }) {
+        throw!(Error::ProgramNotFound(program.clone()));
+    }
+
+    let mut selected_idls: Vec<Idl> = idls
+        .iter()
+        .filter(|idl| {
+            selection.programs.is_empty()
+                || selection
+                    .programs
+                    .iter()
+                    .any(|program| same_program_name(&idl.metadata.name, program))
+        })
+        .cloned()
+        .collect();
+
+    let is_listed = |instructions: &[String], instruction_name: &str| {
+        instructions
+            .iter()
+            .any(|name| same_instruction_name(name, instruction_name))
+    };
+
+    if let Some(instruction) = selection
+        .include
+        .iter()
+        .chain(&selection.exclude)
+        .find(|name| {
+            !selected_idls
+                .iter()
+                .flat_map(|idl| &idl.instructions)
+                .any(|instruction| same_instruction_name(name, &instruction.name))
+        })
+    {
+        throw!(Error::InstructionNotFound(instruction.clone()));
+    }
+
+    for idl in &mut selected_idls {
+        idl.instructions.retain(|instruction| {
+            (selection.include.is_empty() || is_listed(&selection.include, &instruction.name))
+                && !is_listed(&selection.exclude, &instruction.name)
+        });
+    }
+    selected_idls.retain(|idl| !idl.instructions.is_empty());
+
+    if selected_idls.is_empty() {
+        throw!(Error::NoInstructionsSelected);
+    }
+    selected_idls
+}
+
+// Instructions can be specified in any case, e.g. `initialize_user` or `InitializeUser`
+fn same_instruction_name(name: &str, instruction_name: &str) -> bool {
+    name.to_case(Case::Snake) == instruction_name.to_case(Case::Snake)
+}
+
+// Lib names use underscores, while program names might also contain dashes
+fn same_program_name(program_name: &str, lib_name: &str) -> bool {
+    program_name.replace('-', "_") == lib_name.replace('-', "_")
+}
+
 #[throws]
 pub fn update_gitignore(root: &PathBuf, ignored_path: &str) {
     let gitignore_path = construct_path!(root, GIT_IGNORE);
@@ -260,3 +394,217 @@ pub async fn update_fuzz_tests_manifest(
 
     fs::write(cargo_path, toml::to_string(&cargo_toml).unwrap()).await?;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn idl(name: &str, address: &str) -> Idl {
+        serde_json::from_value(json!({
+            "address": address,
+            "metadata": { "name": name, "version": "0.1.0", "spec": "0.1.0" },
+            "instructions": [],
+        }))
+        .unwrap()
+    }
+
+    fn program(name: &str, instructions: &[&str]) -> Idl {
+        let instructions: Vec<_> = instructions
+            .iter()
+            .map(|name| json!({ "name": name, "discriminator": [], "accounts": [], "args": [] }))
+            .collect();
+        serde_json::from_value(json!({
+            "address": "",
+            "metadata": { "name": name, "version": "0.1.0", "spec": "0.1.0" },
+            "instructions": instructions,
+        }))
+        .unwrap()
+    }
+
+    fn selection(programs: &[&str], include: &[&str], exclude: &[&str]) -> FuzzTestSelection {
+        let to_vec = |names: &[&str]| names.iter().map(|name| name.to_string()).collect();
+        FuzzTestSelection {
+            programs: to_vec(programs),
+            include: to_vec(include),
+            exclude: to_vec(exclude),
+        }
+    }
+
+    // Selected instructions of each selected program
+    fn selected(idls: &[Idl], selection: &FuzzTestSelection) -> Vec<(String, Vec<String>)> {
+        select_instructions(idls, selection)
+            .unwrap()
+            .into_iter()
+            .map(|idl| {
+                let instructions = idl.instructions.into_iter().map(|ix| ix.name).collect();
+                (idl.metadata.name, instructions)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_select_instructions() {
+        let idls = [
+            program("my-program", &["initialize", "deposit", "close_account"]),
+            program("other_program", &["initialize", "withdraw"]),
+        ];
+        let names =
+            |names: &[&str]| -> Vec<String> { names.iter().map(|name| name.to_string()).collect() };
+
+        // everything is selected by default
+        assert_eq!(selected(&idls, &selection(&[], &[], &[])).len(), 2);
+
+        // programs are matched with dashes or underscores
+        assert_eq!(
+            selected(&idls, &selection(&["my_program"], &[], &[])),
+            [(
+                "my-program".to_string(),
+                names(&["initialize", "deposit", "close_account"])
+            )]
+        );
+
+        // instruction names are case-insensitive, programs without instructions are left out
+        assert_eq!(
+            selected(&idls, &selection(&[], &["Deposit", "CloseAccount"], &[])),
+            [(
+                "my-program".to_string(),
+                names(&["deposit", "close_account"])
+            )]
+        );
+
+        // excluded instructions are removed from the included ones
+        assert_eq!(
+            selected(
+                &idls,
+                &selection(&[], &["initialize", "deposit"], &["INITIALIZE"])
+            ),
+            [("my-program".to_string(), names(&["deposit"]))]
+        );
+        assert_eq!(
+            selected(&idls, &selection(&["other_program"], &[], &["withdraw"])),
+            [("other_program".to_string(), names(&["initialize"]))]
+        );
+    }
+
+    #[test]
+    fn test_select_instructions_errors() {
+        let idls = [
+            program("my_program", &["initialize", "deposit"]),
+            program("other_program", &["withdraw"]),
+        ];
+
+        assert!(matches!(
+            select_instructions(&idls, &selection(&["unknown"], &[], &[])),
+            Err(Error::ProgramNotFound(program)) if program == "unknown"
+        ));
+        assert!(matches!(
+            select_instructions(&idls, &selection(&[], &["transfer"], &[])),
+            Err(Error::InstructionNotFound(instruction)) if instruction == "transfer"
+        ));
+        // the instruction exists, but not in the selected program
+        assert!(matches!(
+            select_instructions(&idls, &selection(&["my_program"], &[], &["withdraw"])),
+            Err(Error::InstructionNotFound(instruction)) if instruction == "withdraw"
+        ));
+        assert!(matches!(
+            select_instructions(&idls, &selection(&[], &["deposit"], &["deposit"])),
+            Err(Error::NoInstructionsSelected)
+        ));
+    }
+
+    #[test]
+    fn test_selection_is_stored() {
+        let stored = selection(&["my_program"], &["deposit"], &[]);
+        let serialized = toml::to_string(&stored).unwrap();
+
+        assert_eq!(
+            serialized,
+            "programs = [\"my_program\"]\ninclude = [\"deposit\"]\n"
+        );
+        assert_eq!(
+            toml::from_str::<FuzzTestSelection>(&serialized).unwrap(),
+            stored
+        );
+    }
+
+    #[test]
+    fn test_anchor_toml_program_id() {
+        let anchor_toml: Value = toml::from_str(
+            r#"
+            [programs.devnet]
+            my_program = "DevnetProgram"
+            other_program = "DevnetOther"
+
+            [programs.localnet]
+            my-program = "LocalnetProgram"
+            "#,
+        )
+        .unwrap();
+
+        // localnet is preferred, dashes and underscores are interchangeable
+        assert_eq!(
+            anchor_toml_program_id(&anchor_toml, "my_program").as_deref(),
+            Some("LocalnetProgram")
+        );
+        assert_eq!(
+            anchor_toml_program_id(&anchor_toml, "other_program").as_deref(),
+            Some("DevnetOther")
+        );
+        assert_eq!(anchor_toml_program_id(&anchor_toml, "missing"), None);
+        assert_eq!(
+            anchor_toml_program_id(&Value::Table(Table::new()), "my_program"),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resolve_program_ids() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(
+            root.path().join(ANCHOR_TOML),
+            r#"
+            [programs.localnet]
+            with_address = "AnchorTomlAddress"
+            without-address = "LocalnetAddress"
+
+            [programs.mainnet]
+            mainnet_only = "MainnetAddress"
+            "#,
+        )
+        .unwrap();
+
+        let idls = [
+            idl("with-address", "IdlAddress"),
+            idl("without_address", ""),
+        ];
+        let lib_names = ["with_address", "without_address", "mainnet_only"].map(String::from);
+
+        let programs = resolve_program_ids(root.path(), &idls, &lib_names)
+            .await
+            .unwrap();
+        assert_eq!(
+            programs,
+            [
+                ("with_address", "IdlAddress"),
+                ("without_address", "LocalnetAddress"),
+                ("mainnet_only", "MainnetAddress"),
+            ]
+            .map(|(lib_name, program_id)| (lib_name.to_string(), program_id.to_string()))
+        );
+
+        // without Anchor.toml, the program ID has to be in the IDL
+        let empty_root = tempfile::tempdir().unwrap();
+        let programs = resolve_program_ids(empty_root.path(), &idls[..1], &lib_names[..1])
+            .await
+            .unwrap();
+        assert_eq!(programs[0].1, "IdlAddress");
+
+        let error = resolve_program_ids(empty_root.path(), &idls, &lib_names[1..2])
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(error, Error::ProgramIdNotFound(lib_name) if lib_name == "without_address")
+        );
+    }
+}
@@ -0,0 +1,645 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use trident_fuzz::fuzzing::*;
+/// FuzzInstruction contains all available Instructions.
+/// Below, the instruction arguments (accounts and data) are defined.
+#[derive(Arbitrary, DisplayIx, FuzzTestExecutor)]
+pub enum FuzzInstruction {
+    Initialize(Initialize),
+    Increment(Increment),
+    SetLimits(SetLimits),
+    Deposit(Deposit),
+    Configure(Configure),
+}
+#[derive(Arbitrary, Debug)]
+pub struct Initialize {
+    pub accounts: InitializeAccounts,
+    pub data: InitializeData,
+    pub remaining_accounts: Vec<RemainingAccount>,
+}
+#[derive(Arbitrary, Debug)]
+pub struct InitializeAccounts {
+    pub counter: AccountId,
+    pub authority: AccountId,
+    pub system_program: AccountId,
+}
+/// Custom data types must derive `Debug` and `Arbitrary`.
+/// To do this, redefine the type in the fuzz test and implement the `From`
+/// trait
+/// to convert it into the type defined in the program.
+/// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#custom-data-types
+#[fuzz_constraints]
+#[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize)]
+pub struct InitializeData {
+    pub init_args: InitArgs,
+}
+#[derive(Arbitrary, Debug)]
+pub struct Increment {
+    pub accounts: IncrementAccounts,
+    pub data: IncrementData,
+    pub remaining_accounts: Vec<RemainingAccount>,
+}
+#[derive(Arbitrary, Debug)]
+pub struct IncrementAccounts {
+    pub counter: AccountId,
+    pub authority: AccountId,
+}
+/// Custom data types must derive `Debug` and `Arbitrary`.
+/// To do this, redefine the type in the fuzz test and implement the `From`
+/// trait
+/// to convert it into the type defined in the program.
+/// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#custom-data-types
+#[fuzz_constraints]
+#[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize)]
+pub struct IncrementData {
+    pub amount: u64,
+    pub memo: Option<String>,
+}
+#[derive(Arbitrary, Debug)]
+pub struct SetLimits {
+    pub accounts: SetLimitsAccounts,
+    pub data: SetLimitsData,
+    pub remaining_accounts: Vec<RemainingAccount>,
+}
+#[derive(Arbitrary, Debug)]
+pub struct SetLimitsAccounts {
+    pub counter: AccountId,
+    pub authority: AccountId,
+    pub delegate: Option<AccountId>,
+}
+/// Custom data types must derive `Debug` and `Arbitrary`.
+/// To do this, redefine the type in the fuzz test and implement the `From`
+/// trait
+/// to convert it into the type defined in the program.
+/// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#custom-data-types
+#[fuzz_constraints]
+#[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize)]
+pub struct SetLimitsData {
+    pub limits: Vec<SetLimitsLimitsEntry>,
+    pub range: SetLimitsRange,
+}
+#[derive(Arbitrary, Debug)]
+pub struct Deposit {
+    pub accounts: DepositAccounts,
+    pub data: DepositData,
+    pub remaining_accounts: Vec<RemainingAccount>,
+}
+#[derive(Arbitrary, Debug)]
+pub struct DepositAccounts {
+    pub vault: AccountId,
+    pub owner: AccountId,
+}
+/// Custom data types must derive `Debug` and `Arbitrary`.
+/// To do this, redefine the type in the fuzz test and implement the `From`
+/// trait
+/// to convert it into the type defined in the program.
+/// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#custom-data-types
+#[fuzz_constraints]
+#[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize)]
+pub struct DepositData {
+    pub amount: u64,
+    pub memo: Option<String>,
+}
+#[derive(Arbitrary, Debug)]
+pub struct Configure {
+    pub accounts: ConfigureAccounts,
+    pub data: ConfigureData,
+    pub remaining_accounts: Vec<RemainingAccount>,
+}
+#[derive(Arbitrary, Debug)]
+pub struct ConfigureAccounts {
+    pub vault: AccountId,
+    pub owner: AccountId,
+    pub guardian: Option<AccountId>,
+}
+/// Custom data types must derive `Debug` and `Arbitrary`.
+/// To do this, redefine the type in the fuzz test and implement the `From`
+/// trait
+/// to convert it into the type defined in the program.
+/// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#custom-data-types
+#[fuzz_constraints]
+#[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize)]
+pub struct ConfigureData {
+    pub allowed: Vec<AccountId>,
+    pub fees: Vec<ConfigureFeesEntry>,
+    pub schedule: ConfigureSchedule,
+}
+///IxOps implementation for `Initialize` with all required functions.
+impl IxOps for Initialize {
+    type IxAccounts = FuzzAccounts;
+    /// Definition of the instruction DISCRIMINATOR.
+    fn get_discriminator(&self) -> Vec<u8> {
+        vec![0u8]
+    }
+    /// Definition of the program ID that the Instruction is associated with.
+    fn get_program_id(&self) -> solana_sdk::pubkey::Pubkey {
+        pubkey!("9fKUSzDLdEzfDAPYPoz4J3b7pXLFHQv5r881U9MjPibo")
+    }
+    /// Definition of the Instruction data.
+    /// Use randomly generated data from the fuzzer using `self.data.arg_name`
+    /// or customize the data as needed.
+    /// For more details, visit: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-data
+    fn get_data(
+        &self,
+        client: &mut impl FuzzClient,
+        fuzz_accounts: &mut FuzzAccounts,
+    ) -> Result<Vec<u8>, FuzzingError> {
+        let mut args: Vec<u8> = self.get_discriminator();
+        {
+            args.extend(borsh::to_vec(&self.data.init_args).unwrap());
+        }
+        Ok(args)
+    }
+    /// Definition of of the accounts required by the Instruction.
+    /// To utilize accounts stored in `FuzzAccounts`, use
+    /// `fuzz_accounts.account_name.get_or_create_account()`.
+    /// If no signers are required, leave the vector empty.
+    /// For AccountMetas use <program>::accounts::<corresponding_metas>
+    /// The remaining accounts are picked from the `AddressPool`.
+    /// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-accounts
+    fn get_accounts(
+        &self,
+        client: &mut impl FuzzClient,
+        fuzz_accounts: &mut FuzzAccounts,
+    ) -> Result<(Vec<Keypair>, Vec<AccountMeta>), FuzzingError> {
+        let mut account_metas = vec![];
+        let mut signers = vec![];
+        {
+            let counter = fuzz_accounts.counter.get_or_create_account(
+                self.accounts.counter,
+                client,
+                500 * LAMPORTS_PER_SOL,
+            );
+            account_metas.push(AccountMeta::new(counter.pubkey(), true));
+            signers.push(counter.insecure_clone());
+        }
+        {
+            let authority = fuzz_accounts.authority.get_or_create_account(
+                self.accounts.authority,
+                client,
+                500 * LAMPORTS_PER_SOL,
+            );
+            account_metas.push(AccountMeta::new(authority.pubkey(), true));
+            signers.push(authority.insecure_clone());
+        }
+        {
+            let system_program = todo!();
+            account_metas.push(todo!());
+        }
+        account_metas.extend(fuzz_accounts.remaining_account_metas(&self.remaining_accounts));
+        Ok((signers, account_metas))
+    }
+    /// Definition of the layouts of the Instruction arguments, the enum tags
+    /// and length prefixes are mutated if `data_mutations` is enabled in
+    /// Trident.toml.
+    /// Update the layouts if you customize the data in `get_data`.
+    /// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-data-layout
+    fn get_data_layout(&self, data: &[u8]) -> DataLayout {
+        DataLayout::from_data(
+            data,
+            &[DataType::Struct(vec![
+                DataType::Fixed(8),
+                DataType::Enum(vec![
+                    vec![],
+                    vec![DataType::Fixed(1)],
+                    vec![DataType::Fixed(2)],
+                ]),
+            ])],
+        )
+    }
+}
+///IxOps implementation for `Increment` with all required functions.
+impl IxOps for Increment {
+    type IxAccounts = FuzzAccounts;
+    /// Definition of the instruction DISCRIMINATOR.
+    fn get_discriminator(&self) -> Vec<u8> {
+        vec![1u8]
+    }
+    /// Definition of the program ID that the Instruction is associated with.
+    fn get_program_id(&self) -> solana_sdk::pubkey::Pubkey {
+        pubkey!("9fKUSzDLdEzfDAPYPoz4J3b7pXLFHQv5r881U9MjPibo")
+    }
+    /// Definition of the Instruction data.
+    /// Use randomly generated data from the fuzzer using `self.data.arg_name`
+    /// or customize the data as needed.
+    /// For more details, visit: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-data
+    fn get_data(
+        &self,
+        client: &mut impl FuzzClient,
+        fuzz_accounts: &mut FuzzAccounts,
+    ) -> Result<Vec<u8>, FuzzingError> {
+        let mut args: Vec<u8> = self.get_discriminator();
+        {
+            args.extend(borsh::to_vec(&self.data.amount).unwrap());
+        }
+        {
+            args.extend(borsh::to_vec(&self.data.memo).unwrap());
+        }
+        Ok(args)
+    }
+    /// Definition of of the accounts required by the Instruction.
+    /// To utilize accounts stored in `FuzzAccounts`, use
+    /// `fuzz_accounts.account_name.get_or_create_account()`.
+    /// If no signers are required, leave the vector empty.
+    /// For AccountMetas use <program>::accounts::<corresponding_metas>
+    /// The remaining accounts are picked from the `AddressPool`.
+    /// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-accounts
+    fn get_accounts(
+        &self,
+        client: &mut impl FuzzClient,
+        fuzz_accounts: &mut FuzzAccounts,
+    ) -> Result<(Vec<Keypair>, Vec<AccountMeta>), FuzzingError> {
+        let mut account_metas = vec![];
+        let mut signers = vec![];
+        {
+            let counter = todo!();
+            account_metas.push(todo!());
+        }
+        {
+            let authority = fuzz_accounts.authority.get_or_create_account(
+                self.accounts.authority,
+                client,
+                500 * LAMPORTS_PER_SOL,
+            );
+            account_metas.push(AccountMeta::new_readonly(authority.pubkey(), true));
+            signers.push(authority.insecure_clone());
+        }
+        account_metas.extend(fuzz_accounts.remaining_account_metas(&self.remaining_accounts));
+        Ok((signers, account_metas))
+    }
+    /// Definition of the layouts of the Instruction arguments, the enum tags
+    /// and length prefixes are mutated if `data_mutations` is enabled in
+    /// Trident.toml.
+    /// Update the layouts if you customize the data in `get_data`.
+    /// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-data-layout
+    fn get_data_layout(&self, data: &[u8]) -> DataLayout {
+        DataLayout::from_data(
+            data,
+            &[
+                DataType::Fixed(8),
+                DataType::Option(Box::new(DataType::Vec(Box::new(DataType::Fixed(1))))),
+            ],
+        )
+    }
+}
+///IxOps implementation for `SetLimits` with all required functions.
+impl IxOps for SetLimits {
+    type IxAccounts = FuzzAccounts;
+    /// Definition of the instruction DISCRIMINATOR.
+    fn get_discriminator(&self) -> Vec<u8> {
+        vec![2u8]
+    }
+    /// Definition of the program ID that the Instruction is associated with.
+    fn get_program_id(&self) -> solana_sdk::pubkey::Pubkey {
+        pubkey!("9fKUSzDLdEzfDAPYPoz4J3b7pXLFHQv5r881U9MjPibo")
+    }
+    /// Definition of the Instruction data.
+    /// Use randomly generated data from the fuzzer using `self.data.arg_name`
+    /// or customize the data as needed.
+    /// For more details, visit: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-data
+    fn get_data(
+        &self,
+        client: &mut impl FuzzClient,
+        fuzz_accounts: &mut FuzzAccounts,
+    ) -> Result<Vec<u8>, FuzzingError> {
+        let mut args: Vec<u8> = self.get_discriminator();
+        {
+            args.extend(borsh::to_vec(&self.data.limits).unwrap());
+        }
+        {
+            args.extend(borsh::to_vec(&self.data.range).unwrap());
+        }
+        Ok(args)
+    }
+    /// Definition of of the accounts required by the Instruction.
+    /// To utilize accounts stored in `FuzzAccounts`, use
+    /// `fuzz_accounts.account_name.get_or_create_account()`.
+    /// If no signers are required, leave the vector empty.
+    /// For AccountMetas use <program>::accounts::<corresponding_metas>
+    /// The remaining accounts are picked from the `AddressPool`.
+    /// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-accounts
+    fn get_accounts(
+        &self,
+        client: &mut impl FuzzClient,
+        fuzz_accounts: &mut FuzzAccounts,
+    ) -> Result<(Vec<Keypair>, Vec<AccountMeta>), FuzzingError> {
+        let mut account_metas = vec![];
+        let mut signers = vec![];
+        {
+            let counter = todo!();
+            account_metas.push(todo!());
+        }
+        {
+            let authority = fuzz_accounts.authority.get_or_create_account(
+                self.accounts.authority,
+                client,
+                500 * LAMPORTS_PER_SOL,
+            );
+            account_metas.push(AccountMeta::new_readonly(authority.pubkey(), true));
+            signers.push(authority.insecure_clone());
+        }
+        {
+            match self.accounts.delegate {
+                Some(account_id) => {
+                    let delegate = todo!();
+                    account_metas.push(todo!());
+                }
+                None => account_metas.push(AccountMeta::new_readonly(self.get_program_id(), false)),
+            }
+        }
+        account_metas.extend(fuzz_accounts.remaining_account_metas(&self.remaining_accounts));
+        Ok((signers, account_metas))
+    }
+    /// Definition of the layouts of the Instruction arguments, the enum tags
+    /// and length prefixes are mutated if `data_mutations` is enabled in
+    /// Trident.toml.
+    /// Update the layouts if you customize the data in `get_data`.
+    /// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-data-layout
+    fn get_data_layout(&self, data: &[u8]) -> DataLayout {
+        DataLayout::from_data(
+            data,
+            &[
+                DataType::Vec(Box::new(DataType::Fixed(40))),
+                DataType::Fixed(8),
+            ],
+        )
+    }
+}
+///IxOps implementation for `Deposit` with all required functions.
+impl IxOps for Deposit {
+    type IxAccounts = FuzzAccounts;
+    /// Definition of the instruction DISCRIMINATOR.
+    fn get_discriminator(&self) -> Vec<u8> {
+        vec![0u8]
+    }
+    /// Definition of the program ID that the Instruction is associated with.
+    fn get_program_id(&self) -> solana_sdk::pubkey::Pubkey {
+        pubkey!("V1vmDFdMpSNBFGwhbU6CeGPg3A91RjAttibvrNjvANZ")
+    }
+    /// Definition of the Instruction data.
+    /// Use randomly generated data from the fuzzer using `self.data.arg_name`
+    /// or customize the data as needed.
+    /// For more details, visit: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-data
+    fn get_data(
+        &self,
+        client: &mut impl FuzzClient,
+        fuzz_accounts: &mut FuzzAccounts,
+    ) -> Result<Vec<u8>, FuzzingError> {
+        let mut args: Vec<u8> = self.get_discriminator();
+        {
+            args.extend(borsh::to_vec(&self.data.amount).unwrap());
+        }
+        {
+            args.extend(borsh::to_vec(&self.data.memo).unwrap());
+        }
+        Ok(args)
+    }
+    /// Definition of of the accounts required by the Instruction.
+    /// To utilize accounts stored in `FuzzAccounts`, use
+    /// `fuzz_accounts.account_name.get_or_create_account()`.
+    /// If no signers are required, leave the vector empty.
+    /// For AccountMetas use <program>::accounts::<corresponding_metas>
+    /// The remaining accounts are picked from the `AddressPool`.
+    /// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-accounts
+    fn get_accounts(
+        &self,
+        client: &mut impl FuzzClient,
+        fuzz_accounts: &mut FuzzAccounts,
+    ) -> Result<(Vec<Keypair>, Vec<AccountMeta>), FuzzingError> {
+        let mut account_metas = vec![];
+        let mut signers = vec![];
+        {
+            let vault = todo!();
+            account_metas.push(todo!());
+        }
+        {
+            let owner = fuzz_accounts.owner.get_or_create_account(
+                self.accounts.owner,
+                client,
+                500 * LAMPORTS_PER_SOL,
+            );
+            account_metas.push(AccountMeta::new(owner.pubkey(), true));
+            signers.push(owner.insecure_clone());
+        }
+        {
+            account_metas.push(AccountMeta::new_readonly(
+                pubkey!("11111111111111111111111111111111"),
+                false,
+            ));
+        }
+        account_metas.extend(fuzz_accounts.remaining_account_metas(&self.remaining_accounts));
+        Ok((signers, account_metas))
+    }
+    /// Definition of the layouts of the Instruction arguments, the enum tags
+    /// and length prefixes are mutated if `data_mutations` is enabled in
+    /// Trident.toml.
+    /// Update the layouts if you customize the data in `get_data`.
+    /// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-data-layout
+    fn get_data_layout(&self, data: &[u8]) -> DataLayout {
+        DataLayout::from_data(
+            data,
+            &[
+                DataType::Fixed(8),
+                DataType::Option(Box::new(DataType::Vec(Box::new(DataType::Fixed(1))))),
+            ],
+        )
+    }
+}
+///IxOps implementation for `Configure` with all required functions.
+impl IxOps for Configure {
+    type IxAccounts = FuzzAccounts;
+    /// Definition of the instruction DISCRIMINATOR.
+    fn get_discriminator(&self) -> Vec<u8> {
+        vec![1u8]
+    }
+    /// Definition of the program ID that the Instruction is associated with.
+    fn get_program_id(&self) -> solana_sdk::pubkey::Pubkey {
+        pubkey!("V1vmDFdMpSNBFGwhbU6CeGPg3A91RjAttibvrNjvANZ")
+    }
+    /// Definition of the Instruction data.
+    /// Use randomly generated data from the fuzzer using `self.data.arg_name`
+    /// or customize the data as needed.
+    /// For more details, visit: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-data
+    fn get_data(
+        &self,
+        client: &mut impl FuzzClient,
+        fuzz_accounts: &mut FuzzAccounts,
+    ) -> Result<Vec<u8>, FuzzingError> {
+        let mut args: Vec<u8> = self.get_discriminator();
+        {
+            let allowed: Vec<Pubkey> = self
+                .data
+                .allowed
+                .iter()
+                .map(|account_id| fuzz_accounts.pick_address(*account_id))
+                .collect();
+            args.extend(borsh::to_vec(&allowed).unwrap());
+        }
+        {
+            args.extend(borsh::to_vec(&self.data.fees).unwrap());
+        }
+        {
+            args.extend(borsh::to_vec(&self.data.schedule).unwrap());
+        }
+        Ok(args)
+    }
+    /// Definition of of the accounts required by the Instruction.
+    /// To utilize accounts stored in `FuzzAccounts`, use
+    /// `fuzz_accounts.account_name.get_or_create_account()`.
+    /// If no signers are required, leave the vector empty.
+    /// For AccountMetas use <program>::accounts::<corresponding_metas>
+    /// The remaining accounts are picked from the `AddressPool`.
+    /// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-accounts
+    fn get_accounts(
+        &self,
+        client: &mut impl FuzzClient,
+        fuzz_accounts: &mut FuzzAccounts,
+    ) -> Result<(Vec<Keypair>, Vec<AccountMeta>), FuzzingError> {
+        let mut account_metas = vec![];
+        let mut signers = vec![];
+        {
+            let vault = todo!();
+            account_metas.push(todo!());
+        }
+        {
+            let owner = fuzz_accounts.owner.get_or_create_account(
+                self.accounts.owner,
+                client,
+                500 * LAMPORTS_PER_SOL,
+            );
+            account_metas.push(AccountMeta::new_readonly(owner.pubkey(), true));
+            signers.push(owner.insecure_clone());
+        }
+        {
+            match self.accounts.guardian {
+                Some(account_id) => {
+                    let guardian = todo!();
+                    account_metas.push(todo!());
+                }
+                None => account_metas.push(AccountMeta::new_readonly(self.get_program_id(), false)),
+            }
+        }
+        account_metas.extend(fuzz_accounts.remaining_account_metas(&self.remaining_accounts));
+        Ok((signers, account_metas))
+    }
+    /// Definition of the layouts of the Instruction arguments, the enum tags
+    /// and length prefixes are mutated if `data_mutations` is enabled in
+    /// Trident.toml.
+    /// Update the layouts if you customize the data in `get_data`.
+    /// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-data-layout
+    fn get_data_layout(&self, data: &[u8]) -> DataLayout {
+        DataLayout::from_data(
+            data,
+            &[
+                DataType::Vec(Box::new(DataType::Fixed(32))),
+                DataType::Vec(Box::new(DataType::Fixed(3))),
+                DataType::Fixed(16),
+            ],
+        )
+    }
+}
+/// Check supported AccountsStorages at
+/// https://ackee.xyz/trident/docs/latest/features/account-storages/
+#[derive(Clone)]
+pub struct FuzzAccounts {
+    authority: AccountsStorage<KeypairStore>,
+    counter: AccountsStorage<todo!()>,
+    delegate: AccountsStorage<todo!()>,
+    system_program: AccountsStorage<todo!()>,
+    guardian: AccountsStorage<todo!()>,
+    owner: AccountsStorage<KeypairStore>,
+    vault: AccountsStorage<todo!()>,
+}
+/// Limit the number of accounts per Account Storage with
+/// `AccountsStorage::new(max_accounts)`, or set the limits in
+/// `[fuzz.account_storages.<name>]` of Trident.toml
+impl AccountStorages for FuzzAccounts {
+    fn new(config: &TridentConfig) -> Self {
+        Self {
+            authority: AccountsStorage::from_manifest("authority", config),
+            counter: AccountsStorage::from_manifest("counter", config),
+            delegate: AccountsStorage::from_manifest("delegate", config),
+            system_program: AccountsStorage::from_manifest("system_program", config),
+            guardian: AccountsStorage::from_manifest("guardian", config),
+            owner: AccountsStorage::from_manifest("owner", config),
+            vault: AccountsStorage::from_manifest("vault", config),
+        }
+    }
+}
+/// Addresses Public Key arguments without a matching Account Storage
+/// are picked from, extend the pool with other known addresses.
+/// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#public-key-arguments
+impl AddressPool for FuzzAccounts {
+    fn address_pool(&self) -> Vec<Pubkey> {
+        [
+            self.authority.pubkeys(),
+            self.counter.pubkeys(),
+            self.delegate.pubkeys(),
+            self.system_program.pubkeys(),
+            self.guardian.pubkeys(),
+            self.owner.pubkeys(),
+            self.vault.pubkeys(),
+        ]
+        .concat()
+    }
+}
+#[fuzz_constraints]
+#[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize, Clone)]
+struct SetLimitsLimitsEntry(pub AccountId, pub u64);
+#[fuzz_constraints]
+#[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize, Clone)]
+struct SetLimitsRange(pub u32, pub u32);
+#[derive(Debug, BorshDeserialize, BorshSerialize, Clone)]
+pub struct Counter {
+    authority: Pubkey,
+    count: u64,
+    mode: Mode,
+}
+impl Discriminator for Counter {
+    const DISCRIMINATOR: &'static [u8] = &[];
+}
+#[fuzz_constraints]
+#[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize, Clone)]
+pub struct InitArgs {
+    start_value: u64,
+    mode: Mode,
+}
+#[fuzz_constraints]
+#[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize, Clone)]
+pub enum Mode {
+    Up,
+    Down(u8),
+    Step { step_size: u16 },
+}
+#[fuzz_constraints]
+#[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize, Clone)]
+struct ConfigureFeesEntry(pub u8, pub u16);
+#[fuzz_constraints]
+#[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize, Clone)]
+pub struct ConfigureSchedule {
+    start: i64,
+    end: i64,
+}
+#[derive(Debug, BorshDeserialize, BorshSerialize, Clone)]
+pub struct Vault {
+    owner: Pubkey,
+    balance: u64,
+    label: [u8; 16usize],
+    config: VaultConfig,
+}
+impl Discriminator for Vault {
+    const DISCRIMINATOR: &'static [u8] = &[211u8, 8u8, 232u8, 43u8, 2u8, 152u8, 117u8, 119u8];
+}
+#[fuzz_constraints]
+#[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize, Clone)]
+pub struct VaultConfig {
+    max_deposit: u64,
+    state: VaultState,
+    checkpoints: [u32; 4usize],
+}
+#[fuzz_constraints]
+#[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize, Clone)]
+pub enum VaultState {
+    Active,
+    Locked { until: i64 },
+    Migrated(AccountId),
+}
@@ -0,0 +1,34 @@
+use trident_fuzz::fuzzing::*;
+mod fuzz_instructions;
+use counter::process_instruction as process_instruction_counter;
+use fuzz_instructions::FuzzInstruction;
+use fuzz_instructions::*;
+use vault::process_instruction as process_instruction_vault;
+struct InstructionsSequence;
+/// Define instruction sequences for invocation.
+/// `pre` runs at the start, `middle` in the middle, and `post` at the end.
+/// For example, to call `InitializeFn`, `UpdateFn` and then `WithdrawFn` during
+/// each fuzzing iteration:
+/// ```
+/// impl FuzzDataBuilder<FuzzInstruction> for InstructionsSequence {
+///     pre_sequence!(InitializeFn,UpdateFn);
+///     middle_sequence!(WithdrawFn);
+///}
+/// ```
+/// For more details, see: https://ackee.xyz/trident/docs/latest/features/instructions-sequences/#instructions-sequences
+impl FuzzDataBuilder<FuzzInstruction> for InstructionsSequence {}
+fn main() {
+    let program_counter = ProgramEntrypoint::new(
+        pubkey!("9fKUSzDLdEzfDAPYPoz4J3b7pXLFHQv5r881U9MjPibo"),
+        None,
+        processor!(process_instruction_counter),
+    );
+    let program_vault = ProgramEntrypoint::new(
+        pubkey!("V1vmDFdMpSNBFGwhbU6CeGPg3A91RjAttibvrNjvANZ"),
+        None,
+        processor!(process_instruction_vault),
+    );
+    let config = TridentConfig::new();
+    let mut client = TridentClient::new_client(&[program_counter, program_vault], &config);
+    fuzz_trident ! (fuzz_ix : FuzzInstruction , | fuzz_data : InstructionsSequence , client : TridentClient , config : TridentConfig |);
+}
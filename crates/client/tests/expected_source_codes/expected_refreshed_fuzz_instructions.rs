@@ -0,0 +1,1227 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use std::collections::HashMap;
+use trident_fuzz::fuzzing::*;
+/// FuzzInstruction contains all available Instructions.
+/// Below, the instruction arguments (accounts and data) are defined.
+#[derive(Arbitrary, DisplayIx, FuzzTestExecutor)]
+pub enum FuzzInstruction {
+    ProcessCustomTypes(ProcessCustomTypes),
+    ProcessRustTypes(ProcessRustTypes),
+    ProcessTokenAccounts(ProcessTokenAccounts),
+    ProcessPdaAccounts(ProcessPdaAccounts),
+    ProcessPubkeyArgs(ProcessPubkeyArgs),
+    Initialize(Initialize),
+}
+#[derive(Arbitrary, Debug)]
+pub struct ProcessCustomTypes {
+    pub accounts: ProcessCustomTypesAccounts,
+    pub data: ProcessCustomTypesData,
+    pub remaining_accounts: Vec<RemainingAccount>,
+}
+#[derive(Arbitrary, Debug)]
+pub struct ProcessCustomTypesAccounts {
+    pub some_account: AccountId,
+    pub some_account: AccountId,
+    pub signer: AccountId,
+    pub data_account_1: AccountId,
+    pub data_account_2: AccountId,
+    pub data_account_3: AccountId,
+    pub data_account_4: AccountId,
+    pub data_account_5: AccountId,
+    pub data_account_6: AccountId,
+    pub some_account: AccountId,
+    pub signer: AccountId,
+    pub data_account_1: AccountId,
+}
+/// Custom data types must derive `Debug` and `Arbitrary`.
+/// To do this, redefine the type in the fuzz test and implement the `From`
+/// trait
+/// to convert it into the type defined in the program.
+/// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#custom-data-types
+#[fuzz_constraints]
+#[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize)]
+pub struct ProcessCustomTypesData {
+    pub _input_classic: ClassicStruct,
+    pub _input_optional: OptionalFields,
+    pub _input_tuple: TupleStruct,
+    pub _input_enum: SimpleEnum,
+    pub _input_data_enum: DataEnum,
+    pub _input_multi_data_enum: MultiDataEnum,
+    pub _input_named_fields_enum: NamedFieldsEnum,
+    pub _input_generic_enum: GenericEnum<u8>,
+    pub _input_unit_variants: UnitVariants,
+    pub _input_nested: NestedStruct,
+    pub _input_default: DefaultStruct,
+    pub _input_generic_struct: GenericStruct<u32>,
+}
+#[derive(Arbitrary, Debug)]
+pub struct ProcessRustTypes {
+    pub accounts: ProcessRustTypesAccounts,
+    pub data: ProcessRustTypesData,
+    pub remaining_accounts: Vec<RemainingAccount>,
+}
+#[derive(Arbitrary, Debug)]
+pub struct ProcessRustTypesAccounts {
+    pub some_account: AccountId,
+    pub some_account: AccountId,
+    pub signer: AccountId,
+    pub data_account_1: AccountId,
+    pub data_account_2: AccountId,
+    pub data_account_3: AccountId,
+    pub data_account_4: AccountId,
+    pub data_account_5: AccountId,
+    pub data_account_6: AccountId,
+    pub some_account: AccountId,
+    pub signer: AccountId,
+    pub data_account_1: AccountId,
+}
+/// Custom data types must derive `Debug` and `Arbitrary`.
+/// To do this, redefine the type in the fuzz test and implement the `From`
+/// trait
+/// to convert it into the type defined in the program.
+/// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#custom-data-types
+#[fuzz_constraints]
+#[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize)]
+pub struct ProcessRustTypesData {
+    // small values reach the interesting branches
+    pub _input_u8: u8,
+    pub _input_u16: u16,
+    pub _input_u32: u32,
+    pub _input_u64: u64,
+    pub _input_i8: i8,
+    pub _input_i16: i16,
+    pub _input_i32: i32,
+    pub _input_i64: i64,
+    pub _input_i128: i128,
+    pub _input_f32: f32,
+    pub _input_f64: f64,
+    pub _input_string: String,
+    pub _input_vec: Vec<u8>,
+    pub _input_vec_string: Vec<String>,
+    pub _input_bool: bool,
+}
+#[derive(Arbitrary, Debug)]
+pub struct ProcessTokenAccounts {
+    pub accounts: ProcessTokenAccountsAccounts,
+    pub data: ProcessTokenAccountsData,
+    pub remaining_accounts: Vec<RemainingAccount>,
+}
+#[derive(Arbitrary, Debug)]
+pub struct ProcessTokenAccountsAccounts {
+    pub owner: AccountId,
+    pub mint: AccountId,
+    pub owner_token_account: AccountId,
+}
+/// Custom data types must derive `Debug` and `Arbitrary`.
+/// To do this, redefine the type in the fuzz test and implement the `From`
+/// trait
+/// to convert it into the type defined in the program.
+/// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#custom-data-types
+#[fuzz_constraints]
+#[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize)]
+pub struct ProcessTokenAccountsData {
+    pub amount: u64,
+}
+#[derive(Arbitrary, Debug)]
+pub struct ProcessPdaAccounts {
+    pub accounts: ProcessPdaAccountsAccounts,
+    pub data: ProcessPdaAccountsData,
+    pub remaining_accounts: Vec<RemainingAccount>,
+}
+#[derive(Arbitrary, Debug)]
+pub struct ProcessPdaAccountsAccounts {
+    pub config: AccountId,
+    pub vault: AccountId,
+    pub metadata: AccountId,
+    pub authority: AccountId,
+    pub referrer: Option<AccountId>,
+    pub referral: Option<AccountId>,
+}
+/// Custom data types must derive `Debug` and `Arbitrary`.
+/// To do this, redefine the type in the fuzz test and implement the `From`
+/// trait
+/// to convert it into the type defined in the program.
+/// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#custom-data-types
+#[fuzz_constraints]
+#[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize)]
+pub struct ProcessPdaAccountsData {
+    pub id: u64,
+    pub name: String,
+}
+#[derive(Arbitrary, Debug)]
+pub struct ProcessPubkeyArgs {
+    pub accounts: ProcessPubkeyArgsAccounts,
+    pub data: ProcessPubkeyArgsData,
+    pub remaining_accounts: Vec<RemainingAccount>,
+}
+#[derive(Arbitrary, Debug)]
+pub struct ProcessPubkeyArgsAccounts {
+    pub authority: AccountId,
+    pub delegation: AccountId,
+}
+/// Custom data types must derive `Debug` and `Arbitrary`.
+/// To do this, redefine the type in the fuzz test and implement the `From`
+/// trait
+/// to convert it into the type defined in the program.
+/// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#custom-data-types
+#[fuzz_constraints]
+#[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize)]
+pub struct ProcessPubkeyArgsData {
+    pub new_authority: AccountId,
+    pub recipient: AccountId,
+    pub vault_address: Option<AccountId>,
+    pub close_authority: Option<AccountId>,
+    pub members: Vec<AccountId>,
+}
+#[derive(Arbitrary, Debug)]
+pub struct Initialize {
+    pub accounts: InitializeAccounts,
+    pub data: InitializeData,
+    pub remaining_accounts: Vec<RemainingAccount>,
+}
+#[derive(Arbitrary, Debug)]
+pub struct InitializeAccounts {}
+/// Custom data types must derive `Debug` and `Arbitrary`.
+/// To do this, redefine the type in the fuzz test and implement the `From`
+/// trait
+/// to convert it into the type defined in the program.
+/// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#custom-data-types
+#[fuzz_constraints]
+#[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize)]
+pub struct InitializeData {}
+///IxOps implementation for `ProcessCustomTypes` with all required functions.
+impl IxOps for ProcessCustomTypes {
+    type IxAccounts = FuzzAccounts;
+    /// Definition of the instruction DISCRIMINATOR.
+    fn get_discriminator(&self) -> Vec<u8> {
+        vec![37u8, 23u8, 242u8, 88u8, 134u8, 197u8, 190u8, 108u8]
+    }
+    /// Definition of the program ID that the Instruction is associated with.
+    fn get_program_id(&self) -> solana_sdk::pubkey::Pubkey {
+        pubkey!("HtD1eaPZ1JqtxcirNtYt3aAhUMoJWZ2Ddtzu4NDZCrhN")
+    }
+    /// Definition of the Instruction data.
+    /// Use randomly generated data from the fuzzer using `self.data.arg_name`
+    /// or customize the data as needed.
+    /// For more details, visit: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-data
+    fn get_data(
+        &self,
+        client: &mut impl FuzzClient,
+        fuzz_accounts: &mut FuzzAccounts,
+    ) -> Result<Vec<u8>, FuzzingError> {
+        let mut args: Vec<u8> = self.get_discriminator();
+        {
+            args.extend(borsh::to_vec(&self.data._input_classic).unwrap());
+        }
+        {
+            args.extend(borsh::to_vec(&self.data._input_optional).unwrap());
+        }
+        {
+            args.extend(borsh::to_vec(&self.data._input_tuple).unwrap());
+        }
+        {
+            args.extend(borsh::to_vec(&self.data._input_enum).unwrap());
+        }
+        {
+            args.extend(borsh::to_vec(&self.data._input_data_enum).unwrap());
+        }
+        {
+            args.extend(borsh::to_vec(&self.data._input_multi_data_enum).unwrap());
+        }
+        {
+            args.extend(borsh::to_vec(&self.data._input_named_fields_enum).unwrap());
+        }
+        {
+            args.extend(borsh::to_vec(&self.data._input_generic_enum).unwrap());
+        }
+        {
+            args.extend(borsh::to_vec(&self.data._input_unit_variants).unwrap());
+        }
+        {
+            args.extend(borsh::to_vec(&self.data._input_nested).unwrap());
+        }
+        {
+            args.extend(borsh::to_vec(&self.data._input_default).unwrap());
+        }
+        {
+            args.extend(borsh::to_vec(&self.data._input_generic_struct).unwrap());
+        }
+        Ok(args)
+    }
+    /// Definition of of the accounts required by the Instruction.
+    /// To utilize accounts stored in `FuzzAccounts`, use
+    /// `fuzz_accounts.account_name.get_or_create_account()`.
+    /// If no signers are required, leave the vector empty.
+    /// For AccountMetas use <program>::accounts::<corresponding_metas>
+    /// The remaining accounts are picked from the `AddressPool`.
+    /// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-accounts
+    fn get_accounts(
+        &self,
+        client: &mut impl FuzzClient,
+        fuzz_accounts: &mut FuzzAccounts,
+    ) -> Result<(Vec<Keypair>, Vec<AccountMeta>), FuzzingError> {
+        let mut account_metas = vec![];
+        let mut signers = vec![];
+        {
+            let some_account = todo!();
+            account_metas.push(todo!());
+        }
+        {
+            let some_account = todo!();
+            account_metas.push(todo!());
+        }
+        {
+            account_metas.push(AccountMeta::new_readonly(
+                pubkey!("11111111111111111111111111111111"),
+                false,
+            ));
+        }
+        {
+            let signer = fuzz_accounts.signer.get_or_create_account(
+                self.accounts.signer,
+                client,
+                500 * LAMPORTS_PER_SOL,
+            );
+            account_metas.push(AccountMeta::new_readonly(signer.pubkey(), true));
+            signers.push(signer.insecure_clone());
+        }
+        {
+            let data_account_1 = todo!();
+            account_metas.push(todo!());
+        }
+        {
+            let data_account_2 = todo!();
+            account_metas.push(todo!());
+        }
+        {
+            let data_account_3 = todo!();
+            account_metas.push(todo!());
+        }
+        {
+            let data_account_4 = todo!();
+            account_metas.push(todo!());
+        }
+        {
+            let data_account_5 = todo!();
+            account_metas.push(todo!());
+        }
+        {
+            let data_account_6 = todo!();
+            account_metas.push(todo!());
+        }
+        {
+            let some_account = todo!();
+            account_metas.push(todo!());
+        }
+        {
+            let signer = fuzz_accounts.signer.get_or_create_account(
+                self.accounts.signer,
+                client,
+                500 * LAMPORTS_PER_SOL,
+            );
+            account_metas.push(AccountMeta::new_readonly(signer.pubkey(), true));
+            signers.push(signer.insecure_clone());
+        }
+        {
+            let data_account_1 = todo!();
+            account_metas.push(todo!());
+        }
+        account_metas.extend(fuzz_accounts.remaining_account_metas(&self.remaining_accounts));
+        Ok((signers, account_metas))
+    }
+    /// Definition of the layouts of the Instruction arguments, the enum tags
+    /// and length prefixes are mutated if `data_mutations` is enabled in
+    /// Trident.toml.
+    /// Update the layouts if you customize the data in `get_data`.
+    /// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-data-layout
+    fn get_data_layout(&self, data: &[u8]) -> DataLayout {
+        DataLayout::from_data(
+            data,
+            &[
+                DataType::Fixed(35),
+                DataType::Struct(vec![
+                    DataType::Option(Box::new(DataType::Fixed(1))),
+                    DataType::Option(Box::new(DataType::Fixed(2))),
+                    DataType::Option(Box::new(DataType::Fixed(32))),
+                ]),
+                DataType::Fixed(35),
+                DataType::Enum(vec![vec![], vec![], vec![]]),
+                DataType::Enum(vec![
+                    vec![DataType::Fixed(4)],
+                    vec![DataType::Fixed(8)],
+                    vec![DataType::Vec(Box::new(DataType::Fixed(1)))],
+                    vec![DataType::Fixed(32)],
+                ]),
+                DataType::Enum(vec![
+                    vec![DataType::Fixed(4), DataType::Fixed(4)],
+                    vec![DataType::Fixed(4), DataType::Fixed(4), DataType::Fixed(4)],
+                    vec![DataType::Fixed(32), DataType::Fixed(32)],
+                ]),
+                DataType::Enum(vec![
+                    vec![DataType::Fixed(8), DataType::Fixed(8)],
+                    vec![DataType::Fixed(8)],
+                    vec![DataType::Fixed(32), DataType::Fixed(32)],
+                ]),
+                DataType::Enum(vec![vec![DataType::Fixed(1)], vec![]]),
+                DataType::Enum(vec![vec![], vec![], vec![]]),
+                DataType::Fixed(67),
+                DataType::Fixed(35),
+                DataType::Fixed(36),
+            ],
+        )
+    }
+}
+///IxOps implementation for `ProcessRustTypes` with all required functions.
+impl IxOps for ProcessRustTypes {
+    type IxAccounts = FuzzAccounts;
+    /// Definition of the instruction DISCRIMINATOR.
+    fn get_discriminator(&self) -> Vec<u8> {
+        vec![74u8, 102u8, 18u8, 245u8, 253u8, 10u8, 252u8, 246u8]
+    }
+    /// Definition of the program ID that the Instruction is associated with.
+    fn get_program_id(&self) -> solana_sdk::pubkey::Pubkey {
+        pubkey!("HtD1eaPZ1JqtxcirNtYt3aAhUMoJWZ2Ddtzu4NDZCrhN")
+    }
+    /// Definition of the Instruction data.
+    /// Use randomly generated data from the fuzzer using `self.data.arg_name`
+    /// or customize the data as needed.
+    /// For more details, visit: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-data
+    fn get_data(
+        &self,
+        client: &mut impl FuzzClient,
+        fuzz_accounts: &mut FuzzAccounts,
+    ) -> Result<Vec<u8>, FuzzingError> {
+        let mut args: Vec<u8> = self.get_discriminator();
+        {
+            args.extend(borsh::to_vec(&(self.data._input_u8 % 10)).unwrap());
+        }
+        {
+            args.extend(borsh::to_vec(&self.data._input_u16).unwrap());
+        }
+        {
+            args.extend(borsh::to_vec(&self.data._input_u32).unwrap());
+        }
+        {
+            args.extend(borsh::to_vec(&self.data._input_u64).unwrap());
+        }
+        {
+            args.extend(borsh::to_vec(&self.data._input_i8).unwrap());
+        }
+        {
+            args.extend(borsh::to_vec(&self.data._input_i16).unwrap());
+        }
+        {
+            args.extend(borsh::to_vec(&self.data._input_i32).unwrap());
+        }
+        {
+            args.extend(borsh::to_vec(&self.data._input_i64).unwrap());
+        }
+        {
+            args.extend(borsh::to_vec(&self.data._input_i128).unwrap());
+        }
+        {
+            args.extend(borsh::to_vec(&self.data._input_f32).unwrap());
+        }
+        {
+            args.extend(borsh::to_vec(&self.data._input_f64).unwrap());
+        }
+        {
+            args.extend(borsh::to_vec(&self.data._input_string).unwrap());
+        }
+        {
+            args.extend(borsh::to_vec(&self.data._input_vec).unwrap());
+        }
+        {
+            args.extend(borsh::to_vec(&self.data._input_vec_string).unwrap());
+        }
+        Ok(args)
+    }
+    /// Definition of of the accounts required by the Instruction.
+    /// To utilize accounts stored in `FuzzAccounts`, use
+    /// `fuzz_accounts.account_name.get_or_create_account()`.
+    /// If no signers are required, leave the vector empty.
+    /// For AccountMetas use <program>::accounts::<corresponding_metas>
+    /// The remaining accounts are picked from the `AddressPool`.
+    /// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-accounts
+    fn get_accounts(
+        &self,
+        client: &mut impl FuzzClient,
+        fuzz_accounts: &mut FuzzAccounts,
+    ) -> Result<(Vec<Keypair>, Vec<AccountMeta>), FuzzingError> {
+        let mut account_metas = vec![];
+        let mut signers = vec![];
+        {
+            let some_account = todo!();
+            account_metas.push(todo!());
+        }
+        {
+            let some_account = todo!();
+            account_metas.push(todo!());
+        }
+        {
+            account_metas.push(AccountMeta::new_readonly(
+                pubkey!("11111111111111111111111111111111"),
+                false,
+            ));
+        }
+        {
+            let signer = fuzz_accounts.signer.get_or_create_account(
+                self.accounts.signer,
+                client,
+                500 * LAMPORTS_PER_SOL,
+            );
+            account_metas.push(AccountMeta::new_readonly(signer.pubkey(), true));
+            signers.push(signer.insecure_clone());
+        }
+        {
+            let data_account_1 = todo!();
+            account_metas.push(todo!());
+        }
+        {
+            let data_account_2 = todo!();
+            account_metas.push(todo!());
+        }
+        {
+            let data_account_3 = todo!();
+            account_metas.push(todo!());
+        }
+        {
+            let data_account_4 = todo!();
+            account_metas.push(todo!());
+        }
+        {
+            let data_account_5 = todo!();
+            account_metas.push(todo!());
+        }
+        {
+            let data_account_6 = todo!();
+            account_metas.push(todo!());
+        }
+        {
+            let some_account = todo!();
+            account_metas.push(todo!());
+        }
+        {
+            let signer = fuzz_accounts.signer.get_or_create_account(
+                self.accounts.signer,
+                client,
+                500 * LAMPORTS_PER_SOL,
+            );
+            account_metas.push(AccountMeta::new_readonly(signer.pubkey(), true));
+            signers.push(signer.insecure_clone());
+        }
+        {
+            let data_account_1 = todo!();
+            account_metas.push(todo!());
+        }
+        account_metas.extend(fuzz_accounts.remaining_account_metas(&self.remaining_accounts));
+        Ok((signers, account_metas))
+    }
+    /// Definition of the layouts of the Instruction arguments, the enum tags
+    /// and length prefixes are mutated if `data_mutations` is enabled in
+    /// Trident.toml.
+    /// Update the layouts if you customize the data in `get_data`.
+    /// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-data-layout
+    fn get_data_layout(&self, data: &[u8]) -> DataLayout {
+        DataLayout::from_data(
+            data,
+            &[
+                DataType::Fixed(1),
+                DataType::Fixed(2),
+                DataType::Fixed(4),
+                DataType::Fixed(8),
+                DataType::Fixed(1),
+                DataType::Fixed(2),
+                DataType::Fixed(4),
+                DataType::Fixed(8),
+                DataType::Fixed(16),
+                DataType::Fixed(4),
+                DataType::Fixed(8),
+                DataType::Vec(Box::new(DataType::Fixed(1))),
+                DataType::Vec(Box::new(DataType::Fixed(1))),
+                DataType::Vec(Box::new(DataType::Vec(Box::new(DataType::Fixed(1))))),
+                DataType::Fixed(1),
+            ],
+        )
+    }
+}
+///IxOps implementation for `ProcessTokenAccounts` with all required functions.
+impl IxOps for ProcessTokenAccounts {
+    type IxAccounts = FuzzAccounts;
+    /// Definition of the instruction DISCRIMINATOR.
+    fn get_discriminator(&self) -> Vec<u8> {
+        vec![31u8, 79u8, 0u8, 180u8, 32u8, 180u8, 152u8, 160u8]
+    }
+    /// Definition of the program ID that the Instruction is associated with.
+    fn get_program_id(&self) -> solana_sdk::pubkey::Pubkey {
+        pubkey!("HtD1eaPZ1JqtxcirNtYt3aAhUMoJWZ2Ddtzu4NDZCrhN")
+    }
+    /// Definition of the Instruction data.
+    /// Use randomly generated data from the fuzzer using `self.data.arg_name`
+    /// or customize the data as needed.
+    /// For more details, visit: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-data
+    fn get_data(
+        &self,
+        client: &mut impl FuzzClient,
+        fuzz_accounts: &mut FuzzAccounts,
+    ) -> Result<Vec<u8>, FuzzingError> {
+        let mut args: Vec<u8> = self.get_discriminator();
+        {
+            args.extend(borsh::to_vec(&self.data.amount).unwrap());
+        }
+        Ok(args)
+    }
+    /// Definition of of the accounts required by the Instruction.
+    /// To utilize accounts stored in `FuzzAccounts`, use
+    /// `fuzz_accounts.account_name.get_or_create_account()`.
+    /// If no signers are required, leave the vector empty.
+    /// For AccountMetas use <program>::accounts::<corresponding_metas>
+    /// The remaining accounts are picked from the `AddressPool`.
+    /// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-accounts
+    fn get_accounts(
+        &self,
+        client: &mut impl FuzzClient,
+        fuzz_accounts: &mut FuzzAccounts,
+    ) -> Result<(Vec<Keypair>, Vec<AccountMeta>), FuzzingError> {
+        let mut account_metas = vec![];
+        let mut signers = vec![];
+        {
+            let owner = fuzz_accounts.owner.get_or_create_account(
+                self.accounts.owner,
+                client,
+                500 * LAMPORTS_PER_SOL,
+            );
+            account_metas.push(AccountMeta::new(owner.pubkey(), true));
+            signers.push(owner.insecure_clone());
+        }
+        {
+            let mint = fuzz_accounts.mint.get_or_create_account(
+                self.accounts.mint,
+                client,
+                500 * LAMPORTS_PER_SOL,
+            );
+            account_metas.push(AccountMeta::new(mint.pubkey(), true));
+            signers.push(mint.insecure_clone());
+        }
+        {
+            let owner = fuzz_accounts
+                .owner
+                .get_or_create_account(self.accounts.owner, client, 500 * LAMPORTS_PER_SOL)
+                .pubkey();
+            let mint = fuzz_accounts
+                .mint
+                .get_or_create_account(self.accounts.mint, client, 500 * LAMPORTS_PER_SOL)
+                .pubkey();
+            let owner_token_account = fuzz_accounts.owner_token_account.get_or_create_account(
+                self.accounts.owner_token_account,
+                client,
+                owner,
+                mint,
+                &pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"),
+                0,
+            );
+            account_metas.push(AccountMeta::new(owner_token_account, false));
+        }
+        {
+            account_metas.push(AccountMeta::new_readonly(
+                pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"),
+                false,
+            ));
+        }
+        {
+            account_metas.push(AccountMeta::new_readonly(
+                pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"),
+                false,
+            ));
+        }
+        {
+            account_metas.push(AccountMeta::new_readonly(
+                pubkey!("11111111111111111111111111111111"),
+                false,
+            ));
+        }
+        account_metas.extend(fuzz_accounts.remaining_account_metas(&self.remaining_accounts));
+        Ok((signers, account_metas))
+    }
+}
+///IxOps implementation for `ProcessPdaAccounts` with all required functions.
+impl IxOps for ProcessPdaAccounts {
+    type IxAccounts = FuzzAccounts;
+    /// Definition of the instruction DISCRIMINATOR.
+    fn get_discriminator(&self) -> Vec<u8> {
+        vec![40u8, 60u8, 48u8, 61u8, 11u8, 208u8, 49u8, 148u8]
+    }
+    /// Definition of the program ID that the Instruction is associated with.
+    fn get_program_id(&self) -> solana_sdk::pubkey::Pubkey {
+        pubkey!("HtD1eaPZ1JqtxcirNtYt3aAhUMoJWZ2Ddtzu4NDZCrhN")
+    }
+    /// Definition of the Instruction data.
+    /// Use randomly generated data from the fuzzer using `self.data.arg_name`
+    /// or customize the data as needed.
+    /// For more details, visit: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-data
+    fn get_data(
+        &self,
+        client: &mut impl FuzzClient,
+        fuzz_accounts: &mut FuzzAccounts,
+    ) -> Result<Vec<u8>, FuzzingError> {
+        let mut args: Vec<u8> = self.get_discriminator();
+        {
+            args.extend(borsh::to_vec(&self.data.id).unwrap());
+        }
+        {
+            args.extend(borsh::to_vec(&self.data.name).unwrap());
+        }
+        Ok(args)
+    }
+    /// Definition of of the accounts required by the Instruction.
+    /// To utilize accounts stored in `FuzzAccounts`, use
+    /// `fuzz_accounts.account_name.get_or_create_account()`.
+    /// If no signers are required, leave the vector empty.
+    /// For AccountMetas use <program>::accounts::<corresponding_metas>
+    /// The remaining accounts are picked from the `AddressPool`.
+    /// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-accounts
+    fn get_accounts(
+        &self,
+        client: &mut impl FuzzClient,
+        fuzz_accounts: &mut FuzzAccounts,
+    ) -> Result<(Vec<Keypair>, Vec<AccountMeta>), FuzzingError> {
+        let mut account_metas = vec![];
+        let mut signers = vec![];
+        {
+            let authority = fuzz_accounts
+                .authority
+                .get_or_create_account(self.accounts.authority, client, 500 * LAMPORTS_PER_SOL)
+                .pubkey();
+            let config = fuzz_accounts.config.get_or_create_account(
+                self.accounts.config,
+                client,
+                &[
+                    "config".as_bytes(),
+                    &self.data.id.to_le_bytes(),
+                    authority.as_ref(),
+                ],
+                &self.get_program_id(),
+            );
+            account_metas.push(AccountMeta::new(config, false));
+        }
+        {
+            let authority = fuzz_accounts
+                .authority
+                .get_or_create_account(self.accounts.authority, client, 500 * LAMPORTS_PER_SOL)
+                .pubkey();
+            let config = fuzz_accounts.config.get_or_create_account(
+                self.accounts.config,
+                client,
+                &[
+                    "config".as_bytes(),
+                    &self.data.id.to_le_bytes(),
+                    authority.as_ref(),
+                ],
+                &self.get_program_id(),
+            );
+            let vault = fuzz_accounts.vault.get_or_create_account(
+                self.accounts.vault,
+                client,
+                &[&[1u8, 2u8, 3u8], config.as_ref()],
+                &pubkey!("8bPSKGoWCdAW8Hu3S1hLHPpBv8BNwse4jDyaXNrj3jWB"),
+            );
+            account_metas.push(AccountMeta::new(vault, false));
+        }
+        {
+            let metadata = fuzz_accounts.metadata.get_or_create_account(
+                self.accounts.metadata,
+                client,
+                &["metadata".as_bytes(), self.data.name.as_bytes()],
+                &Pubkey::new_from_array([
+                    11u8, 118u8, 82u8, 151u8, 214u8, 187u8, 127u8, 210u8, 121u8, 164u8, 174u8,
+                    44u8, 101u8, 14u8, 36u8, 223u8, 12u8, 217u8, 181u8, 86u8, 9u8, 216u8, 179u8,
+                    240u8, 84u8, 121u8, 75u8, 29u8, 207u8, 43u8, 30u8, 5u8,
+                ]),
+            );
+            account_metas.push(AccountMeta::new_readonly(metadata, false));
+        }
+        {
+            let authority = fuzz_accounts.authority.get_or_create_account(
+                self.accounts.authority,
+                client,
+                500 * LAMPORTS_PER_SOL,
+            );
+            account_metas.push(AccountMeta::new(authority.pubkey(), true));
+            signers.push(authority.insecure_clone());
+        }
+        {
+            match self.accounts.referrer {
+                Some(account_id) => {
+                    let referrer = fuzz_accounts.referrer.get_or_create_account(
+                        account_id,
+                        client,
+                        500 * LAMPORTS_PER_SOL,
+                    );
+                    account_metas.push(AccountMeta::new_readonly(referrer.pubkey(), true));
+                    signers.push(referrer.insecure_clone());
+                }
+                None => account_metas.push(AccountMeta::new_readonly(self.get_program_id(), false)),
+            }
+        }
+        {
+            match self.accounts.referral {
+                Some(account_id) => match self.accounts.referrer {
+                    Some(referrer_id) => {
+                        let referrer = fuzz_accounts
+                            .referrer
+                            .get_or_create_account(referrer_id, client, 500 * LAMPORTS_PER_SOL)
+                            .pubkey();
+                        let referral = fuzz_accounts.referral.get_or_create_account(
+                            account_id,
+                            client,
+                            &["referral".as_bytes(), referrer.as_ref()],
+                            &self.get_program_id(),
+                        );
+                        account_metas.push(AccountMeta::new(referral, false));
+                    }
+                    None => {
+                        account_metas.push(AccountMeta::new_readonly(self.get_program_id(), false))
+                    }
+                },
+                None => account_metas.push(AccountMeta::new_readonly(self.get_program_id(), false)),
+            }
+        }
+        {
+            account_metas.push(AccountMeta::new_readonly(
+                pubkey!("8bPSKGoWCdAW8Hu3S1hLHPpBv8BNwse4jDyaXNrj3jWB"),
+                false,
+            ));
+        }
+        account_metas.extend(fuzz_accounts.remaining_account_metas(&self.remaining_accounts));
+        Ok((signers, account_metas))
+    }
+    /// Definition of the layouts of the Instruction arguments, the enum tags
+    /// and length prefixes are mutated if `data_mutations` is enabled in
+    /// Trident.toml.
+    /// Update the layouts if you customize the data in `get_data`.
+    /// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-data-layout
+    fn get_data_layout(&self, data: &[u8]) -> DataLayout {
+        DataLayout::from_data(
+            data,
+            &[
+                DataType::Fixed(8),
+                DataType::Vec(Box::new(DataType::Fixed(1))),
+            ],
+        )
+    }
+}
+///IxOps implementation for `ProcessPubkeyArgs` with all required functions.
+impl IxOps for ProcessPubkeyArgs {
+    type IxAccounts = FuzzAccounts;
+    /// Definition of the instruction DISCRIMINATOR.
+    fn get_discriminator(&self) -> Vec<u8> {
+        vec![255u8, 123u8, 206u8, 192u8, 143u8, 10u8, 141u8, 81u8]
+    }
+    /// Definition of the program ID that the Instruction is associated with.
+    fn get_program_id(&self) -> solana_sdk::pubkey::Pubkey {
+        pubkey!("HtD1eaPZ1JqtxcirNtYt3aAhUMoJWZ2Ddtzu4NDZCrhN")
+    }
+    /// Definition of the Instruction data.
+    /// Use randomly generated data from the fuzzer using `self.data.arg_name`
+    /// or customize the data as needed.
+    /// For more details, visit: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-data
+    fn get_data(
+        &self,
+        client: &mut impl FuzzClient,
+        fuzz_accounts: &mut FuzzAccounts,
+    ) -> Result<Vec<u8>, FuzzingError> {
+        let mut args: Vec<u8> = self.get_discriminator();
+        {
+            let new_authority: Pubkey = fuzz_accounts
+                .authority
+                .get(self.data.new_authority)
+                .pubkey();
+            args.extend(borsh::to_vec(&new_authority).unwrap());
+        }
+        {
+            let recipient: Pubkey = fuzz_accounts.pick_address(self.data.recipient);
+            args.extend(borsh::to_vec(&recipient).unwrap());
+        }
+        {
+            let vault_address: Option<Pubkey> = self
+                .data
+                .vault_address
+                .map(|account_id| fuzz_accounts.vault.get(account_id));
+            args.extend(borsh::to_vec(&vault_address).unwrap());
+        }
+        {
+            let close_authority: Option<Pubkey> = self
+                .data
+                .close_authority
+                .map(|account_id| fuzz_accounts.pick_address(account_id));
+            args.extend(borsh::to_vec(&close_authority).unwrap());
+        }
+        {
+            let members: Vec<Pubkey> = self
+                .data
+                .members
+                .iter()
+                .map(|account_id| fuzz_accounts.pick_address(*account_id))
+                .collect();
+            args.extend(borsh::to_vec(&members).unwrap());
+        }
+        Ok(args)
+    }
+    /// Definition of of the accounts required by the Instruction.
+    /// To utilize accounts stored in `FuzzAccounts`, use
+    /// `fuzz_accounts.account_name.get_or_create_account()`.
+    /// If no signers are required, leave the vector empty.
+    /// For AccountMetas use <program>::accounts::<corresponding_metas>
+    /// The remaining accounts are picked from the `AddressPool`.
+    /// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-accounts
+    fn get_accounts(
+        &self,
+        client: &mut impl FuzzClient,
+        fuzz_accounts: &mut FuzzAccounts,
+    ) -> Result<(Vec<Keypair>, Vec<AccountMeta>), FuzzingError> {
+        let mut account_metas = vec![];
+        let mut signers = vec![];
+        {
+            let authority = fuzz_accounts.authority.get_or_create_account(
+                self.accounts.authority,
+                client,
+                500 * LAMPORTS_PER_SOL,
+            );
+            account_metas.push(AccountMeta::new_readonly(authority.pubkey(), true));
+            signers.push(authority.insecure_clone());
+        }
+        {
+            let delegation = fuzz_accounts.delegation.get_or_create_account(
+                self.accounts.delegation,
+                client,
+                &[
+                    "delegation".as_bytes(),
+                    fuzz_accounts
+                        .authority
+                        .get(self.data.new_authority)
+                        .pubkey()
+                        .as_ref(),
+                ],
+                &self.get_program_id(),
+            );
+            account_metas.push(AccountMeta::new(delegation, false));
+        }
+        account_metas.extend(fuzz_accounts.remaining_account_metas(&self.remaining_accounts));
+        Ok((signers, account_metas))
+    }
+    /// Definition of the layouts of the Instruction arguments, the enum tags
+    /// and length prefixes are mutated if `data_mutations` is enabled in
+    /// Trident.toml.
+    /// Update the layouts if you customize the data in `get_data`.
+    /// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-data-layout
+    fn get_data_layout(&self, data: &[u8]) -> DataLayout {
+        DataLayout::from_data(
+            data,
+            &[
+                DataType::Fixed(32),
+                DataType::Fixed(32),
+                DataType::Option(Box::new(DataType::Fixed(32))),
+                DataType::Option(Box::new(DataType::Fixed(32))),
+                DataType::Vec(Box::new(DataType::Fixed(32))),
+            ],
+        )
+    }
+}
+///IxOps implementation for `Initialize` with all required functions.
+impl IxOps for Initialize {
+    type IxAccounts = FuzzAccounts;
+    /// Definition of the instruction DISCRIMINATOR.
+    fn get_discriminator(&self) -> Vec<u8> {
+        vec![175u8, 175u8, 109u8, 31u8, 13u8, 152u8, 155u8, 237u8]
+    }
+    /// Definition of the program ID that the Instruction is associated with.
+    fn get_program_id(&self) -> solana_sdk::pubkey::Pubkey {
+        pubkey!("8bPSKGoWCdAW8Hu3S1hLHPpBv8BNwse4jDyaXNrj3jWB")
+    }
+    /// Definition of the Instruction data.
+    /// Use randomly generated data from the fuzzer using `self.data.arg_name`
+    /// or customize the data as needed.
+    /// For more details, visit: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-data
+    fn get_data(
+        &self,
+        client: &mut impl FuzzClient,
+        fuzz_accounts: &mut FuzzAccounts,
+    ) -> Result<Vec<u8>, FuzzingError> {
+        let mut args: Vec<u8> = self.get_discriminator();
+        Ok(args)
+    }
+    /// Definition of of the accounts required by the Instruction.
+    /// To utilize accounts stored in `FuzzAccounts`, use
+    /// `fuzz_accounts.account_name.get_or_create_account()`.
+    /// If no signers are required, leave the vector empty.
+    /// For AccountMetas use <program>::accounts::<corresponding_metas>
+    /// The remaining accounts are picked from the `AddressPool`.
+    /// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-accounts
+    fn get_accounts(
+        &self,
+        client: &mut impl FuzzClient,
+        fuzz_accounts: &mut FuzzAccounts,
+    ) -> Result<(Vec<Keypair>, Vec<AccountMeta>), FuzzingError> {
+        let mut account_metas = vec![];
+        let mut signers = vec![];
+        // the authority is the first signer
+        let authority = fuzz_accounts
+            .signer
+            .get_or_create_account(0, client, LAMPORTS_PER_SOL);
+        signers.push(authority);
+        account_metas.extend(fuzz_accounts.remaining_account_metas(&self.remaining_accounts));
+        Ok((signers, account_metas))
+    }
+    fn check(
+        &self,
+        pre_ix: &[SnapshotAccount],
+        post_ix: &[SnapshotAccount],
+        ix_data: Vec<u8>,
+    ) -> Result<(), FuzzingError> {
+        // the counter is only ever incremented
+        Ok(())
+    }
+}
+/// Check supported AccountsStorages at
+/// https://ackee.xyz/trident/docs/latest/features/account-storages/
+#[derive(Clone)]
+pub struct FuzzAccounts {
+    authority: AccountsStorage<KeypairStore>,
+    config: AccountsStorage<PdaStore>,
+    data_account_1: AccountsStorage<todo!()>,
+    data_account_2: AccountsStorage<todo!()>,
+    data_account_3: AccountsStorage<todo!()>,
+    data_account_4: AccountsStorage<todo!()>,
+    data_account_5: AccountsStorage<todo!()>,
+    data_account_6: AccountsStorage<todo!()>,
+    delegation: AccountsStorage<PdaStore>,
+    metadata: AccountsStorage<PdaStore>,
+    mint: AccountsStorage<KeypairStore>,
+    owner: AccountsStorage<KeypairStore>,
+    owner_token_account: AccountsStorage<AtaStore>,
+    referral: AccountsStorage<PdaStore>,
+    referrer: AccountsStorage<KeypairStore>,
+    signer: AccountsStorage<KeypairStore>,
+    some_account: AccountsStorage<PdaStore>,
+    vault: AccountsStorage<PdaStore>,
+    // receives the fees of every instruction
+    treasury: AccountsStorage<KeypairStore>,
+}
+/// Limit the number of accounts per Account Storage with
+/// `AccountsStorage::new(max_accounts)`, or set the limits in
+/// `[fuzz.account_storages.<name>]` of Trident.toml
+impl AccountStorages for FuzzAccounts {
+    fn new(config: &TridentConfig) -> Self {
+        Self {
+            authority: AccountsStorage::from_manifest("authority", config),
+            config: AccountsStorage::from_manifest("config", config),
+            data_account_1: AccountsStorage::from_manifest("data_account_1", config),
+            data_account_2: AccountsStorage::from_manifest("data_account_2", config),
+            data_account_3: AccountsStorage::from_manifest("data_account_3", config),
+            data_account_4: AccountsStorage::from_manifest("data_account_4", config),
+            data_account_5: AccountsStorage::from_manifest("data_account_5", config),
+            data_account_6: AccountsStorage::from_manifest("data_account_6", config),
+            delegation: AccountsStorage::from_manifest("delegation", config),
+            metadata: AccountsStorage::from_manifest("metadata", config),
+            mint: AccountsStorage::from_manifest("mint", config),
+            owner: AccountsStorage::from_manifest("owner", config),
+            owner_token_account: AccountsStorage::from_manifest("owner_token_account", config),
+            referral: AccountsStorage::from_manifest("referral", config),
+            referrer: AccountsStorage::from_manifest("referrer", config),
+            signer: AccountsStorage::from_manifest("signer", config),
+            some_account: AccountsStorage::from_manifest("some_account", config),
+            vault: AccountsStorage::from_manifest("vault", config),
+            treasury: AccountsStorage::new(1),
+        }
+    }
+    // captured in the snapshot of every Instruction
+    fn watched_accounts(&self) -> Vec<Pubkey> {
+        self.treasury.pubkeys()
+    }
+}
+/// Addresses Public Key arguments without a matching Account Storage
+/// are picked from, extend the pool with other known addresses.
+/// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#public-key-arguments
+impl AddressPool for FuzzAccounts {
+    fn address_pool(&self) -> Vec<Pubkey> {
+        [
+            self.authority.pubkeys(),
+            self.config.pubkeys(),
+            self.data_account_1.pubkeys(),
+            self.data_account_2.pubkeys(),
+            self.data_account_3.pubkeys(),
+            self.data_account_4.pubkeys(),
+            self.data_account_5.pubkeys(),
+            self.data_account_6.pubkeys(),
+            self.delegation.pubkeys(),
+            self.metadata.pubkeys(),
+            self.mint.pubkeys(),
+            self.owner.pubkeys(),
+            self.owner_token_account.pubkeys(),
+            self.referral.pubkeys(),
+            self.referrer.pubkeys(),
+            self.signer.pubkeys(),
+            self.some_account.pubkeys(),
+            self.vault.pubkeys(),
+            self.treasury.pubkeys(),
+        ]
+        .concat()
+    }
+}
+pub type Amount = u64;
+#[fuzz_constraints]
+#[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize, Clone)]
+pub struct ClassicStruct {
+    field1: u8,
+    field2: u16,
+    field3: AccountId,
+}
+#[derive(Debug, BorshDeserialize, BorshSerialize, Clone)]
+pub struct ClassicStructAccount {
+    field1: u8,
+    field2: u16,
+    field3: Pubkey,
+}
+impl Discriminator for ClassicStructAccount {
+    const DISCRIMINATOR: &'static [u8] = &[10u8, 204u8, 168u8, 207u8, 9u8, 6u8, 242u8, 89u8];
+}
+#[derive(Debug, BorshDeserialize, BorshSerialize, Clone)]
+pub struct DataAccount {
+    unit_struct: UnitStruct,
+    tuple_struct: TupleStruct,
+    classic_struct: ClassicStruct,
+    generic_struct: GenericStruct<u8>,
+    optional_fields: OptionalFields,
+    default_struct: DefaultStruct,
+    nested_struct: NestedStruct,
+    simple_enum: SimpleEnum,
+    data_enum: DataEnum,
+    multi_data_enum: MultiDataEnum,
+    named_fields_enum: NamedFieldsEnum,
+    generic_enum: GenericEnum<u8>,
+    unit_variants: UnitVariants,
+}
+impl Discriminator for DataAccount {
+    const DISCRIMINATOR: &'static [u8] = &[85u8, 240u8, 182u8, 158u8, 76u8, 7u8, 18u8, 233u8];
+}
+#[fuzz_constraints]
+#[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize, Clone)]
+pub enum DataEnum {
+    Integer(i32),
+    Float(f64),
+    Text(String),
+    Pubkey(AccountId),
+}
+#[fuzz_constraints]
+#[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize, Clone)]
+pub struct DefaultStruct {
+    field1: u8,
+    field2: u16,
+    field3: AccountId,
+}
+#[derive(Debug, BorshDeserialize, BorshSerialize, Clone)]
+pub enum EnumAccount {
+    Uninitialized,
+    Amount(u64),
+    Authority { authority: Pubkey, bump: u8 },
+}
+impl Discriminator for EnumAccount {
+    const DISCRIMINATOR: &'static [u8] = &[53u8, 221u8, 43u8, 191u8, 228u8, 94u8, 201u8, 208u8];
+}
+pub type GenericAlias<T> = Vec<T>;
+#[fuzz_constraints]
+#[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize, Clone)]
+pub enum GenericEnum<T> {
+    Value(T),
+    None,
+}
+#[fuzz_constraints]
+#[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize, Clone)]
+pub struct GenericStruct<T> {
+    value: T,
+    key: AccountId,
+}
+#[fuzz_constraints]
+#[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize, Clone)]
+pub enum MultiDataEnum {
+    Pair(i32, i32),
+    Triple(i32, i32, i32),
+    Pubkey(AccountId, AccountId),
+}
+#[fuzz_constraints]
+#[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize, Clone)]
+pub enum NamedFieldsEnum {
+    Point {
+        x: f64,
+        y: f64,
+    },
+    Circle {
+        radius: f64,
+    },
+    Pubkey {
+        pubkey1: AccountId,
+        pubkey2: AccountId,
+    },
+}
+#[fuzz_constraints]
+#[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize, Clone)]
+pub struct NestedStruct {
+    inner: ClassicStruct,
+    key: AccountId,
+}
+#[derive(Debug, BorshDeserialize, BorshSerialize, Clone)]
+pub struct NestedStructAccount {
+    inner: ClassicStructAccount,
+}
+impl Discriminator for NestedStructAccount {
+    const DISCRIMINATOR: &'static [u8] = &[83u8, 54u8, 74u8, 216u8, 227u8, 166u8, 36u8, 8u8];
+}
+#[fuzz_constraints]
+#[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize, Clone)]
+pub struct OptionalFields {
+    field1: Option<u8>,
+    field2: Option<u16>,
+    field3: Option<AccountId>,
+}
+#[derive(Debug, BorshDeserialize, BorshSerialize, Clone)]
+pub struct OptionalFieldsAccount {
+    field1: Option<u8>,
+    field2: Option<u16>,
+    field3: Option<Pubkey>,
+}
+impl Discriminator for OptionalFieldsAccount {
+    const DISCRIMINATOR: &'static [u8] = &[106u8, 159u8, 212u8, 74u8, 108u8, 186u8, 212u8, 251u8];
+}
+#[fuzz_constraints]
+#[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize, Clone)]
+pub enum SimpleEnum {
+    Variant1,
+    Variant2,
+    Pubkey,
+}
+#[fuzz_constraints]
+#[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize, Clone)]
+struct TupleStruct(pub u8, pub u16, pub AccountId);
+#[derive(Debug, BorshDeserialize, BorshSerialize)]
+struct TupleStructAccount(pub u8, pub u16, pub Pubkey);
+impl Discriminator for TupleStructAccount {
+    const DISCRIMINATOR: &'static [u8] = &[178u8, 81u8, 243u8, 166u8, 161u8, 145u8, 202u8, 99u8];
+}
+#[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize, Clone)]
+pub struct UnitStruct;
+#[derive(Debug, BorshDeserialize, BorshSerialize, Clone)]
+pub struct UnitStructAccount;
+impl Discriminator for UnitStructAccount {
+    const DISCRIMINATOR: &'static [u8] = &[24u8, 221u8, 246u8, 80u8, 62u8, 247u8, 138u8, 203u8];
+}
+#[fuzz_constraints]
+#[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize, Clone)]
+pub enum UnitVariants {
+    VariantA,
+    VariantB,
+    VariantC,
+}
+/// Lamports every instruction pays to the treasury.
+fn fee(data: &HashMap<Pubkey, u64>) -> u64 {
+    data.values().sum()
+}
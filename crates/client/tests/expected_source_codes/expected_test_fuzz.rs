@@ -19,17 +19,17 @@ struct InstructionsSequence;
 impl FuzzDataBuilder<FuzzInstruction> for InstructionsSequence {}
 fn main() {
     let program_additional_program = ProgramEntrypoint::new(
-        pubkey!("fill corresponding program ID here"),
+        pubkey!("8bPSKGoWCdAW8Hu3S1hLHPpBv8BNwse4jDyaXNrj3jWB"),
         None,
         processor!(entry_additional_program),
     );
     let program_idl_test = ProgramEntrypoint::new(
-        pubkey!("fill corresponding program ID here"),
+        pubkey!("HtD1eaPZ1JqtxcirNtYt3aAhUMoJWZ2Ddtzu4NDZCrhN"),
         None,
         processor!(entry_idl_test),
     );
     let config = TridentConfig::new();
     let mut client =
-        TridentSVM::new_client(&[program_additional_program, program_idl_test], &config);
-    fuzz_trident ! (fuzz_ix : FuzzInstruction , | fuzz_data : InstructionsSequence , client : TridentSVM , config : TridentConfig |);
+        TridentClient::new_client(&[program_additional_program, program_idl_test], &config);
+    fuzz_trident ! (fuzz_ix : FuzzInstruction , | fuzz_data : InstructionsSequence , client : TridentClient , config : TridentConfig |);
 }
@@ -32,6 +32,96 @@ async fn test_fuzz_instructions() {
     assert_str_eq!(fuzz_instructions_code, expected_fuzz_instructions_code);
 }
 
+#[throws]
+#[tokio::test]
+async fn test_non_anchor_fuzz_instructions() {
+    let expected_fuzz_instructions_code = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/expected_source_codes/expected_non_anchor_fuzz_instructions.rs"
+    ));
+
+    let shank_idl = read_non_anchor_idl("shank_idl/counter.json")?;
+    let codama_idl = read_non_anchor_idl("codama_idl/vault.json")?;
+
+    let fuzz_instructions_code =
+        trident_template::fuzz_instructions_generator::generate_source_code(&vec![
+            shank_idl, codama_idl,
+        ]);
+
+    let fuzz_instructions_code =
+        trident_client::___private::Commander::format_program_code_nightly(&fuzz_instructions_code)
+            .await?;
+
+    assert_str_eq!(fuzz_instructions_code, expected_fuzz_instructions_code);
+}
+
+#[throws]
+#[tokio::test]
+async fn test_fuzz_instructions_refresh() {
+    let current_fuzz_instructions_code = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/refresh_source_codes/current_fuzz_instructions.rs"
+    ));
+    let expected_fuzz_instructions_code = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/expected_source_codes/expected_refreshed_fuzz_instructions.rs"
+    ));
+
+    let idl_test = read_idl("idl_test.json")?;
+    let additional_program = read_idl("additional_program.json")?;
+
+    let fuzz_instructions_code =
+        trident_template::fuzz_instructions_generator::generate_source_code(&vec![
+            idl_test,
+            additional_program,
+        ]);
+
+    let fuzz_instructions_code =
+        trident_client::___private::Commander::format_program_code_nightly(&fuzz_instructions_code)
+            .await?;
+
+    let refreshed = trident_template::fuzz_instructions_refresh::refresh_source_code(
+        current_fuzz_instructions_code,
+        &fuzz_instructions_code,
+    )?;
+
+    let refreshed_code =
+        trident_client::___private::Commander::format_program_code_nightly(&refreshed.source_code)
+            .await?;
+
+    assert_str_eq!(refreshed_code, expected_fuzz_instructions_code);
+
+    let conflicts: Vec<&str> = refreshed
+        .conflicts
+        .iter()
+        .map(|conflict| conflict.item.as_str())
+        .collect();
+    assert_eq!(
+        conflicts,
+        [
+            "ProcessCustomTypes::get_accounts",
+            "ProcessRustTypes::get_data"
+        ]
+    );
+    // the template with generated todo!() was regenerated, the customized method was kept
+    let regenerated: Vec<bool> = refreshed
+        .conflicts
+        .iter()
+        .map(|conflict| conflict.regenerated)
+        .collect();
+    assert_eq!(regenerated, [true, false]);
+    let removed: Vec<&str> = refreshed
+        .removed_instructions
+        .iter()
+        .map(|instruction| instruction.name.as_str())
+        .collect();
+    assert_eq!(removed, ["Close"]);
+    // the user code of the removed instruction is kept in the diff
+    assert!(refreshed.removed_instructions[0]
+        .diff()
+        .contains("-impl IxOps for Close {"));
+}
+
 #[throws]
 #[tokio::test]
 async fn test_fuzz_test() {
@@ -45,7 +135,66 @@ async fn test_fuzz_test() {
 
     let test_fuzz = trident_template::test_fuzz_generator::generate_source_code(
         &[idl_test, additional_program],
-        &["additional_program".to_string(), "idl_test".to_string()],
+        &[
+            (
+                "additional_program".to_string(),
+                "8bPSKGoWCdAW8Hu3S1hLHPpBv8BNwse4jDyaXNrj3jWB".to_string(),
+            ),
+            (
+                "idl_test".to_string(),
+                "HtD1eaPZ1JqtxcirNtYt3aAhUMoJWZ2Ddtzu4NDZCrhN".to_string(),
+            ),
+        ],
+    );
+
+    let test_fuzz =
+        trident_client::___private::Commander::format_program_code_nightly(&test_fuzz).await?;
+
+    assert_str_eq!(test_fuzz, test_fuzz_expected);
+}
+
+#[throws]
+#[tokio::test]
+async fn test_fuzz_dictionary() {
+    let expected_dictionary = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/expected_source_codes/expected_dictionary.dict"
+    ));
+
+    let idl_test = read_idl("idl_test.json")?;
+    let additional_program = read_idl("additional_program.json")?;
+
+    let dictionary = trident_template::dictionary_generator::generate_dictionary(&[
+        idl_test,
+        additional_program,
+    ]);
+
+    assert_str_eq!(dictionary, expected_dictionary);
+}
+
+#[throws]
+#[tokio::test]
+async fn test_non_anchor_fuzz_test() {
+    let test_fuzz_expected = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/expected_source_codes/expected_non_anchor_test_fuzz.rs"
+    ));
+
+    let shank_idl = read_non_anchor_idl("shank_idl/counter.json")?;
+    let codama_idl = read_non_anchor_idl("codama_idl/vault.json")?;
+
+    let test_fuzz = trident_template::test_fuzz_generator::generate_source_code(
+        &[shank_idl, codama_idl],
+        &[
+            (
+                "counter".to_string(),
+                "9fKUSzDLdEzfDAPYPoz4J3b7pXLFHQv5r881U9MjPibo".to_string(),
+            ),
+            (
+                "vault".to_string(),
+                "V1vmDFdMpSNBFGwhbU6CeGPg3A91RjAttibvrNjvANZ".to_string(),
+            ),
+        ],
     );
 
     let test_fuzz =
@@ -54,6 +203,23 @@ async fn test_fuzz_test() {
     assert_str_eq!(test_fuzz, test_fuzz_expected);
 }
 
+#[throws]
+#[tokio::test]
+async fn test_non_anchor_fuzz_dictionary() {
+    let expected_dictionary = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/expected_source_codes/expected_non_anchor_dictionary.dict"
+    ));
+
+    let shank_idl = read_non_anchor_idl("shank_idl/counter.json")?;
+    let codama_idl = read_non_anchor_idl("codama_idl/vault.json")?;
+
+    let dictionary =
+        trident_template::dictionary_generator::generate_dictionary(&[shank_idl, codama_idl]);
+
+    assert_str_eq!(dictionary, expected_dictionary);
+}
+
 #[throws]
 fn read_idl(_idl_name: &str) -> Idl {
     let current_dir = std::env::current_dir()?;
@@ -79,3 +245,25 @@ fn read_idl(_idl_name: &str) -> Idl {
         }
     }
 }
+
+#[throws]
+fn read_non_anchor_idl(idl_path: &str) -> Idl {
+    let current_dir = std::env::current_dir()?;
+    let idl_path: PathBuf = [
+        current_dir.as_ref(),
+        Path::new("tests"),
+        Path::new(idl_path),
+    ]
+    .iter()
+    .collect();
+
+    let json_content = std::fs::read_to_string(&idl_path)?;
+
+    // Shank and Codama IDLs are converted into the Anchor IDL format
+    match trident_client::___private::parse_idl(&json_content) {
+        Ok(parsed_idl) => parsed_idl,
+        Err(e) => {
+            panic!("Failed to parse {}: {}", idl_path.display(), e);
+        }
+    }
+}
@@ -1,4 +1,8 @@
-use crate::{argument::Argument, constants::*, utils::arg_to_string};
+use crate::{
+    argument::Argument,
+    constants::*,
+    utils::{arg_to_string, resolve_path},
+};
 use serde::Deserialize;
 
 #[derive(Debug, Deserialize, Clone)]
@@ -21,6 +25,9 @@ pub struct Afl {
     // seeds
     // -s
     pub seeds: Option<Vec<AflSeed>>,
+    // dictionary
+    // -x
+    pub dictionary: Option<String>,
 }
 
 impl Afl {
@@ -62,6 +69,16 @@ impl Afl {
             .filter(|&run_time| *run_time > 0)
             .map(|run_time| Argument::new("-V", "", Some(&run_time.to_string())))
     }
+    pub fn get_dictionary(&self) -> Option<Argument> {
+        // dictionary
+        self.dictionary
+            .as_ref()
+            .filter(|dictionary| !dictionary.is_empty())
+            .map(|dictionary| {
+                let full_path = resolve_path(dictionary);
+                Argument::new("-x", "", full_path.to_str())
+            })
+    }
     pub fn get_seeds(&self) -> Vec<AflSeed> {
         // seeds
         if let Some(seeds) = &self.seeds {
@@ -85,6 +102,9 @@ impl Afl {
         if let Some(seconds) = self.get_run_time() {
             result.extend(arg_to_string(&seconds));
         }
+        if let Some(dictionary) = self.get_dictionary() {
+            result.extend(arg_to_string(&dictionary));
+        }
         result
     }
 }
@@ -121,6 +141,7 @@ mod tests {
                 iterations: None,
                 run_time: None,
                 seeds: None,
+                dictionary: None,
             }
         }
     }
@@ -176,4 +197,14 @@ mod tests {
         let arg = afl.get_collect_fuzz_args();
         assert_eq!(arg, vec!["-V", "15"]);
     }
+    #[test]
+    fn test_dictionary() {
+        let mut afl = Afl::clean();
+
+        // dictionary
+        afl.dictionary = Some("/foo/bar/fuzz.dict".to_string());
+
+        let arg = afl.get_collect_fuzz_args();
+        assert_eq!(arg, vec!["-x", "/foo/bar/fuzz.dict"]);
+    }
 }
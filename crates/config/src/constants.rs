@@ -15,5 +15,8 @@ pub const AFL_WORKSPACE_DEFAULT_IN: &str = "trident-tests/fuzzing/afl/afl_worksp
 
 pub const AFL_WORKSPACE_DEFAULT_OUT: &str = "trident-tests/fuzzing/afl/afl_workspace/out";
 
+// stateful fuzzing
+pub const CRASH_CHAIN_ENV: &str = "TRIDENT_CRASH_CHAIN";
+
 pub const DEFAULT_SEED_FILENAME: &str = "trident-seed";
 pub const DEFAULT_SEED: &str = "trident";
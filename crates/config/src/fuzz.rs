@@ -1,18 +1,30 @@
 use crate::utils::resolve_path;
+use crate::Error;
 use base64::{prelude::BASE64_STANDARD, Engine};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use solana_sdk::{
     account::{AccountSharedData, WritableAccount},
+    clock::Clock,
+    epoch_schedule::{EpochSchedule, MINIMUM_SLOTS_PER_EPOCH},
     pubkey::Pubkey,
+    rent::Rent,
+    sysvar::last_restart_slot::LastRestartSlot,
 };
-use std::{fs, str::FromStr};
+use std::{collections::HashMap, fs, path::PathBuf, str::FromStr};
 
 #[derive(Debug, Deserialize, Clone, Default)]
 pub struct Fuzz {
     pub fuzzing_with_stats: Option<bool>,
     pub allow_duplicate_txs: Option<bool>,
+    pub snapshot_pre_ixs: Option<bool>,
+    pub accounts_seed: Option<u64>,
+    pub account_mutations: Option<bool>,
+    pub data_mutations: Option<bool>,
     pub programs: Option<Vec<_FuzzProgram>>,
     pub accounts: Option<Vec<_FuzzAccount>>,
+    pub sysvars: Option<FuzzSysvars>,
+    pub stateful: Option<FuzzStateful>,
+    pub account_storages: Option<HashMap<String, FuzzAccountStorage>>,
 }
 
 impl Fuzz {
@@ -22,6 +34,166 @@ impl Fuzz {
     pub fn get_allow_duplicate_txs(&self) -> bool {
         self.allow_duplicate_txs.unwrap_or(false)
     }
+    pub fn get_snapshot_pre_ixs(&self) -> bool {
+        self.snapshot_pre_ixs.unwrap_or(false)
+    }
+    pub fn get_accounts_seed(&self) -> u64 {
+        self.accounts_seed.unwrap_or(0)
+    }
+    pub fn get_account_mutations(&self) -> bool {
+        self.account_mutations.unwrap_or(false)
+    }
+    pub fn get_data_mutations(&self) -> bool {
+        self.data_mutations.unwrap_or(false)
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct FuzzStateful {
+    pub reset_interval: Option<u64>,
+    pub crash_chains_dir: Option<String>,
+}
+
+impl FuzzStateful {
+    pub fn get_reset_interval(&self) -> u64 {
+        // resetting every iteration is the same as the stateless mode
+        self.reset_interval.unwrap_or(1000).max(1)
+    }
+    pub fn get_crash_chains_dir(&self) -> PathBuf {
+        self.crash_chains_dir
+            .as_ref()
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from("fuzzing/crash_chains"))
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct FuzzAccountStorage {
+    pub max_accounts: Option<u8>,
+    pub reuse_probability: Option<u8>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct FuzzSysvars {
+    pub clock: Option<FuzzClock>,
+    pub rent: Option<FuzzRent>,
+    #[serde(default, deserialize_with = "deserialize_epoch_schedule")]
+    pub epoch_schedule: Option<FuzzEpochSchedule>,
+    pub last_restart_slot: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct FuzzClock {
+    pub slot: Option<u64>,
+    pub epoch_start_timestamp: Option<i64>,
+    pub epoch: Option<u64>,
+    pub leader_schedule_epoch: Option<u64>,
+    pub unix_timestamp: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct FuzzRent {
+    pub lamports_per_byte_year: Option<u64>,
+    pub exemption_threshold: Option<f64>,
+    pub burn_percent: Option<u8>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct FuzzEpochSchedule {
+    pub slots_per_epoch: Option<u64>,
+    pub leader_schedule_slot_offset: Option<u64>,
+    pub warmup: Option<bool>,
+}
+
+impl FuzzSysvars {
+    pub fn get_clock(&self) -> Clock {
+        self.clock.as_ref().map(Clock::from).unwrap_or_default()
+    }
+    pub fn get_rent(&self) -> Rent {
+        self.rent.as_ref().map(Rent::from).unwrap_or_default()
+    }
+    pub fn get_epoch_schedule(&self) -> EpochSchedule {
+        self.epoch_schedule
+            .as_ref()
+            .map(|epoch_schedule| {
+                EpochSchedule::try_from(epoch_schedule)
+                    .expect("the epoch_schedule sysvar is checked when the config is loaded")
+            })
+            .unwrap_or_default()
+    }
+    pub fn get_last_restart_slot(&self) -> Option<LastRestartSlot> {
+        self.last_restart_slot
+            .map(|last_restart_slot| LastRestartSlot { last_restart_slot })
+    }
+}
+
+impl From<&FuzzClock> for Clock {
+    fn from(_f: &FuzzClock) -> Self {
+        let default = Clock::default();
+        Clock {
+            slot: _f.slot.unwrap_or(default.slot),
+            epoch_start_timestamp: _f
+                .epoch_start_timestamp
+                .unwrap_or(default.epoch_start_timestamp),
+            epoch: _f.epoch.unwrap_or(default.epoch),
+            leader_schedule_epoch: _f
+                .leader_schedule_epoch
+                .unwrap_or(default.leader_schedule_epoch),
+            unix_timestamp: _f.unix_timestamp.unwrap_or(default.unix_timestamp),
+        }
+    }
+}
+
+impl From<&FuzzRent> for Rent {
+    fn from(_f: &FuzzRent) -> Self {
+        let default = Rent::default();
+        Rent {
+            lamports_per_byte_year: _f
+                .lamports_per_byte_year
+                .unwrap_or(default.lamports_per_byte_year),
+            exemption_threshold: _f
+                .exemption_threshold
+                .unwrap_or(default.exemption_threshold),
+            burn_percent: _f.burn_percent.unwrap_or(default.burn_percent),
+        }
+    }
+}
+
+impl TryFrom<&FuzzEpochSchedule> for EpochSchedule {
+    type Error = Error;
+
+    fn try_from(_f: &FuzzEpochSchedule) -> Result<Self, Self::Error> {
+        let default = EpochSchedule::default();
+        let slots_per_epoch = _f.slots_per_epoch.unwrap_or(default.slots_per_epoch);
+
+        // EpochSchedule::custom panics on fewer slots
+        if slots_per_epoch < MINIMUM_SLOTS_PER_EPOCH {
+            return Err(Error::InvalidValue {
+                field: "fuzz.sysvars.epoch_schedule.slots_per_epoch",
+                reason: format!(
+                    "has to be at least {}, got {}",
+                    MINIMUM_SLOTS_PER_EPOCH, slots_per_epoch
+                ),
+            });
+        }
+
+        Ok(EpochSchedule::custom(
+            slots_per_epoch,
+            _f.leader_schedule_slot_offset.unwrap_or(slots_per_epoch),
+            _f.warmup.unwrap_or(default.warmup),
+        ))
+    }
+}
+
+/// Rejects an invalid epoch schedule while the config is loaded, not once the fuzzer starts
+fn deserialize_epoch_schedule<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<FuzzEpochSchedule>, D::Error> {
+    let epoch_schedule = Option::<FuzzEpochSchedule>::deserialize(deserializer)?;
+    if let Some(epoch_schedule) = &epoch_schedule {
+        EpochSchedule::try_from(epoch_schedule).map_err(serde::de::Error::custom)?;
+    }
+    Ok(epoch_schedule)
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -126,3 +298,142 @@ pub struct AccountRaw {
     #[serde(rename = "rentEpoch")]
     pub rent_epoch: u64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stateful() {
+        let fuzz: Fuzz = toml::from_str(
+            "
+            [stateful]
+            reset_interval = 50
+            ",
+        )
+        .unwrap();
+
+        let stateful = fuzz.stateful.unwrap();
+        assert_eq!(stateful.get_reset_interval(), 50);
+        assert_eq!(
+            stateful.get_crash_chains_dir(),
+            PathBuf::from("fuzzing/crash_chains")
+        );
+
+        let stateful = FuzzStateful {
+            reset_interval: Some(0),
+            crash_chains_dir: None,
+        };
+        assert_eq!(stateful.get_reset_interval(), 1);
+    }
+    #[test]
+    fn test_account_storages() {
+        let fuzz: Fuzz = toml::from_str(
+            "
+            [account_storages.author]
+            max_accounts = 5
+            reuse_probability = 80
+
+            [account_storages.mint]
+            max_accounts = 1
+            ",
+        )
+        .unwrap();
+
+        assert_eq!(fuzz.get_accounts_seed(), 0);
+
+        let storages = fuzz.account_storages.unwrap();
+        let author = &storages["author"];
+        assert_eq!(author.max_accounts, Some(5));
+        assert_eq!(author.reuse_probability, Some(80));
+        let mint = &storages["mint"];
+        assert_eq!(mint.max_accounts, Some(1));
+        assert_eq!(mint.reuse_probability, None);
+    }
+    #[test]
+    fn test_sysvars_defaults() {
+        let sysvars = FuzzSysvars::default();
+
+        assert_eq!(sysvars.get_clock(), Clock::default());
+        assert_eq!(sysvars.get_rent(), Rent::default());
+        assert_eq!(sysvars.get_epoch_schedule(), EpochSchedule::default());
+        assert_eq!(sysvars.get_last_restart_slot(), None);
+    }
+    #[test]
+    fn test_sysvars_clock() {
+        let fuzz: Fuzz = toml::from_str(
+            "
+            [sysvars.clock]
+            slot = 100
+            epoch = 2
+            unix_timestamp = 1700000000
+            ",
+        )
+        .unwrap();
+
+        let clock = fuzz.sysvars.unwrap().get_clock();
+        assert_eq!(clock.slot, 100);
+        assert_eq!(clock.epoch, 2);
+        assert_eq!(clock.unix_timestamp, 1700000000);
+        assert_eq!(clock.epoch_start_timestamp, 0);
+        assert_eq!(clock.leader_schedule_epoch, 0);
+    }
+    #[test]
+    fn test_sysvars_rent() {
+        let fuzz: Fuzz = toml::from_str(
+            "
+            [sysvars.rent]
+            lamports_per_byte_year = 1
+            ",
+        )
+        .unwrap();
+
+        let rent = fuzz.sysvars.unwrap().get_rent();
+        assert_eq!(rent.lamports_per_byte_year, 1);
+        assert_eq!(
+            rent.exemption_threshold,
+            Rent::default().exemption_threshold
+        );
+        assert_eq!(rent.burn_percent, Rent::default().burn_percent);
+    }
+    #[test]
+    fn test_sysvars_epoch_schedule() {
+        let fuzz: Fuzz = toml::from_str(
+            "
+            [sysvars]
+            last_restart_slot = 5
+
+            [sysvars.epoch_schedule]
+            slots_per_epoch = 64
+            warmup = false
+            ",
+        )
+        .unwrap();
+
+        let sysvars = fuzz.sysvars.unwrap();
+        assert_eq!(
+            sysvars.get_epoch_schedule(),
+            EpochSchedule::custom(64, 64, false)
+        );
+        assert_eq!(
+            sysvars.get_last_restart_slot(),
+            Some(LastRestartSlot {
+                last_restart_slot: 5
+            })
+        );
+    }
+    #[test]
+    fn test_sysvars_invalid_epoch_schedule() {
+        let err = toml::from_str::<Fuzz>(
+            "
+            [sysvars.epoch_schedule]
+            slots_per_epoch = 10
+            ",
+        )
+        .unwrap_err();
+
+        assert!(err
+            .to_string()
+            .contains("invalid value of `fuzz.sysvars.epoch_schedule.slots_per_epoch`: has to be at least 32, got 10"));
+    }
+}
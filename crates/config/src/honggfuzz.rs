@@ -3,7 +3,7 @@ use serde::Deserialize;
 use crate::{
     argument::{Argument, EnvironmentVariable},
     constants::*,
-    utils::arg_to_string,
+    utils::{arg_to_string, resolve_path},
 };
 
 #[derive(Default, Debug, Deserialize, Clone)]
@@ -73,6 +73,11 @@ pub struct HonggFuzz {
     /// -u
     /// --save_all
     pub save_all: Option<bool>,
+    #[serde(default)]
+    /// Dictionary file, defaults to the dictionary generated with the fuzz test
+    /// -w
+    /// --dict
+    pub dictionary: Option<String>,
 }
 
 impl HonggFuzz {
@@ -164,6 +169,16 @@ impl HonggFuzz {
             }
         })
     }
+    pub fn get_dictionary(&self) -> Option<Argument> {
+        // dictionary
+        self.dictionary
+            .as_ref()
+            .filter(|dictionary| !dictionary.is_empty())
+            .map(|dictionary| {
+                let full_path = resolve_path(dictionary);
+                Argument::new("-w", "--dict", full_path.to_str())
+            })
+    }
     pub fn get_cargo_target_dir(&self) -> EnvironmentVariable {
         // cargo_target_dir
         if let Some(cargo_target_dir) = &self.cargo_target_dir {
@@ -228,6 +243,9 @@ impl HonggFuzz {
         if let Some(save_all) = self.get_save_all() {
             result.extend(arg_to_string(&save_all));
         }
+        if let Some(dictionary) = self.get_dictionary() {
+            result.extend(arg_to_string(&dictionary));
+        }
         result
     }
 }
@@ -253,6 +271,7 @@ mod tests {
                 run_time: None,
                 max_file_size: None,
                 save_all: None,
+                dictionary: None,
             }
         }
     }
@@ -386,6 +405,16 @@ mod tests {
         assert_eq!(arg, vec!["-u", ""]);
     }
     #[test]
+    fn test_dictionary() {
+        let mut honggfuzz = HonggFuzz::clean();
+
+        // dictionary
+        honggfuzz.dictionary = Some("/foo/bar/fuzz.dict".to_string());
+
+        let arg = honggfuzz.get_collect_fuzz_args();
+        assert_eq!(arg, vec!["-w", "/foo/bar/fuzz.dict"]);
+    }
+    #[test]
     fn test_cargo_target_dir() {
         let mut honggfuzz = HonggFuzz::clean();
 
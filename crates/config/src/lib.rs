@@ -12,7 +12,7 @@ use honggfuzz::*;
 pub mod utils;
 
 use serde::Deserialize;
-use std::{fs, io};
+use std::{collections::HashMap, fs, io};
 use thiserror::Error;
 use utils::{discover_root, resolve_path};
 
@@ -26,6 +26,8 @@ pub enum Error {
     Io(#[from] io::Error),
     #[error("{0:?}")]
     Toml(#[from] toml::de::Error),
+    #[error("invalid value of `{field}`: {reason}")]
+    InvalidValue { field: &'static str, reason: String },
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -46,8 +48,8 @@ impl TridentConfig {
         let root = discover_root().expect("failed to find the root folder");
         let s = fs::read_to_string(root.join(TRIDENT_TOML).as_path())
             .expect("failed to read the Trident config file");
-        let _config: TridentConfig =
-            toml::from_str(&s).expect("failed to parse the Trident config file");
+        let _config: TridentConfig = toml::from_str(&s)
+            .unwrap_or_else(|err| panic!("failed to parse the Trident config file: {}", err));
         _config
     }
 
@@ -148,6 +150,30 @@ impl TridentConfig {
             .map(|fuzz| fuzz.get_allow_duplicate_txs())
             .unwrap_or_default()
     }
+    pub fn get_snapshot_pre_ixs(&self) -> bool {
+        self.fuzz
+            .as_ref()
+            .map(|fuzz| fuzz.get_snapshot_pre_ixs())
+            .unwrap_or_default()
+    }
+    pub fn get_accounts_seed(&self) -> u64 {
+        self.fuzz
+            .as_ref()
+            .map(|fuzz| fuzz.get_accounts_seed())
+            .unwrap_or_default()
+    }
+    pub fn get_account_mutations(&self) -> bool {
+        self.fuzz
+            .as_ref()
+            .map(|fuzz| fuzz.get_account_mutations())
+            .unwrap_or_default()
+    }
+    pub fn get_data_mutations(&self) -> bool {
+        self.fuzz
+            .as_ref()
+            .map(|fuzz| fuzz.get_data_mutations())
+            .unwrap_or_default()
+    }
 
     pub fn programs(&self) -> Vec<FuzzProgram> {
         self.fuzz
@@ -161,6 +187,29 @@ impl TridentConfig {
             })
             .unwrap_or_default()
     }
+    pub fn stateful(&self) -> Option<FuzzStateful> {
+        self.fuzz.as_ref().and_then(|fuzz| fuzz.stateful.clone())
+    }
+    pub fn sysvars(&self) -> FuzzSysvars {
+        self.fuzz
+            .as_ref()
+            .and_then(|fuzz| fuzz.sysvars.clone())
+            .unwrap_or_default()
+    }
+    pub fn account_storages(&self) -> HashMap<String, FuzzAccountStorage> {
+        self.fuzz
+            .as_ref()
+            .and_then(|fuzz| fuzz.account_storages.clone())
+            .unwrap_or_default()
+    }
+    /// Settings of `[fuzz.account_storages.<name>]`, all unset if the storage is not configured
+    pub fn account_storage(&self, name: &str) -> FuzzAccountStorage {
+        self.fuzz
+            .as_ref()
+            .and_then(|fuzz| fuzz.account_storages.as_ref())
+            .and_then(|account_storages| account_storages.get(name).cloned())
+            .unwrap_or_default()
+    }
     pub fn accounts(&self) -> Vec<FuzzAccount> {
         self.fuzz
             .as_ref()
@@ -0,0 +1,303 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::parse::ParseStream;
+use syn::{
+    parse_quote, Data, DeriveInput, Expr, ExprLit, ExprRange, ExprUnary, Fields, Ident, Lit,
+    RangeLimits, Token, UnOp,
+};
+
+/// Turns the `#[fuzz(...)]` field attributes into `#[arbitrary(with = ...)]` attributes,
+/// so the values generated by the `Arbitrary` derive respect the constraints.
+/// Has to be placed before `#[derive(Arbitrary)]`.
+///
+/// - `#[fuzz(range = 1..=100)]` - integer in the range
+/// - `#[fuzz(one_of = [1, 10, 100])]` - one of the values
+/// - `#[fuzz(len = 0..32)]` - vector or string with the length in the range
+///
+/// Empty ranges and empty lists of values are rejected, bounds which are not literals
+/// are checked once the values are generated.
+#[proc_macro_attribute]
+pub fn fuzz_constraints(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    match expand(item.into()) {
+        Ok(output) => TokenStream::from(output),
+        Err(e) => TokenStream::from(e.to_compile_error()),
+    }
+}
+
+fn expand(item: TokenStream2) -> syn::Result<TokenStream2> {
+    let mut input: DeriveInput = syn::parse2(item)?;
+
+    match &mut input.data {
+        Data::Struct(struct_data) => process_fields(&mut struct_data.fields)?,
+        Data::Enum(enum_data) => enum_data
+            .variants
+            .iter_mut()
+            .try_for_each(|variant| process_fields(&mut variant.fields))?,
+        Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "fuzz_constraints can only be used on structs and enums",
+            ))
+        }
+    };
+
+    Ok(quote! { #input })
+}
+
+fn process_fields(fields: &mut Fields) -> syn::Result<()> {
+    for field in fields.iter_mut() {
+        let mut constraint = None;
+        let mut attrs = Vec::with_capacity(field.attrs.len());
+
+        for attr in field.attrs.drain(..) {
+            if !attr.path.is_ident("fuzz") {
+                attrs.push(attr);
+                continue;
+            }
+            if constraint.is_some() {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "only one #[fuzz] attribute can be used on a field",
+                ));
+            }
+            constraint = Some(attr.parse_args_with(parse_constraint)?);
+        }
+
+        if let Some(constraint) = constraint {
+            attrs.push(parse_quote!(#[arbitrary(with = #constraint)]));
+        }
+        field.attrs = attrs;
+    }
+    Ok(())
+}
+
+fn parse_constraint(input: ParseStream) -> syn::Result<TokenStream2> {
+    let name: Ident = input.parse()?;
+    input.parse::<Token![=]>()?;
+    let value: Expr = input.parse()?;
+
+    match name.to_string().as_str() {
+        "range" => {
+            let range = inclusive_range(&value)?;
+            Ok(quote!(trident_fuzz::constraints::in_range(#range)))
+        }
+        "one_of" => match &value {
+            Expr::Array(array) if array.elems.is_empty() => Err(syn::Error::new_spanned(
+                value,
+                "expected at least one value, e.g. `[1, 10, 100]`",
+            )),
+            Expr::Array(array) => {
+                let values = &array.elems;
+                Ok(quote!(trident_fuzz::constraints::one_of(vec![#values])))
+            }
+            _ => Err(syn::Error::new_spanned(
+                value,
+                "expected an array of values, e.g. `[1, 10, 100]`",
+            )),
+        },
+        "len" => {
+            if let Expr::Range(ExprRange {
+                from: Some(from), ..
+            }) = &value
+            {
+                if matches!(int_literal(from), Some(from) if from < 0) {
+                    return Err(syn::Error::new_spanned(
+                        from,
+                        "the length cannot be negative",
+                    ));
+                }
+            }
+            let range = inclusive_range(&value)?;
+            Ok(quote!(trident_fuzz::constraints::with_len(#range)))
+        }
+        _ => Err(syn::Error::new_spanned(
+            name,
+            "unknown constraint, expected `range`, `one_of` or `len`",
+        )),
+    }
+}
+
+// Both bounds are required, the half-open range is converted to the inclusive one
+fn inclusive_range(value: &Expr) -> syn::Result<TokenStream2> {
+    let (from, to, limits) = match value {
+        Expr::Range(ExprRange {
+            from: Some(from),
+            to: Some(to),
+            limits,
+            ..
+        }) => (from, to, limits),
+        _ => {
+            return Err(syn::Error::new_spanned(
+                value,
+                "expected a range with both bounds, e.g. `1..=100`",
+            ))
+        }
+    };
+
+    let literals = int_literal(from).zip(int_literal(to));
+    let is_empty = match (literals, limits) {
+        (Some((from, to)), RangeLimits::Closed(_)) => from > to,
+        (Some((from, to)), RangeLimits::HalfOpen(_)) => from >= to,
+        // checked once the values are generated
+        (None, _) => false,
+    };
+    if is_empty {
+        return Err(syn::Error::new_spanned(
+            value,
+            "the range is empty, the start has to be lower than the end",
+        ));
+    }
+
+    match limits {
+        RangeLimits::Closed(_) => Ok(quote!((#from)..=(#to))),
+        RangeLimits::HalfOpen(_) if literals.is_some() => Ok(quote!((#from)..=((#to) - 1))),
+        // the end is decremented only if the range is not empty, so it cannot underflow
+        RangeLimits::HalfOpen(_) => Ok(quote!({
+            let (from, to) = (#from, #to);
+            assert!(from < to, "#[fuzz] requires a non-empty range");
+            from..=(to - 1)
+        })),
+    }
+}
+
+// Value of an integer literal, possibly negative
+fn int_literal(expr: &Expr) -> Option<i128> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(int), ..
+        }) => int.base10_parse().ok(),
+        Expr::Unary(ExprUnary {
+            op: UnOp::Neg(_),
+            expr,
+            ..
+        }) => int_literal(expr).map(|value| -value),
+        Expr::Paren(paren) => int_literal(&paren.expr),
+        Expr::Group(group) => int_literal(&group.expr),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand_field(field: TokenStream2) -> syn::Result<String> {
+        expand(quote! {
+            struct Data {
+                #field
+                amount: u64,
+            }
+        })
+        .map(|output| output.to_string())
+    }
+
+    fn expand_error(field: TokenStream2) -> String {
+        expand_field(field).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn test_constraints_expansion() {
+        let expected = |attribute: TokenStream2| {
+            quote! {
+                struct Data {
+                    #attribute
+                    amount: u64,
+                }
+            }
+            .to_string()
+        };
+
+        assert_eq!(
+            expand_field(quote!(#[fuzz(range = 1..=100)])).unwrap(),
+            expected(quote!(#[arbitrary(with = trident_fuzz::constraints::in_range((1)..=(100)))]))
+        );
+        assert_eq!(
+            expand_field(quote!(#[fuzz(range = -5..0)])).unwrap(),
+            expected(
+                quote!(#[arbitrary(with = trident_fuzz::constraints::in_range((-5)..=((0) - 1)))])
+            )
+        );
+        assert_eq!(
+            expand_field(quote!(#[fuzz(range = 0..LIMIT)])).unwrap(),
+            expected(
+                quote!(#[arbitrary(with = trident_fuzz::constraints::in_range({
+                    let (from, to) = (0, LIMIT);
+                    assert!(from < to, "#[fuzz] requires a non-empty range");
+                    from..=(to - 1)
+                }))])
+            )
+        );
+        assert_eq!(
+            expand_field(quote!(#[fuzz(one_of = [1, 10])])).unwrap(),
+            expected(quote!(#[arbitrary(with = trident_fuzz::constraints::one_of(vec![1, 10]))]))
+        );
+        assert_eq!(
+            expand_field(quote!(#[fuzz(len = 0..=MAX_LEN)])).unwrap(),
+            expected(
+                quote!(#[arbitrary(with = trident_fuzz::constraints::with_len((0)..=(MAX_LEN)))])
+            )
+        );
+    }
+
+    #[test]
+    fn test_constraints_in_enums() {
+        let output = expand(quote! {
+            enum Action {
+                Deposit {
+                    #[fuzz(range = 1..10)]
+                    amount: u64,
+                },
+                Withdraw(#[fuzz(one_of = [1])] u64),
+            }
+        })
+        .unwrap()
+        .to_string();
+
+        assert!(output.contains("in_range"));
+        assert!(output.contains("one_of (vec ! [1])"));
+        assert!(!output.contains("# [fuzz"));
+    }
+
+    #[test]
+    fn test_empty_ranges_are_rejected() {
+        let empty = "the range is empty, the start has to be lower than the end";
+        assert_eq!(expand_error(quote!(#[fuzz(range = 0..0)])), empty);
+        assert_eq!(expand_error(quote!(#[fuzz(range = 7..7)])), empty);
+        assert_eq!(expand_error(quote!(#[fuzz(range = 10..=1)])), empty);
+        assert_eq!(expand_error(quote!(#[fuzz(range = -1..-5)])), empty);
+        assert_eq!(expand_error(quote!(#[fuzz(len = 32..0)])), empty);
+
+        // single values are not empty
+        assert!(expand_field(quote!(#[fuzz(range = 7..=7)])).is_ok());
+        assert!(expand_field(quote!(#[fuzz(range = 7..8)])).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_constraints_are_rejected() {
+        assert_eq!(
+            expand_error(quote!(#[fuzz(one_of = [])])),
+            "expected at least one value, e.g. `[1, 10, 100]`"
+        );
+        assert_eq!(
+            expand_error(quote!(#[fuzz(one_of = 5)])),
+            "expected an array of values, e.g. `[1, 10, 100]`"
+        );
+        assert_eq!(
+            expand_error(quote!(#[fuzz(range = 1..)])),
+            "expected a range with both bounds, e.g. `1..=100`"
+        );
+        assert_eq!(
+            expand_error(quote!(#[fuzz(len = -1..5)])),
+            "the length cannot be negative"
+        );
+        assert_eq!(
+            expand_error(quote!(#[fuzz(size = 1..5)])),
+            "unknown constraint, expected `range`, `one_of` or `len`"
+        );
+        assert_eq!(
+            expand_error(quote!(#[fuzz(range = 1..5)] #[fuzz(len = 1..5)])),
+            "only one #[fuzz] attribute can be used on a field"
+        );
+    }
+}
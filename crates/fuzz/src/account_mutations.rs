@@ -0,0 +1,129 @@
+use arbitrary::Arbitrary;
+use solana_sdk::account::WritableAccount;
+use solana_sdk::hash::hashv;
+use solana_sdk::instruction::AccountMeta;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signer::Signer;
+
+use crate::fuzz_client::FuzzClient;
+
+/// Mutation of the account metas of an Instruction. The indexes are taken modulo
+/// the number of account metas.
+///
+/// If the Instruction still succeeds after the mutation, the program might be missing
+/// an account constraint.
+#[derive(Arbitrary, Debug, Clone, PartialEq, Eq)]
+pub enum AccountMutation {
+    /// Swap the addresses of two accounts, the flags stay in place
+    Swap { first: u8, second: u8 },
+    /// Substitute the account with a look-alike account with identical data but a different owner
+    LookAlike { index: u8 },
+    /// Use the address of the `source` account also in place of the `target` account
+    Duplicate { source: u8, target: u8 },
+    /// Remove the writable privilege of the account, from all of its account metas.
+    /// Only privileges are removed, as instructions succeed with additional privileges.
+    RemoveWritable { index: u8 },
+    /// Remove the signer privilege of the account, from all of its account metas
+    RemoveSigner { index: u8 },
+}
+
+impl AccountMutation {
+    /// Apply the mutation to the account metas, look-alike accounts are created in the client
+    pub fn apply(&self, client: &mut impl FuzzClient, account_metas: &mut [AccountMeta]) {
+        if account_metas.is_empty() {
+            return;
+        }
+        let len = account_metas.len();
+        let index = |i: &u8| usize::from(*i) % len;
+
+        match self {
+            AccountMutation::Swap { first, second } => {
+                let (first, second) = (index(first), index(second));
+                let first_pubkey = account_metas[first].pubkey;
+                account_metas[first].pubkey = account_metas[second].pubkey;
+                account_metas[second].pubkey = first_pubkey;
+            }
+            AccountMutation::LookAlike { index: i } => {
+                let i = index(i);
+                account_metas[i].pubkey = look_alike_account(client, &account_metas[i].pubkey);
+            }
+            AccountMutation::Duplicate { source, target } => {
+                let (source, target) = (index(source), index(target));
+                account_metas[target].pubkey = account_metas[source].pubkey;
+            }
+            // the fee payer is always a writable signer of the transaction
+            AccountMutation::RemoveWritable { index: i } => {
+                let pubkey = account_metas[index(i)].pubkey;
+                if pubkey != client.payer().pubkey() {
+                    account_metas
+                        .iter_mut()
+                        .filter(|account_meta| account_meta.pubkey == pubkey)
+                        .for_each(|account_meta| account_meta.is_writable = false);
+                }
+            }
+            AccountMutation::RemoveSigner { index: i } => {
+                let pubkey = account_metas[index(i)].pubkey;
+                if pubkey != client.payer().pubkey() {
+                    account_metas
+                        .iter_mut()
+                        .filter(|account_meta| account_meta.pubkey == pubkey)
+                        .for_each(|account_meta| account_meta.is_signer = false);
+                }
+            }
+        }
+    }
+}
+
+// The look-alike address and owner are derived from the original, so the mutation is reproducible
+fn look_alike_account(client: &mut impl FuzzClient, original: &Pubkey) -> Pubkey {
+    let mut account = client.get_account(original);
+
+    let address =
+        Pubkey::new_from_array(hashv(&[b"trident-look-alike", original.as_ref()]).to_bytes());
+    let owner =
+        Pubkey::new_from_array(hashv(&[b"trident-look-alike-owner", original.as_ref()]).to_bytes());
+
+    account.set_owner(owner);
+    client.set_account_custom(&address, &account);
+    address
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fuzz_client_impl::TridentClient;
+    use trident_config::TridentConfig;
+
+    fn metas(client: &TridentClient, other: Pubkey) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new(client.payer().pubkey(), true),
+            AccountMeta::new(other, true),
+            AccountMeta::new_readonly(other, false),
+        ]
+    }
+
+    #[test]
+    fn test_privileges_are_only_removed() {
+        let config: TridentConfig = toml::from_str("[fuzz]").unwrap();
+        let mut client = TridentClient::new_client(&[], &config);
+        let other = Pubkey::new_unique();
+
+        // privileges are removed from all account metas of the account
+        let mut account_metas = metas(&client, other);
+        AccountMutation::RemoveSigner { index: 2 }.apply(&mut client, &mut account_metas);
+        AccountMutation::RemoveWritable { index: 1 }.apply(&mut client, &mut account_metas);
+        assert_eq!(
+            account_metas[1..],
+            [
+                AccountMeta::new_readonly(other, false),
+                AccountMeta::new_readonly(other, false)
+            ]
+        );
+
+        // the fee payer keeps its privileges
+        let mut account_metas = metas(&client, other);
+        AccountMutation::RemoveSigner { index: 0 }.apply(&mut client, &mut account_metas);
+        AccountMutation::RemoveWritable { index: 3 }.apply(&mut client, &mut account_metas);
+        assert_eq!(account_metas, metas(&client, other));
+    }
+}
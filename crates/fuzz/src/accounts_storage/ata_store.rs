@@ -0,0 +1,132 @@
+use solana_sdk::{
+    account::AccountSharedData, program_option::COption, program_pack::Pack, pubkey::Pubkey,
+    rent::Rent,
+};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+
+use crate::{fuzz_client::FuzzClient, AccountId};
+
+use super::{
+    token_2022::{self, AccountExtension},
+    AccountsStorage,
+};
+
+#[derive(Clone)]
+pub struct AtaStore {
+    pub pubkey: Pubkey,
+    pub wallet: Pubkey,
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+}
+impl AtaStore {
+    pub fn pubkey(&self) -> Pubkey {
+        self.pubkey
+    }
+}
+
+impl From<Pubkey> for AtaStore {
+    fn from(pubkey: Pubkey) -> Self {
+        AtaStore {
+            pubkey,
+            // Note: This creates empty wallet / mint association
+            wallet: Pubkey::default(),
+            mint: Pubkey::default(),
+            token_program: spl_token::id(),
+        }
+    }
+}
+
+impl AccountsStorage<AtaStore> {
+    /// Public keys of all stored accounts
+    pub fn pubkeys(&self) -> Vec<Pubkey> {
+        self.sorted()
+            .into_iter()
+            .map(|account| account.pubkey())
+            .collect()
+    }
+    /// Get Initialized or Create new Associated Token Account
+    ///
+    /// The address is the canonical Associated Token Account of the wallet and mint for the
+    /// given token program, both `spl_token` and `spl_token_2022` are supported.
+    pub fn get_or_create_account(
+        &mut self,
+        account_id: AccountId,
+        client: &mut impl FuzzClient,
+        wallet: Pubkey,
+        mint: Pubkey,
+        token_program: &Pubkey,
+        amount: u64,
+    ) -> Pubkey {
+        let key = self.entry(account_id).or_insert_with(|| {
+            let address =
+                get_associated_token_address_with_program_id(&wallet, &mint, token_program);
+
+            let account = if *token_program == spl_token_2022::id() {
+                // the associated token program always initializes the immutable owner
+                token_2022::token_account(
+                    client,
+                    mint,
+                    wallet,
+                    amount,
+                    None,
+                    None,
+                    0,
+                    None,
+                    &[AccountExtension::ImmutableOwner],
+                )
+            } else {
+                let r = client.get_sysvar::<Rent>();
+                let lamports = r.minimum_balance(spl_token::state::Account::LEN);
+
+                let mut account =
+                    AccountSharedData::new(lamports, spl_token::state::Account::LEN, token_program);
+
+                let token_account = spl_token::state::Account {
+                    mint,
+                    owner: wallet,
+                    amount,
+                    delegate: COption::None,
+                    state: spl_token::state::AccountState::Initialized,
+                    is_native: COption::None,
+                    delegated_amount: 0,
+                    close_authority: COption::None,
+                };
+
+                let mut data = vec![0u8; spl_token::state::Account::LEN];
+                spl_token::state::Account::pack(token_account, &mut data[..]).unwrap();
+                account.set_data_from_slice(&data);
+                account
+            };
+
+            client.set_account_custom(&address, &account);
+
+            AtaStore {
+                pubkey: address,
+                wallet,
+                mint,
+                token_program: *token_program,
+            }
+        });
+        key.pubkey
+    }
+    pub fn get(&self, account_id: AccountId) -> Pubkey {
+        match self.stored(account_id) {
+            Some(v) => v.pubkey,
+            None => self.missing_address(account_id, "address"),
+        }
+    }
+    /// Get the wallet the Associated Token Account belongs to
+    pub fn get_wallet(&self, account_id: AccountId) -> Pubkey {
+        match self.stored(account_id) {
+            Some(v) => v.wallet,
+            None => self.missing_address(account_id, "wallet"),
+        }
+    }
+    /// Get the mint of the Associated Token Account
+    pub fn get_mint(&self, account_id: AccountId) -> Pubkey {
+        match self.stored(account_id) {
+            Some(v) => v.mint,
+            None => self.missing_address(account_id, "mint"),
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use borsh::BorshSerialize;
 use solana_sdk::{
     account::{AccountSharedData, WritableAccount},
     clock::{Clock, Epoch},
@@ -15,10 +16,15 @@ use solana_stake_program::stake_state::{
 };
 use solana_vote_program::vote_state::{VoteInit, VoteState, VoteStateVersions};
 use spl_token::state::Mint;
+use std::collections::hash_map::Entry;
 
 use crate::{fuzz_client::FuzzClient, AccountId};
 
-use super::AccountsStorage;
+use super::{
+    derive_keypair, program_account,
+    token_2022::{self, AccountExtension, MintExtension},
+    AccountsStorage, Discriminator,
+};
 
 pub struct KeypairStore {
     pub keypair: Keypair,
@@ -30,8 +36,22 @@ impl KeypairStore {
     }
 }
 
-// TODO Add an easy way to limit the number of created accounts
+impl Clone for KeypairStore {
+    fn clone(&self) -> Self {
+        KeypairStore {
+            keypair: self.keypair.insecure_clone(),
+        }
+    }
+}
+
 impl AccountsStorage<KeypairStore> {
+    /// Public keys of all stored accounts
+    pub fn pubkeys(&self) -> Vec<Pubkey> {
+        self.sorted()
+            .into_iter()
+            .map(|account| account.pubkey())
+            .collect()
+    }
     /// Get Initialized or Create new Solana Wallet
     pub fn get_or_create_account(
         &mut self,
@@ -39,8 +59,9 @@ impl AccountsStorage<KeypairStore> {
         client: &mut impl FuzzClient,
         lamports: u64,
     ) -> Keypair {
-        let key = self.accounts.entry(account_id).or_insert_with(|| {
-            let keypair = Keypair::new();
+        let (account_id, entry, (accounts_seed, name)) = self.keypair_entry(account_id);
+        let key = entry.or_insert_with(|| {
+            let keypair = derive_keypair(accounts_seed, name, account_id);
 
             let empty_account =
                 AccountSharedData::new(lamports, 0, &solana_sdk::system_program::ID);
@@ -51,6 +72,48 @@ impl AccountsStorage<KeypairStore> {
         });
         key.keypair.insecure_clone()
     }
+    /// Get Initialized or Create new Program Account
+    ///
+    /// The account data are the discriminator followed by the serialized `data`,
+    /// the account is rent-exempt and owned by the `owner`.
+    pub fn get_or_create_program_account<T: BorshSerialize + Discriminator>(
+        &mut self,
+        account_id: AccountId,
+        client: &mut impl FuzzClient,
+        owner: &Pubkey,
+        data: &T,
+    ) -> Keypair {
+        self.get_or_create_program_account_with_discriminator(
+            account_id,
+            client,
+            owner,
+            T::DISCRIMINATOR,
+            data,
+        )
+    }
+    /// Get Initialized or Create new Program Account with the given `discriminator`,
+    /// e.g. `Account::DISCRIMINATOR` of an Anchor account
+    pub fn get_or_create_program_account_with_discriminator(
+        &mut self,
+        account_id: AccountId,
+        client: &mut impl FuzzClient,
+        owner: &Pubkey,
+        discriminator: &[u8],
+        data: &impl BorshSerialize,
+    ) -> Keypair {
+        let (account_id, entry, (accounts_seed, name)) = self.keypair_entry(account_id);
+        let key = entry.or_insert_with(|| {
+            let program_account_keypair = derive_keypair(accounts_seed, name, account_id);
+
+            let account = program_account(client, owner, discriminator, data);
+            client.set_account_custom(&program_account_keypair.pubkey(), &account);
+
+            KeypairStore {
+                keypair: program_account_keypair.insecure_clone(),
+            }
+        });
+        key.keypair.insecure_clone()
+    }
     /// Get Initialized or Create new Token Account
     #[allow(clippy::too_many_arguments)]
     pub fn get_or_create_token_account(
@@ -65,8 +128,9 @@ impl AccountsStorage<KeypairStore> {
         delegated_amount: u64,
         close_authority: Option<Pubkey>,
     ) -> Keypair {
-        let key = self.accounts.entry(account_id).or_insert_with(|| {
-            let token_account = Keypair::new();
+        let (account_id, entry, (accounts_seed, name)) = self.keypair_entry(account_id);
+        let key = entry.or_insert_with(|| {
+            let token_account = derive_keypair(accounts_seed, name, account_id);
 
             let delegate = match delegate {
                 Some(a) => COption::Some(a),
@@ -83,7 +147,7 @@ impl AccountsStorage<KeypairStore> {
                 _ => COption::None,
             };
 
-            let r = Rent::default();
+            let r = client.get_sysvar::<Rent>();
             let lamports = r.minimum_balance(spl_token::state::Account::LEN);
 
             let mut account =
@@ -122,15 +186,16 @@ impl AccountsStorage<KeypairStore> {
         owner: &Pubkey,
         freeze_authority: Option<Pubkey>,
     ) -> Keypair {
-        let key = self.accounts.entry(account_id).or_insert_with(|| {
-            let mint_account = Keypair::new();
+        let (account_id, entry, (accounts_seed, name)) = self.keypair_entry(account_id);
+        let key = entry.or_insert_with(|| {
+            let mint_account = derive_keypair(accounts_seed, name, account_id);
 
             let authority = match freeze_authority {
                 Some(a) => COption::Some(a),
                 _ => COption::None,
             };
 
-            let r = Rent::default();
+            let r = client.get_sysvar::<Rent>();
             let lamports = r.minimum_balance(Mint::LEN);
 
             let mut account = AccountSharedData::new(lamports, Mint::LEN, &spl_token::id());
@@ -155,6 +220,70 @@ impl AccountsStorage<KeypairStore> {
         });
         key.keypair.insecure_clone()
     }
+    /// Get Initialized or Create new Token 2022 Account
+    ///
+    /// Extensions required by the mint are initialized together with the given extensions.
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_or_create_token_2022_account(
+        &mut self,
+        account_id: AccountId,
+        client: &mut impl FuzzClient,
+        mint: Pubkey,
+        owner: Pubkey,
+        amount: u64,
+        delegate: Option<Pubkey>,
+        is_native: Option<u64>,
+        delegated_amount: u64,
+        close_authority: Option<Pubkey>,
+        extensions: &[AccountExtension],
+    ) -> Keypair {
+        let (account_id, entry, (accounts_seed, name)) = self.keypair_entry(account_id);
+        let key = entry.or_insert_with(|| {
+            let token_account = derive_keypair(accounts_seed, name, account_id);
+
+            let account = token_2022::token_account(
+                client,
+                mint,
+                owner,
+                amount,
+                delegate,
+                is_native,
+                delegated_amount,
+                close_authority,
+                extensions,
+            );
+            client.set_account_custom(&token_account.pubkey(), &account);
+
+            KeypairStore {
+                keypair: token_account.insecure_clone(),
+            }
+        });
+        key.keypair.insecure_clone()
+    }
+    /// Get Initialized or Create new Token 2022 Mint Account with the given extensions
+    pub fn get_or_create_token_2022_mint_account(
+        &mut self,
+        account_id: AccountId,
+        client: &mut impl FuzzClient,
+        decimals: u8,
+        owner: &Pubkey,
+        freeze_authority: Option<Pubkey>,
+        extensions: &[MintExtension],
+    ) -> Keypair {
+        let (account_id, entry, (accounts_seed, name)) = self.keypair_entry(account_id);
+        let key = entry.or_insert_with(|| {
+            let mint_account = derive_keypair(accounts_seed, name, account_id);
+
+            let account =
+                token_2022::mint_account(client, decimals, owner, freeze_authority, extensions);
+            client.set_account_custom(&mint_account.pubkey(), &account);
+
+            KeypairStore {
+                keypair: mint_account.insecure_clone(),
+            }
+        });
+        key.keypair.insecure_clone()
+    }
     /// Get Initialized or Create new Delegated Stake Account
     #[allow(clippy::too_many_arguments)]
     pub fn get_or_create_delegated_account(
@@ -169,10 +298,11 @@ impl AccountsStorage<KeypairStore> {
         deactivation_epoch: Option<Epoch>,
         lockup: Option<Lockup>,
     ) -> Keypair {
-        let key = self.accounts.entry(account_id).or_insert_with(|| {
-            let stake_account = Keypair::new();
+        let (account_id, entry, (accounts_seed, name)) = self.keypair_entry(account_id);
+        let key = entry.or_insert_with(|| {
+            let stake_account = derive_keypair(accounts_seed, name, account_id);
 
-            let rent = Rent::default();
+            let rent = client.get_sysvar::<Rent>();
             let rent_exempt_lamports = rent.minimum_balance(StakeStateV2::size_of());
             let minimum_delegation = LAMPORTS_PER_SOL; // TODO: a way to get minimum delegation with feature set?
             let minimum_lamports = rent_exempt_lamports.saturating_add(minimum_delegation);
@@ -228,10 +358,11 @@ impl AccountsStorage<KeypairStore> {
         withdrawer: Pubkey,
         lockup: Option<Lockup>,
     ) -> Keypair {
-        let key = self.accounts.entry(account_id).or_insert_with(|| {
-            let stake_account = Keypair::new();
+        let (account_id, entry, (accounts_seed, name)) = self.keypair_entry(account_id);
+        let key = entry.or_insert_with(|| {
+            let stake_account = derive_keypair(accounts_seed, name, account_id);
 
-            let rent = Rent::default();
+            let rent = client.get_sysvar::<Rent>();
             let rent_exempt_lamports = rent.minimum_balance(StakeStateV2::size_of());
 
             let stake_state = StakeStateV2::Initialized(Meta {
@@ -266,10 +397,11 @@ impl AccountsStorage<KeypairStore> {
         commission: u8,
         clock: &Clock,
     ) -> Keypair {
-        let key = self.accounts.entry(account_id).or_insert_with(|| {
-            let vote_account = Keypair::new();
+        let (account_id, entry, (accounts_seed, name)) = self.keypair_entry(account_id);
+        let key = entry.or_insert_with(|| {
+            let vote_account = derive_keypair(accounts_seed, name, account_id);
 
-            let rent = Rent::default();
+            let rent = client.get_sysvar::<Rent>();
             let lamports = rent.minimum_balance(VoteState::size_of());
             let mut account = AccountSharedData::new(
                 lamports,
@@ -302,10 +434,26 @@ impl AccountsStorage<KeypairStore> {
         key.keypair.insecure_clone()
     }
 
+    /// Get the stored Keypair, or the Keypair the account would be created with
     pub fn get(&self, account_id: AccountId) -> Keypair {
+        let account_id = self.resolve_account_id(account_id);
         match self.accounts.get(&account_id) {
             Some(v) => v.keypair.insecure_clone(),
-            None => Keypair::new(),
+            None => derive_keypair(self.accounts_seed, &self.name, account_id),
         }
     }
+
+    /// Entry of the resolved AccountId together with the accounts seed and the storage name
+    /// the keypair is derived from
+    fn keypair_entry(
+        &mut self,
+        account_id: AccountId,
+    ) -> (AccountId, Entry<'_, AccountId, KeypairStore>, (u64, &str)) {
+        let account_id = self.resolve_account_id(account_id);
+        (
+            account_id,
+            self.accounts.entry(account_id),
+            (self.accounts_seed, &self.name),
+        )
+    }
 }
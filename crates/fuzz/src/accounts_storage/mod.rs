@@ -1,31 +1,173 @@
 #![allow(dead_code)]
+use std::cell::Cell;
+use std::cell::RefCell;
+use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 
+use crate::remaining_accounts::{pick_address, RemainingAccount, MAX_REMAINING_ACCOUNTS};
 use crate::AccountId;
 
+pub mod ata_store;
 pub mod keypair_store;
 pub mod pda_store;
+pub mod token_2022;
 
 use crate::fuzz_client::FuzzClient;
+pub use ata_store::AtaStore;
+use borsh::BorshSerialize;
 pub use keypair_store::KeypairStore;
 pub use pda_store::PdaStore;
 use solana_sdk::account::AccountSharedData;
+use solana_sdk::hash::hashv;
+use solana_sdk::instruction::AccountMeta;
 use solana_sdk::pubkey::Pubkey;
+use solana_sdk::rent::Rent;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::keypair::keypair_from_seed;
+use solana_sdk::signer::Signer;
+pub use token_2022::AccountExtension;
+pub use token_2022::MintExtension;
+use trident_config::TridentConfig;
 
+/// By default every AccountId has its own account
+pub const DEFAULT_MAX_ACCOUNTS: u16 = AccountId::MAX as u16 + 1;
+/// By default a not yet created account is never replaced by an existing one
+pub const DEFAULT_REUSE_PROBABILITY: u8 = 0;
+
+thread_local! {
+    // number of Account Storages created since the FuzzAccounts were last built,
+    // unnamed storages are named by it
+    static CREATED_STORAGES: Cell<u32> = const { Cell::new(0) };
+}
+
+/// Account Storages of a fuzz test, the FuzzAccounts are built at the start of every iteration.
+///
+/// FuzzAccounts which implement `Default` (e.g. `#[derive(Default)]`) are built with it and do not
+/// use the settings of the Trident Manifest.
+pub trait AccountStorages {
+    /// Builds the Account Storages with the settings of `[fuzz.account_storages]`,
+    /// usually with [`AccountsStorage::from_manifest`]
+    fn new(config: &TridentConfig) -> Self;
+
+    /// Provides accounts captured in the snapshots of every Instruction, in addition to the
+    /// accounts returned by `get_watched_accounts` of the Instruction. By default, none.
+    fn watched_accounts(&self) -> Vec<Pubkey> {
+        vec![]
+    }
+}
+
+impl<T: Default> AccountStorages for T {
+    fn new(_config: &TridentConfig) -> Self {
+        T::default()
+    }
+}
+
+/// Builds the FuzzAccounts of an iteration. Unnamed storages are named by the order in which
+/// they are created, so the order is counted from the start for every FuzzAccounts.
+pub(crate) fn new_fuzz_accounts<V: AccountStorages>(config: &TridentConfig) -> V {
+    CREATED_STORAGES.with(|created| created.set(0));
+    V::new(config)
+}
+
+/// Discriminator of a program account, the account data are prefixed with it.
+///
+/// Generated for the accounts in the IDL, types defined elsewhere (e.g. Anchor accounts
+/// used directly from the program crate) can pass `Account::DISCRIMINATOR` to the
+/// `*_with_discriminator` methods instead.
+pub trait Discriminator {
+    const DISCRIMINATOR: &'static [u8];
+}
+
+/// Pool of known addresses, Public Key instruction arguments without a matching
+/// Account Storage are picked from it
+pub trait AddressPool {
+    fn address_pool(&self) -> Vec<Pubkey>;
+
+    /// Address of the pool selected by the AccountId, derived from the AccountId if the pool is empty
+    fn pick_address(&self, account_id: AccountId) -> Pubkey {
+        pick_address(&self.address_pool(), account_id)
+    }
+
+    /// AccountMetas of the remaining accounts picked from the pool,
+    /// at most `MAX_REMAINING_ACCOUNTS` are used
+    fn remaining_account_metas(&self, remaining_accounts: &[RemainingAccount]) -> Vec<AccountMeta> {
+        let addresses = self.address_pool();
+        remaining_accounts
+            .iter()
+            .take(MAX_REMAINING_ACCOUNTS)
+            .map(|remaining_account| remaining_account.to_account_meta(&addresses))
+            .collect()
+    }
+}
+
+#[derive(Clone)]
 pub struct AccountsStorage<T> {
     accounts: HashMap<AccountId, T>,
-    _max_accounts: u8,
+    max_accounts: u16,
+    reuse_probability: u8,
+    name: String,
+    accounts_seed: u64,
+    // AccountIds resolved so far, an AccountId keeps its account for the whole iteration
+    resolved: RefCell<HashMap<AccountId, AccountId>>,
 }
 
 impl<T> AccountsStorage<T> {
+    /// Storage with at most `max_accounts` accounts, every AccountId is mapped onto this pool
     pub fn new(max_accounts: u8) -> Self {
-        let accounts: HashMap<AccountId, T> = HashMap::new();
+        Self::bounded(u16::from(max_accounts))
+    }
+
+    fn bounded(max_accounts: u16) -> Self {
+        let index = CREATED_STORAGES.with(|created| created.replace(created.get() + 1));
         Self {
-            accounts,
-            _max_accounts: max_accounts,
+            accounts: HashMap::new(),
+            max_accounts: max_accounts.clamp(1, DEFAULT_MAX_ACCOUNTS),
+            reuse_probability: DEFAULT_REUSE_PROBABILITY,
+            // not a valid field name, so it cannot collide with names of named storages
+            name: format!("storage-{}", index),
+            accounts_seed: 0,
+            resolved: RefCell::default(),
         }
     }
 
+    /// Storage named after its field in FuzzAccounts, with the limits set in
+    /// `[fuzz.account_storages.<name>]` and the `accounts_seed` of the Trident Manifest.
+    /// The defaults are used for limits which are not set.
+    pub fn from_manifest(name: &str, config: &TridentConfig) -> Self {
+        let storage = config.account_storage(name);
+        let max_accounts = storage.max_accounts.map_or(DEFAULT_MAX_ACCOUNTS, u16::from);
+
+        Self::bounded(max_accounts)
+            .with_reuse_probability(
+                storage
+                    .reuse_probability
+                    .unwrap_or(DEFAULT_REUSE_PROBABILITY),
+            )
+            .with_name(name)
+            .with_accounts_seed(config.get_accounts_seed())
+    }
+
+    /// Keypairs are derived from the name, the AccountId and the accounts seed, so the addresses
+    /// are the same in every run. Unnamed storages are named by the order in which the storages
+    /// of FuzzAccounts are created, so adding a storage changes the addresses of the later ones.
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    /// Seed the keypairs are derived from together with the name and the AccountId
+    pub fn with_accounts_seed(mut self, accounts_seed: u64) -> Self {
+        self.accounts_seed = accounts_seed;
+        self
+    }
+
+    /// Probability in percent that an AccountId pointing to a not yet created account
+    /// is resolved to an already existing account instead
+    pub fn with_reuse_probability(mut self, reuse_probability: u8) -> Self {
+        self.reuse_probability = reuse_probability.min(100);
+        self
+    }
+
     pub fn set_custom(
         &mut self,
         account_id: AccountId,
@@ -36,14 +178,302 @@ impl<T> AccountsStorage<T> {
         T: From<Pubkey>,
     {
         client.set_account_custom(&address, &account);
-        self.accounts.insert(account_id, T::from(address));
+        let slot = self.slot(account_id);
+        self.resolved.borrow_mut().insert(account_id, slot);
+        self.accounts.insert(slot, T::from(address));
     }
     pub fn is_empty(&self) -> bool {
         self.accounts.is_empty()
     }
+
+    /// Maps the AccountId onto the pool, biased towards the already existing accounts. Once
+    /// resolved, the AccountId is mapped onto the same account until the storage is rebuilt.
+    fn resolve_account_id(&self, account_id: AccountId) -> AccountId {
+        *self
+            .resolved
+            .borrow_mut()
+            .entry(account_id)
+            .or_insert_with(|| self.resolve_new_account_id(account_id))
+    }
+
+    fn resolve_new_account_id(&self, account_id: AccountId) -> AccountId {
+        let slot = self.slot(account_id);
+        if self.accounts.is_empty() || self.accounts.contains_key(&slot) {
+            return slot;
+        }
+
+        // the part of the AccountId which does not select the slot decides about the reuse
+        let round = u16::from(account_id) / self.max_accounts;
+        let rounds = u16::from(AccountId::MAX) / self.max_accounts + 1;
+        if round * 100 / rounds >= u16::from(self.reuse_probability) {
+            return slot;
+        }
+
+        // sorted so the same AccountId always resolves to the same account
+        let mut existing: Vec<AccountId> = self.accounts.keys().copied().collect();
+        existing.sort_unstable();
+        existing[usize::from(round) % existing.len()]
+    }
+
+    fn slot(&self, account_id: AccountId) -> AccountId {
+        // lower than the AccountId, so it fits into it
+        (u16::from(account_id) % self.max_accounts) as AccountId
+    }
+    fn entry(&mut self, account_id: AccountId) -> Entry<'_, AccountId, T> {
+        let account_id = self.resolve_account_id(account_id);
+        self.accounts.entry(account_id)
+    }
+    fn stored(&self, account_id: AccountId) -> Option<&T> {
+        self.accounts.get(&self.resolve_account_id(account_id))
+    }
+    /// Address returned for an AccountId without an account, derived the same as keypairs.
+    /// The `kind` distinguishes addresses of the same AccountId, e.g. of a wallet and a mint.
+    fn missing_address(&self, account_id: AccountId, kind: &str) -> Pubkey {
+        let account_id = self.resolve_account_id(account_id);
+        let name = format!("{}/{}", self.name, kind);
+        derive_keypair(self.accounts_seed, &name, account_id).pubkey()
+    }
+    /// Stored accounts sorted by their AccountId
+    fn sorted(&self) -> Vec<&T> {
+        let mut accounts: Vec<(&AccountId, &T)> = self.accounts.iter().collect();
+        accounts.sort_unstable_by_key(|(account_id, _)| **account_id);
+        accounts.into_iter().map(|(_, account)| account).collect()
+    }
 }
 impl<T> Default for AccountsStorage<T> {
     fn default() -> Self {
-        Self::new(2)
+        Self::bounded(DEFAULT_MAX_ACCOUNTS)
+    }
+}
+
+/// Creates rent-exempt account owned by the `owner` with the `discriminator` and the serialized `data`
+fn program_account(
+    client: &mut impl FuzzClient,
+    owner: &Pubkey,
+    discriminator: &[u8],
+    data: &impl BorshSerialize,
+) -> AccountSharedData {
+    let mut account_data = discriminator.to_vec();
+    data.serialize(&mut account_data)
+        .expect("Program account data serialization failed");
+
+    let r = client.get_sysvar::<Rent>();
+    let lamports = r.minimum_balance(account_data.len());
+
+    let mut account = AccountSharedData::new(lamports, account_data.len(), owner);
+    account.set_data_from_slice(&account_data);
+    account
+}
+
+/// Keypair derived from the accounts seed, the storage name and the AccountId
+pub(crate) fn derive_keypair(accounts_seed: u64, name: &str, account_id: AccountId) -> Keypair {
+    let keypair_seed = hashv(&[
+        b"trident",
+        &accounts_seed.to_le_bytes(),
+        name.as_bytes(),
+        &[account_id],
+    ]);
+    keypair_from_seed(keypair_seed.as_ref()).expect("Keypair derivation failed")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::FuzzingError;
+    use crate::fuzz_client_impl::TridentClient;
+    use crate::snapshot::Snapshot;
+    use solana_sdk::instruction::AccountMeta;
+
+    #[derive(Default)]
+    struct FuzzAccounts {
+        author: AccountsStorage<KeypairStore>,
+        hello_world_account: AccountsStorage<PdaStore>,
+        token_account: AccountsStorage<AtaStore>,
+    }
+
+    // FuzzAccounts as generated by the template
+    struct ManifestFuzzAccounts {
+        author: AccountsStorage<KeypairStore>,
+        vault: AccountsStorage<PdaStore>,
+        treasury: AccountsStorage<KeypairStore>,
+    }
+
+    impl AccountStorages for ManifestFuzzAccounts {
+        fn new(config: &TridentConfig) -> Self {
+            Self {
+                author: AccountsStorage::from_manifest("author", config),
+                vault: AccountsStorage::from_manifest("vault", config),
+                treasury: AccountsStorage::new(1),
+            }
+        }
+    }
+
+    fn config(manifest: &str) -> TridentConfig {
+        toml::from_str(manifest).unwrap()
+    }
+
+    #[test]
+    fn test_unnamed_storages_are_deterministic() {
+        let config = config("[fuzz]");
+        let first: FuzzAccounts = new_fuzz_accounts(&config);
+        let second: FuzzAccounts = new_fuzz_accounts(&config);
+
+        assert_eq!(first.author.get(1).pubkey(), second.author.get(1).pubkey());
+        assert_ne!(first.author.get(0).pubkey(), first.author.get(1).pubkey());
+        assert_eq!(
+            first.hello_world_account.get(1),
+            second.hello_world_account.get(1)
+        );
+        assert_eq!(
+            first.token_account.get_mint(1),
+            second.token_account.get_mint(1)
+        );
+
+        // storages and addresses of the same AccountId are distinct
+        assert_ne!(first.hello_world_account.get(1), first.token_account.get(1));
+        assert_ne!(
+            first.token_account.get_wallet(1),
+            first.token_account.get_mint(1)
+        );
+    }
+
+    #[test]
+    fn test_named_storages_do_not_depend_on_order() {
+        let author = AccountsStorage::<KeypairStore>::default().with_name("author");
+        let _other = AccountsStorage::<KeypairStore>::default();
+        let same_author = AccountsStorage::<KeypairStore>::new(5).with_name("author");
+
+        assert_eq!(author.get(0).pubkey(), same_author.get(0).pubkey());
+        assert_eq!(pick_address(&[], 3), pick_address(&[], 3));
+    }
+
+    #[test]
+    fn test_storages_are_built_with_config() {
+        let limited = config(
+            "[fuzz]\naccounts_seed = 7\n\n[fuzz.account_storages.author]\nmax_accounts = 5\nreuse_probability = 30",
+        );
+        let fuzz_accounts: ManifestFuzzAccounts = new_fuzz_accounts(&limited);
+        assert_eq!(fuzz_accounts.author.max_accounts, 5);
+        assert_eq!(fuzz_accounts.author.reuse_probability, 30);
+        assert_eq!(fuzz_accounts.author.accounts_seed, 7);
+        assert_eq!(fuzz_accounts.vault.max_accounts, DEFAULT_MAX_ACCOUNTS);
+        assert_eq!(fuzz_accounts.vault.accounts_seed, 7);
+
+        // every build uses the config it is given
+        let default: ManifestFuzzAccounts = new_fuzz_accounts(&config("[fuzz]"));
+        assert_eq!(default.author.max_accounts, DEFAULT_MAX_ACCOUNTS);
+        assert_eq!(default.author.reuse_probability, DEFAULT_REUSE_PROBABILITY);
+        assert_ne!(
+            default.author.get(0).pubkey(),
+            fuzz_accounts.author.get(0).pubkey()
+        );
+        // unnamed storages are named the same in every build
+        assert_eq!(
+            default.treasury.get(0).pubkey(),
+            new_fuzz_accounts::<ManifestFuzzAccounts>(&config("[fuzz]"))
+                .treasury
+                .get(0)
+                .pubkey()
+        );
+    }
+
+    #[test]
+    fn test_default_storage_is_unbounded() {
+        let config = config("[fuzz]");
+        let mut client = TridentClient::new_client(&[], &config);
+        let mut storage = AccountsStorage::<AtaStore>::default();
+        let addresses: Vec<Pubkey> = (0..=AccountId::MAX).map(|_| Pubkey::new_unique()).collect();
+
+        // same as before the pools were bounded, every AccountId has its own account
+        for (account_id, address) in (0..=AccountId::MAX).zip(&addresses) {
+            storage.set_custom(
+                account_id,
+                &mut client,
+                *address,
+                AccountSharedData::default(),
+            );
+        }
+        assert_eq!(storage.pubkeys(), addresses);
+        assert_eq!(storage.get(200), addresses[200]);
+    }
+
+    #[test]
+    fn test_resolved_account_id_is_stable() {
+        let config = config("[fuzz]");
+        let mut client = TridentClient::new_client(&[], &config);
+        let mut storage = AccountsStorage::<AtaStore>::new(2).with_reuse_probability(100);
+        let first = Pubkey::new_unique();
+        let second = Pubkey::new_unique();
+
+        storage.set_custom(0, &mut client, first, AccountSharedData::default());
+        // the slot of AccountId 3 is empty, so the existing account is reused
+        assert_eq!(storage.get(3), first);
+
+        // once the slot is taken, AccountId 3 still resolves to the same account
+        storage.set_custom(1, &mut client, second, AccountSharedData::default());
+        assert_eq!(storage.get(1), second);
+        assert_eq!(storage.get(3), first);
+        assert_eq!(storage.pubkeys(), [first, second]);
+    }
+
+    #[derive(BorshSerialize, borsh::BorshDeserialize, Debug, PartialEq)]
+    enum Vault {
+        Empty,
+        Funded { balance: u64 },
+    }
+
+    impl Discriminator for Vault {
+        const DISCRIMINATOR: &'static [u8] = &[1, 2, 3, 4, 5, 6, 7, 8];
+    }
+
+    #[test]
+    fn test_program_accounts() {
+        let config = config("[fuzz]");
+        let mut client = TridentClient::new_client(&[], &config);
+        let mut keypairs = AccountsStorage::<KeypairStore>::new(2);
+        let mut pdas = AccountsStorage::<PdaStore>::new(2);
+        let owner = Pubkey::new_unique();
+
+        let funded = keypairs
+            .get_or_create_program_account(0, &mut client, &owner, &Vault::Funded { balance: 5 })
+            .pubkey();
+        // e.g. Anchor accounts from the program crate pass their discriminator explicitly
+        let empty = pdas.get_or_create_program_account_with_discriminator(
+            0,
+            &mut client,
+            &[b"vault"],
+            &owner,
+            &[9; 8],
+            &Vault::Empty,
+        );
+
+        let mut snapshot = Snapshot::new(&[
+            AccountMeta::new(funded, false),
+            AccountMeta::new(empty, false),
+        ]);
+        snapshot.capture_before(&mut client).unwrap();
+        let accounts = snapshot.get_before();
+
+        assert_eq!(accounts[0].owner(), &owner);
+        assert_eq!(
+            accounts[0].lamports(),
+            client.get_sysvar::<Rent>().minimum_balance(17)
+        );
+        assert_eq!(
+            accounts[0].try_deserialize::<Vault>().unwrap(),
+            Vault::Funded { balance: 5 }
+        );
+
+        assert_eq!(&accounts[1].data()[..8], &[9; 8]);
+        assert!(matches!(
+            accounts[1].try_deserialize::<Vault>(),
+            Err(FuzzingError::InvalidDiscriminator(address)) if address == empty
+        ));
+        assert_eq!(
+            accounts[1]
+                .try_deserialize_with_discriminator::<Vault>(&[9; 8])
+                .unwrap(),
+            Vault::Empty
+        );
     }
 }
@@ -1,6 +1,14 @@
+use borsh::BorshSerialize;
 use solana_sdk::{
-    account::{AccountSharedData, WritableAccount},
-    clock::{Clock, Epoch},
+    account::{AccountSharedData, ReadableAccount, WritableAccount},
+    address_lookup_table::{
+        self,
+        instruction::derive_lookup_table_address,
+        state::{
+            AddressLookupTable, LookupTableMeta, LOOKUP_TABLE_MAX_ADDRESSES, LOOKUP_TABLE_META_SIZE,
+        },
+    },
+    clock::{Clock, Epoch, Slot},
     native_token::LAMPORTS_PER_SOL,
     program_option::COption,
     program_pack::Pack,
@@ -16,8 +24,13 @@ use spl_token::state::Mint;
 
 use crate::{fuzz_client::FuzzClient, AccountId};
 
-use super::AccountsStorage;
+use super::{
+    program_account,
+    token_2022::{self, AccountExtension, MintExtension},
+    AccountsStorage, Discriminator,
+};
 
+#[derive(Clone)]
 pub struct PdaStore {
     pub pubkey: Pubkey,
     pub seeds: (Vec<Vec<u8>>, Pubkey),
@@ -38,6 +51,13 @@ impl From<Pubkey> for PdaStore {
 }
 
 impl AccountsStorage<PdaStore> {
+    /// Public keys of all stored accounts
+    pub fn pubkeys(&self) -> Vec<Pubkey> {
+        self.sorted()
+            .into_iter()
+            .map(|account| account.pubkey())
+            .collect()
+    }
     pub fn get_or_create_account(
         &mut self,
         account_id: AccountId,
@@ -45,6 +65,7 @@ impl AccountsStorage<PdaStore> {
         seeds: &[&[u8]],
         program_id: &Pubkey,
     ) -> Pubkey {
+        let account_id = self.resolve_account_id(account_id);
         match self.accounts.get(&account_id) {
             Some(v) => v.pubkey,
             None => {
@@ -64,11 +85,57 @@ impl AccountsStorage<PdaStore> {
                     client.set_account_custom(&key, &account);
                     key
                 } else {
-                    Pubkey::new_unique()
+                    self.missing_address(account_id, "address")
                 }
             }
         }
     }
+    /// Get Initialized or Create new Program Account
+    ///
+    /// The account data are the discriminator followed by the serialized `data`,
+    /// the account is rent-exempt and owned by the `program_id`.
+    pub fn get_or_create_program_account<T: BorshSerialize + Discriminator>(
+        &mut self,
+        account_id: AccountId,
+        client: &mut impl FuzzClient,
+        seeds: &[&[u8]],
+        program_id: &Pubkey,
+        data: &T,
+    ) -> Pubkey {
+        self.get_or_create_program_account_with_discriminator(
+            account_id,
+            client,
+            seeds,
+            program_id,
+            T::DISCRIMINATOR,
+            data,
+        )
+    }
+    /// Get Initialized or Create new Program Account with the given `discriminator`,
+    /// e.g. `Account::DISCRIMINATOR` of an Anchor account
+    pub fn get_or_create_program_account_with_discriminator(
+        &mut self,
+        account_id: AccountId,
+        client: &mut impl FuzzClient,
+        seeds: &[&[u8]],
+        program_id: &Pubkey,
+        discriminator: &[u8],
+        data: &impl BorshSerialize,
+    ) -> Pubkey {
+        let key = self.entry(account_id).or_insert_with(|| {
+            let address = derive_pda(seeds, program_id);
+
+            let account = program_account(client, program_id, discriminator, data);
+            client.set_account_custom(&address.0, &account);
+
+            let seeds_vec: Vec<_> = seeds.iter().map(|&s| s.to_vec()).collect();
+            PdaStore {
+                pubkey: address.0,
+                seeds: (seeds_vec, *program_id),
+            }
+        });
+        key.pubkey
+    }
     /// Get Initialized or Create new Token Account
     #[allow(clippy::too_many_arguments)]
     pub fn get_or_create_token_account(
@@ -85,7 +152,7 @@ impl AccountsStorage<PdaStore> {
         delegated_amount: u64,
         close_authority: Option<Pubkey>,
     ) -> Pubkey {
-        let key = self.accounts.entry(account_id).or_insert_with(|| {
+        let key = self.entry(account_id).or_insert_with(|| {
             let address = derive_pda(seeds, program_id);
 
             let delegate = match delegate {
@@ -103,7 +170,7 @@ impl AccountsStorage<PdaStore> {
                 _ => COption::None,
             };
 
-            let r = Rent::default();
+            let r = client.get_sysvar::<Rent>();
             let lamports = r.minimum_balance(spl_token::state::Account::LEN);
 
             let mut account =
@@ -146,7 +213,7 @@ impl AccountsStorage<PdaStore> {
         owner: &Pubkey,
         freeze_authority: Option<Pubkey>,
     ) -> Pubkey {
-        let key = self.accounts.entry(account_id).or_insert_with(|| {
+        let key = self.entry(account_id).or_insert_with(|| {
             let address = derive_pda(seeds, program_id);
 
             let authority = match freeze_authority {
@@ -154,7 +221,7 @@ impl AccountsStorage<PdaStore> {
                 _ => COption::None,
             };
 
-            let r = Rent::default();
+            let r = client.get_sysvar::<Rent>();
             let lamports = r.minimum_balance(Mint::LEN);
 
             let mut account = AccountSharedData::new(lamports, Mint::LEN, &spl_token::id());
@@ -181,6 +248,77 @@ impl AccountsStorage<PdaStore> {
         });
         key.pubkey
     }
+    /// Get Initialized or Create new Token 2022 Account
+    ///
+    /// Extensions required by the mint are initialized together with the given extensions.
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_or_create_token_2022_account(
+        &mut self,
+        account_id: AccountId,
+        client: &mut impl FuzzClient,
+        seeds: &[&[u8]],
+        program_id: &Pubkey,
+        mint: Pubkey,
+        owner: Pubkey,
+        amount: u64,
+        delegate: Option<Pubkey>,
+        is_native: Option<u64>,
+        delegated_amount: u64,
+        close_authority: Option<Pubkey>,
+        extensions: &[AccountExtension],
+    ) -> Pubkey {
+        let key = self.entry(account_id).or_insert_with(|| {
+            let address = derive_pda(seeds, program_id);
+
+            let account = token_2022::token_account(
+                client,
+                mint,
+                owner,
+                amount,
+                delegate,
+                is_native,
+                delegated_amount,
+                close_authority,
+                extensions,
+            );
+            client.set_account_custom(&address.0, &account);
+
+            let seeds_vec: Vec<_> = seeds.iter().map(|&s| s.to_vec()).collect();
+            PdaStore {
+                pubkey: address.0,
+                seeds: (seeds_vec, *program_id),
+            }
+        });
+        key.pubkey
+    }
+    /// Get Initialized or Create new Token 2022 Mint Account with the given extensions
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_or_create_token_2022_mint_account(
+        &mut self,
+        account_id: AccountId,
+        client: &mut impl FuzzClient,
+        seeds: &[&[u8]],
+        program_id: &Pubkey,
+        decimals: u8,
+        owner: &Pubkey,
+        freeze_authority: Option<Pubkey>,
+        extensions: &[MintExtension],
+    ) -> Pubkey {
+        let key = self.entry(account_id).or_insert_with(|| {
+            let address = derive_pda(seeds, program_id);
+
+            let account =
+                token_2022::mint_account(client, decimals, owner, freeze_authority, extensions);
+            client.set_account_custom(&address.0, &account);
+
+            let seeds_vec: Vec<_> = seeds.iter().map(|&s| s.to_vec()).collect();
+            PdaStore {
+                pubkey: address.0,
+                seeds: (seeds_vec, *program_id),
+            }
+        });
+        key.pubkey
+    }
     /// Get Initialized or Create new Delegated Stake Account
     #[allow(clippy::too_many_arguments)]
     pub fn get_or_create_delegated_account(
@@ -197,10 +335,10 @@ impl AccountsStorage<PdaStore> {
         deactivation_epoch: Option<Epoch>,
         lockup: Option<Lockup>,
     ) -> Pubkey {
-        let key = self.accounts.entry(account_id).or_insert_with(|| {
+        let key = self.entry(account_id).or_insert_with(|| {
             let address = derive_pda(seeds, program_id);
 
-            let rent = Rent::default();
+            let rent = client.get_sysvar::<Rent>();
             let rent_exempt_lamports = rent.minimum_balance(StakeStateV2::size_of());
             let minimum_delegation = LAMPORTS_PER_SOL; // TODO: a way to get minimum delegation with feature set?
             let minimum_lamports = rent_exempt_lamports.saturating_add(minimum_delegation);
@@ -261,10 +399,10 @@ impl AccountsStorage<PdaStore> {
         withdrawer: Pubkey,
         lockup: Option<Lockup>,
     ) -> Pubkey {
-        let key = self.accounts.entry(account_id).or_insert_with(|| {
+        let key = self.entry(account_id).or_insert_with(|| {
             let address = derive_pda(seeds, program_id);
 
-            let rent = Rent::default();
+            let rent = client.get_sysvar::<Rent>();
             let rent_exempt_lamports = rent.minimum_balance(StakeStateV2::size_of());
 
             let stake_state = StakeStateV2::Initialized(Meta {
@@ -303,10 +441,10 @@ impl AccountsStorage<PdaStore> {
         commission: u8,
         clock: &Clock,
     ) -> Pubkey {
-        let key = self.accounts.entry(account_id).or_insert_with(|| {
+        let key = self.entry(account_id).or_insert_with(|| {
             let address = derive_pda(seeds, program_id);
 
-            let rent = Rent::default();
+            let rent = client.get_sysvar::<Rent>();
             let lamports = rent.minimum_balance(VoteState::size_of());
             let mut account = AccountSharedData::new(
                 lamports,
@@ -340,14 +478,118 @@ impl AccountsStorage<PdaStore> {
         });
         key.pubkey
     }
+    /// Get Initialized or Create new Address Lookup Table Account
+    ///
+    /// The address is derived from the authority and the recent slot, same as in the
+    /// CreateLookupTable instruction. The addresses can be looked up right away, except for the
+    /// last address of a full table in slot 0.
+    pub fn get_or_create_lookup_table_account(
+        &mut self,
+        account_id: AccountId,
+        client: &mut impl FuzzClient,
+        authority: &Pubkey,
+        recent_slot: Slot,
+        addresses: &[Pubkey],
+    ) -> Pubkey {
+        let key = self.entry(account_id).or_insert_with(|| {
+            let address = derive_lookup_table_address(authority, recent_slot);
+
+            let addresses = &addresses[..addresses.len().min(LOOKUP_TABLE_MAX_ADDRESSES)];
+
+            // all addresses count as extended in the previous slot, so they are active right away,
+            // in the first slot only the start index applies, which cannot cover a full table
+            let current_slot = client.get_sysvar::<Clock>().slot;
+            let meta = LookupTableMeta {
+                last_extended_slot: current_slot.saturating_sub(1),
+                last_extended_slot_start_index: u8::try_from(addresses.len()).unwrap_or(u8::MAX),
+                ..LookupTableMeta::new(*authority)
+            };
+
+            let account = lookup_table_account(client, meta, addresses);
+            client.set_account_custom(&address.0, &account);
+
+            PdaStore {
+                pubkey: address.0,
+                seeds: (
+                    vec![
+                        authority.to_bytes().to_vec(),
+                        recent_slot.to_le_bytes().to_vec(),
+                    ],
+                    address_lookup_table::program::ID,
+                ),
+            }
+        });
+        key.pubkey
+    }
+    /// Extend the Address Lookup Table Account with new addresses
+    ///
+    /// Same as with the ExtendLookupTable instruction, the new addresses cannot be looked up
+    /// until the next slot. Addresses over the table capacity are ignored. If no record exists
+    /// for the `account_id`, an address derived from the storage name and the AccountId is returned.
+    pub fn extend_lookup_table_account(
+        &mut self,
+        account_id: AccountId,
+        client: &mut impl FuzzClient,
+        new_addresses: &[Pubkey],
+    ) -> Pubkey {
+        let address = match self.stored(account_id) {
+            Some(v) => v.pubkey,
+            None => return self.missing_address(account_id, "address"),
+        };
+
+        let account = client.get_account(&address);
+        let lookup_table = AddressLookupTable::deserialize(account.data())
+            .expect("PDA Store, account is not an address lookup table");
+
+        let capacity = LOOKUP_TABLE_MAX_ADDRESSES.saturating_sub(lookup_table.addresses.len());
+        if capacity == 0 || new_addresses.is_empty() {
+            return address;
+        }
+
+        let current_slot = client.get_sysvar::<Clock>().slot;
+        let mut meta = lookup_table.meta;
+        if meta.last_extended_slot != current_slot {
+            meta.last_extended_slot = current_slot;
+            meta.last_extended_slot_start_index = lookup_table.addresses.len() as u8;
+        }
+
+        let mut addresses = lookup_table.addresses.into_owned();
+        addresses.extend(new_addresses.iter().take(capacity));
+
+        let account = lookup_table_account(client, meta, &addresses);
+        client.set_account_custom(&address, &account);
+
+        address
+    }
     pub fn get(&self, account_id: AccountId) -> Pubkey {
-        match self.accounts.get(&account_id) {
+        match self.stored(account_id) {
             Some(v) => v.pubkey,
-            None => Pubkey::new_unique(),
+            None => self.missing_address(account_id, "address"),
         }
     }
 }
 
+fn lookup_table_account(
+    client: &mut impl FuzzClient,
+    meta: LookupTableMeta,
+    addresses: &[Pubkey],
+) -> AccountSharedData {
+    // same layout as written by the address lookup table program, the metadata
+    // padded to LOOKUP_TABLE_META_SIZE followed by the addresses
+    let mut data = vec![0u8; LOOKUP_TABLE_META_SIZE];
+    AddressLookupTable::overwrite_meta_data(&mut data, meta)
+        .expect("PDA Store, lookup table metadata serialization failed");
+    data.extend(addresses.iter().flat_map(|address| address.to_bytes()));
+
+    let r = client.get_sysvar::<Rent>();
+    let lamports = r.minimum_balance(data.len());
+
+    let mut account =
+        AccountSharedData::new(lamports, data.len(), &address_lookup_table::program::ID);
+    account.set_data_from_slice(&data);
+    account
+}
+
 fn derive_pda(seeds: &[&[u8]], program_id: &Pubkey) -> (Pubkey, u8) {
     if let Some(address) = Pubkey::try_find_program_address(seeds, program_id) {
         address
@@ -355,3 +597,139 @@ fn derive_pda(seeds: &[&[u8]], program_id: &Pubkey) -> (Pubkey, u8) {
         panic!("PDA Store, seeds did not create valid PDA address")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fuzz_client_impl::TridentClient;
+    use solana_sdk::signer::Signer;
+    use solana_sdk::system_instruction;
+    use solana_sdk::transaction::TransactionError;
+    use trident_config::TridentConfig;
+
+    fn transfer(
+        client: &mut TridentClient,
+        lookup_table: Pubkey,
+        to: Pubkey,
+    ) -> Result<(), TransactionError> {
+        let ix = system_instruction::transfer(&client.payer().pubkey(), &to, LAMPORTS_PER_SOL);
+        client.process_instructions_with_lookup_tables(&[ix], &[lookup_table])
+    }
+
+    #[test]
+    fn test_lookup_table_account_layout() {
+        let config: TridentConfig = toml::from_str("[fuzz]").unwrap();
+        let mut client = TridentClient::new_client(&[], &config);
+        let meta = LookupTableMeta {
+            last_extended_slot: 7,
+            last_extended_slot_start_index: 1,
+            authority: Some(Pubkey::new_unique()),
+            ..LookupTableMeta::default()
+        };
+        let addresses = [Pubkey::new_unique(), Pubkey::new_unique()];
+
+        let account = lookup_table_account(&mut client, meta.clone(), &addresses);
+        assert_eq!(account.owner(), &address_lookup_table::program::ID);
+        assert_eq!(account.data().len(), LOOKUP_TABLE_META_SIZE + 2 * 32);
+
+        let lookup_table = AddressLookupTable::deserialize(account.data()).unwrap();
+        assert_eq!(lookup_table.meta, meta);
+        assert_eq!(lookup_table.addresses.as_ref(), addresses);
+    }
+
+    #[test]
+    fn test_full_lookup_table_is_active() {
+        let config: TridentConfig = toml::from_str("[fuzz]").unwrap();
+        let mut client = TridentClient::new_client(&[], &config);
+        let authority = client.payer().pubkey();
+        let addresses: Vec<Pubkey> = (0..LOOKUP_TABLE_MAX_ADDRESSES)
+            .map(|_| Pubkey::new_unique())
+            .collect();
+
+        client.warp_to_slot(5);
+        let mut storage = AccountsStorage::<PdaStore>::default();
+        let lookup_table =
+            storage.get_or_create_lookup_table_account(0, &mut client, &authority, 0, &addresses);
+
+        let last = addresses[LOOKUP_TABLE_MAX_ADDRESSES - 1];
+        assert_eq!(transfer(&mut client, lookup_table, last), Ok(()));
+        assert_eq!(client.get_account(&last).lamports(), LAMPORTS_PER_SOL);
+    }
+
+    #[test]
+    fn test_full_lookup_table_in_first_slot() {
+        let config: TridentConfig = toml::from_str("[fuzz]").unwrap();
+        let mut client = TridentClient::new_client(&[], &config);
+        let authority = client.payer().pubkey();
+        let addresses: Vec<Pubkey> = (0..LOOKUP_TABLE_MAX_ADDRESSES)
+            .map(|_| Pubkey::new_unique())
+            .collect();
+
+        let mut storage = AccountsStorage::<PdaStore>::default();
+        let lookup_table =
+            storage.get_or_create_lookup_table_account(0, &mut client, &authority, 0, &addresses);
+
+        // the last address of a full table is active from the next slot
+        assert_eq!(transfer(&mut client, lookup_table, addresses[0]), Ok(()));
+        let last = addresses[LOOKUP_TABLE_MAX_ADDRESSES - 1];
+        assert_eq!(
+            transfer(&mut client, lookup_table, last),
+            Err(TransactionError::InvalidAddressLookupTableIndex)
+        );
+        client.warp_to_slot(1);
+        assert_eq!(transfer(&mut client, lookup_table, last), Ok(()));
+    }
+
+    #[test]
+    fn test_extended_addresses_are_active_in_next_slot() {
+        let config: TridentConfig = toml::from_str("[fuzz]").unwrap();
+        let mut client = TridentClient::new_client(&[], &config);
+        let authority = client.payer().pubkey();
+        let (address, new_address) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        client.warp_to_slot(5);
+        let mut storage = AccountsStorage::<PdaStore>::default();
+        let lookup_table =
+            storage.get_or_create_lookup_table_account(0, &mut client, &authority, 0, &[address]);
+        storage.extend_lookup_table_account(0, &mut client, &[new_address]);
+
+        assert_eq!(transfer(&mut client, lookup_table, address), Ok(()));
+        assert_eq!(
+            transfer(&mut client, lookup_table, new_address),
+            Err(TransactionError::InvalidAddressLookupTableIndex)
+        );
+        client.warp_to_slot(6);
+        assert_eq!(transfer(&mut client, lookup_table, new_address), Ok(()));
+        assert_eq!(
+            client.get_account(&new_address).lamports(),
+            LAMPORTS_PER_SOL
+        );
+    }
+
+    #[test]
+    fn test_deactivated_lookup_table() {
+        let config: TridentConfig = toml::from_str("[fuzz]").unwrap();
+        let mut client = TridentClient::new_client(&[], &config);
+        let authority = client.payer().pubkey();
+        let address = Pubkey::new_unique();
+
+        client.warp_to_slot(5);
+        let mut storage = AccountsStorage::<PdaStore>::default();
+        let lookup_table =
+            storage.get_or_create_lookup_table_account(0, &mut client, &authority, 0, &[address]);
+
+        // deactivated in the current slot, so the table is not in the slot hashes yet
+        let account = client.get_account(&lookup_table);
+        let mut table = AddressLookupTable::deserialize(account.data()).unwrap();
+        table.meta.deactivation_slot = 5;
+        let account = lookup_table_account(&mut client, table.meta.clone(), &table.addresses);
+        client.set_account_custom(&lookup_table, &account);
+        assert_eq!(transfer(&mut client, lookup_table, address), Ok(()));
+
+        client.warp_to_slot(5 + 600);
+        assert_eq!(
+            transfer(&mut client, lookup_table, address),
+            Err(TransactionError::AddressLookupTableNotFound)
+        );
+    }
+}
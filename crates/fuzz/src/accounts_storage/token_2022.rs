@@ -0,0 +1,494 @@
+use solana_sdk::{
+    account::{AccountSharedData, ReadableAccount},
+    clock::Clock,
+    program_option::COption,
+    pubkey::Pubkey,
+    rent::Rent,
+};
+use spl_token_2022::{
+    extension::{
+        cpi_guard::CpiGuard, default_account_state::DefaultAccountState,
+        interest_bearing_mint::InterestBearingConfig, memo_transfer::MemoTransfer,
+        metadata_pointer::MetadataPointer, mint_close_authority::MintCloseAuthority,
+        non_transferable::NonTransferable, permanent_delegate::PermanentDelegate,
+        transfer_fee::TransferFeeConfig, transfer_hook::TransferHook, BaseStateWithExtensions,
+        BaseStateWithExtensionsMut, ExtensionType, StateWithExtensions, StateWithExtensionsMut,
+    },
+    state::{Account, AccountState, Mint},
+};
+
+use crate::fuzz_client::FuzzClient;
+
+/// Extension of a Token 2022 Mint Account
+#[derive(Debug, Clone)]
+pub enum MintExtension {
+    TransferFeeConfig {
+        transfer_fee_config_authority: Option<Pubkey>,
+        withdraw_withheld_authority: Option<Pubkey>,
+        transfer_fee_basis_points: u16,
+        maximum_fee: u64,
+    },
+    InterestBearingConfig {
+        rate_authority: Option<Pubkey>,
+        rate: i16,
+    },
+    PermanentDelegate {
+        delegate: Pubkey,
+    },
+    TransferHook {
+        authority: Option<Pubkey>,
+        program_id: Option<Pubkey>,
+    },
+    DefaultAccountState {
+        state: AccountState,
+    },
+    MetadataPointer {
+        authority: Option<Pubkey>,
+        metadata_address: Option<Pubkey>,
+    },
+    MintCloseAuthority {
+        close_authority: Option<Pubkey>,
+    },
+    NonTransferable,
+}
+
+/// Extension of a Token 2022 Token Account
+///
+/// Extensions required by the mint (such as `TransferFeeAmount`) are initialized automatically.
+#[derive(Debug, Clone)]
+pub enum AccountExtension {
+    ImmutableOwner,
+    MemoTransfer {
+        require_incoming_transfer_memos: bool,
+    },
+    CpiGuard {
+        lock_cpi: bool,
+    },
+}
+
+impl MintExtension {
+    fn extension_type(&self) -> ExtensionType {
+        match self {
+            MintExtension::TransferFeeConfig { .. } => ExtensionType::TransferFeeConfig,
+            MintExtension::InterestBearingConfig { .. } => ExtensionType::InterestBearingConfig,
+            MintExtension::PermanentDelegate { .. } => ExtensionType::PermanentDelegate,
+            MintExtension::TransferHook { .. } => ExtensionType::TransferHook,
+            MintExtension::DefaultAccountState { .. } => ExtensionType::DefaultAccountState,
+            MintExtension::MetadataPointer { .. } => ExtensionType::MetadataPointer,
+            MintExtension::MintCloseAuthority { .. } => ExtensionType::MintCloseAuthority,
+            MintExtension::NonTransferable => ExtensionType::NonTransferable,
+        }
+    }
+
+    fn init(&self, state: &mut StateWithExtensionsMut<Mint>, clock: &Clock) {
+        // zero pubkeys are not valid optional pubkeys, they are stored as None
+        match self {
+            MintExtension::TransferFeeConfig {
+                transfer_fee_config_authority,
+                withdraw_withheld_authority,
+                transfer_fee_basis_points,
+                maximum_fee,
+            } => {
+                let extension = state.init_extension::<TransferFeeConfig>(true).unwrap();
+                extension.transfer_fee_config_authority = (*transfer_fee_config_authority)
+                    .try_into()
+                    .unwrap_or_default();
+                extension.withdraw_withheld_authority = (*withdraw_withheld_authority)
+                    .try_into()
+                    .unwrap_or_default();
+                extension.older_transfer_fee.epoch = clock.epoch.into();
+                extension.older_transfer_fee.transfer_fee_basis_points =
+                    (*transfer_fee_basis_points).into();
+                extension.older_transfer_fee.maximum_fee = (*maximum_fee).into();
+                extension.newer_transfer_fee = extension.older_transfer_fee;
+            }
+            MintExtension::InterestBearingConfig {
+                rate_authority,
+                rate,
+            } => {
+                let extension = state.init_extension::<InterestBearingConfig>(true).unwrap();
+                extension.rate_authority = (*rate_authority).try_into().unwrap_or_default();
+                extension.initialization_timestamp = clock.unix_timestamp.into();
+                extension.last_update_timestamp = clock.unix_timestamp.into();
+                extension.pre_update_average_rate = (*rate).into();
+                extension.current_rate = (*rate).into();
+            }
+            MintExtension::PermanentDelegate { delegate } => {
+                let extension = state.init_extension::<PermanentDelegate>(true).unwrap();
+                extension.delegate = Some(*delegate).try_into().unwrap_or_default();
+            }
+            MintExtension::TransferHook {
+                authority,
+                program_id,
+            } => {
+                let extension = state.init_extension::<TransferHook>(true).unwrap();
+                extension.authority = (*authority).try_into().unwrap_or_default();
+                extension.program_id = (*program_id).try_into().unwrap_or_default();
+            }
+            MintExtension::DefaultAccountState {
+                state: account_state,
+            } => {
+                let extension = state.init_extension::<DefaultAccountState>(true).unwrap();
+                extension.state = (*account_state).into();
+            }
+            MintExtension::MetadataPointer {
+                authority,
+                metadata_address,
+            } => {
+                let extension = state.init_extension::<MetadataPointer>(true).unwrap();
+                extension.authority = (*authority).try_into().unwrap_or_default();
+                extension.metadata_address = (*metadata_address).try_into().unwrap_or_default();
+            }
+            MintExtension::MintCloseAuthority { close_authority } => {
+                let extension = state.init_extension::<MintCloseAuthority>(true).unwrap();
+                extension.close_authority = (*close_authority).try_into().unwrap_or_default();
+            }
+            MintExtension::NonTransferable => {
+                state.init_extension::<NonTransferable>(true).unwrap();
+            }
+        }
+    }
+}
+
+impl AccountExtension {
+    fn extension_type(&self) -> ExtensionType {
+        match self {
+            AccountExtension::ImmutableOwner => ExtensionType::ImmutableOwner,
+            AccountExtension::MemoTransfer { .. } => ExtensionType::MemoTransfer,
+            AccountExtension::CpiGuard { .. } => ExtensionType::CpiGuard,
+        }
+    }
+
+    fn init(&self, state: &mut StateWithExtensionsMut<Account>) {
+        match self {
+            AccountExtension::ImmutableOwner => {
+                state
+                    .init_account_extension_from_type(ExtensionType::ImmutableOwner)
+                    .unwrap();
+            }
+            AccountExtension::MemoTransfer {
+                require_incoming_transfer_memos,
+            } => {
+                let extension = state.init_extension::<MemoTransfer>(true).unwrap();
+                extension.require_incoming_transfer_memos =
+                    (*require_incoming_transfer_memos).into();
+            }
+            AccountExtension::CpiGuard { lock_cpi } => {
+                let extension = state.init_extension::<CpiGuard>(true).unwrap();
+                extension.lock_cpi = (*lock_cpi).into();
+            }
+        }
+    }
+}
+
+/// Creates initialized Token 2022 Mint Account with the given extensions
+pub(crate) fn mint_account(
+    client: &mut impl FuzzClient,
+    decimals: u8,
+    owner: &Pubkey,
+    freeze_authority: Option<Pubkey>,
+    extensions: &[MintExtension],
+) -> AccountSharedData {
+    let mut extension_types = vec![];
+    for extension in extensions {
+        push_unique(&mut extension_types, extension.extension_type());
+    }
+
+    let space = ExtensionType::try_calculate_account_len::<Mint>(&extension_types).unwrap();
+    let mut data = vec![0u8; space];
+    {
+        let clock = client.get_sysvar::<Clock>();
+        let mut state = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+
+        for extension in extensions {
+            extension.init(&mut state, &clock);
+        }
+
+        state.base = Mint {
+            mint_authority: COption::Some(*owner),
+            supply: 0,
+            decimals,
+            is_initialized: true,
+            freeze_authority: freeze_authority.into(),
+        };
+        state.pack_base();
+        state.init_account_type().unwrap();
+    }
+
+    let r = client.get_sysvar::<Rent>();
+    let lamports = r.minimum_balance(space);
+
+    let mut account = AccountSharedData::new(lamports, space, &spl_token_2022::id());
+    account.set_data_from_slice(&data);
+    account
+}
+
+/// Creates initialized Token 2022 Token Account. Extensions required by the mint are initialized
+/// together with the given extensions and the state respects the default state of the mint.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn token_account(
+    client: &mut impl FuzzClient,
+    mint: Pubkey,
+    owner: Pubkey,
+    amount: u64,
+    delegate: Option<Pubkey>,
+    is_native: Option<u64>,
+    delegated_amount: u64,
+    close_authority: Option<Pubkey>,
+    extensions: &[AccountExtension],
+) -> AccountSharedData {
+    // the mint does not have to exist, in that case the account has no required extensions
+    let mint_account = client.get_account(&mint);
+    let mint_state = StateWithExtensions::<Mint>::unpack(mint_account.data()).ok();
+
+    let mint_extension_types = mint_state
+        .as_ref()
+        .and_then(|mint_state| mint_state.get_extension_types().ok())
+        .unwrap_or_default();
+    let account_state = mint_state
+        .as_ref()
+        .and_then(|mint_state| mint_state.get_extension::<DefaultAccountState>().ok())
+        .and_then(|default_state| AccountState::try_from(default_state.state).ok())
+        .unwrap_or(AccountState::Initialized);
+
+    let required_extension_types =
+        ExtensionType::get_required_init_account_extensions(&mint_extension_types);
+
+    let mut extension_types = vec![];
+    for extension_type in &required_extension_types {
+        push_unique(&mut extension_types, *extension_type);
+    }
+    for extension in extensions {
+        push_unique(&mut extension_types, extension.extension_type());
+    }
+
+    let space = ExtensionType::try_calculate_account_len::<Account>(&extension_types).unwrap();
+    let mut data = vec![0u8; space];
+
+    let r = client.get_sysvar::<Rent>();
+    let lamports = r.minimum_balance(space);
+    {
+        let mut state = StateWithExtensionsMut::<Account>::unpack_uninitialized(&mut data).unwrap();
+
+        for extension_type in required_extension_types {
+            state
+                .init_account_extension_from_type(extension_type)
+                .unwrap();
+        }
+        for extension in extensions {
+            extension.init(&mut state);
+        }
+
+        state.base = Account {
+            mint,
+            owner,
+            amount,
+            delegate: delegate.into(),
+            state: account_state,
+            is_native: is_native.into(),
+            delegated_amount,
+            close_authority: close_authority.into(),
+        };
+        state.pack_base();
+        state.init_account_type().unwrap();
+    }
+
+    let mut account = AccountSharedData::new(lamports, space, &spl_token_2022::id());
+    account.set_data_from_slice(&data);
+    account
+}
+
+fn push_unique(extension_types: &mut Vec<ExtensionType>, extension_type: ExtensionType) {
+    if !extension_types.contains(&extension_type) {
+        extension_types.push(extension_type);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fuzz_client_impl::TridentClient;
+    use spl_token_2022::extension::{
+        immutable_owner::ImmutableOwner, non_transferable::NonTransferableAccount,
+        transfer_fee::TransferFeeAmount, transfer_hook::TransferHookAccount,
+    };
+    use trident_config::TridentConfig;
+
+    fn client() -> TridentClient<'static> {
+        let config: TridentConfig = toml::from_str("[fuzz]").unwrap();
+        TridentClient::new_client(&[], &config)
+    }
+
+    #[test]
+    fn test_mint_with_extensions() {
+        let mut client = client();
+        let owner = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let hook_program = Pubkey::new_unique();
+
+        let account = mint_account(
+            &mut client,
+            6,
+            &owner,
+            Some(authority),
+            &[
+                MintExtension::TransferFeeConfig {
+                    transfer_fee_config_authority: Some(authority),
+                    withdraw_withheld_authority: None,
+                    transfer_fee_basis_points: 100,
+                    maximum_fee: 1_000,
+                },
+                MintExtension::InterestBearingConfig {
+                    rate_authority: None,
+                    rate: -5,
+                },
+                MintExtension::PermanentDelegate {
+                    delegate: authority,
+                },
+                MintExtension::TransferHook {
+                    authority: None,
+                    program_id: Some(hook_program),
+                },
+                MintExtension::DefaultAccountState {
+                    state: AccountState::Frozen,
+                },
+                MintExtension::MetadataPointer {
+                    authority: Some(owner),
+                    metadata_address: None,
+                },
+                MintExtension::MintCloseAuthority {
+                    close_authority: Some(owner),
+                },
+                MintExtension::NonTransferable,
+            ],
+        );
+        assert_eq!(account.owner(), &spl_token_2022::id());
+        assert!(client
+            .get_sysvar::<Rent>()
+            .is_exempt(account.lamports(), account.data().len()));
+
+        let state = StateWithExtensions::<Mint>::unpack(account.data()).unwrap();
+        assert_eq!(state.base.mint_authority, COption::Some(owner));
+        assert_eq!(state.base.freeze_authority, COption::Some(authority));
+        assert_eq!(state.base.decimals, 6);
+        assert!(state.base.is_initialized);
+        assert_eq!(state.get_extension_types().unwrap().len(), 8);
+
+        let transfer_fee = state.get_extension::<TransferFeeConfig>().unwrap();
+        assert_eq!(
+            Option::<Pubkey>::from(transfer_fee.transfer_fee_config_authority),
+            Some(authority)
+        );
+        assert_eq!(
+            Option::<Pubkey>::from(transfer_fee.withdraw_withheld_authority),
+            None
+        );
+        assert_eq!(
+            u16::from(transfer_fee.newer_transfer_fee.transfer_fee_basis_points),
+            100
+        );
+        assert_eq!(
+            u64::from(transfer_fee.newer_transfer_fee.maximum_fee),
+            1_000
+        );
+
+        let interest = state.get_extension::<InterestBearingConfig>().unwrap();
+        assert_eq!(i16::from(interest.current_rate), -5);
+        let delegate = state.get_extension::<PermanentDelegate>().unwrap();
+        assert_eq!(Option::<Pubkey>::from(delegate.delegate), Some(authority));
+        let hook = state.get_extension::<TransferHook>().unwrap();
+        assert_eq!(Option::<Pubkey>::from(hook.program_id), Some(hook_program));
+        let default_state = state.get_extension::<DefaultAccountState>().unwrap();
+        assert_eq!(default_state.state, AccountState::Frozen as u8);
+        let pointer = state.get_extension::<MetadataPointer>().unwrap();
+        assert_eq!(Option::<Pubkey>::from(pointer.authority), Some(owner));
+        let close_authority = state.get_extension::<MintCloseAuthority>().unwrap();
+        assert_eq!(
+            Option::<Pubkey>::from(close_authority.close_authority),
+            Some(owner)
+        );
+        assert!(state.get_extension::<NonTransferable>().is_ok());
+    }
+
+    #[test]
+    fn test_token_account_with_extensions() {
+        let mut client = client();
+        let mint = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+
+        let mint_with_extensions = mint_account(
+            &mut client,
+            9,
+            &owner,
+            None,
+            &[
+                MintExtension::TransferFeeConfig {
+                    transfer_fee_config_authority: None,
+                    withdraw_withheld_authority: None,
+                    transfer_fee_basis_points: 50,
+                    maximum_fee: 10,
+                },
+                MintExtension::TransferHook {
+                    authority: None,
+                    program_id: None,
+                },
+                MintExtension::DefaultAccountState {
+                    state: AccountState::Frozen,
+                },
+                MintExtension::NonTransferable,
+            ],
+        );
+        client.set_account_custom(&mint, &mint_with_extensions);
+
+        let account = token_account(
+            &mut client,
+            mint,
+            owner,
+            500,
+            None,
+            None,
+            0,
+            Some(owner),
+            &[
+                AccountExtension::ImmutableOwner,
+                AccountExtension::MemoTransfer {
+                    require_incoming_transfer_memos: true,
+                },
+                AccountExtension::CpiGuard { lock_cpi: true },
+            ],
+        );
+        assert_eq!(account.owner(), &spl_token_2022::id());
+
+        let state = StateWithExtensions::<Account>::unpack(account.data()).unwrap();
+        assert_eq!(state.base.mint, mint);
+        assert_eq!(state.base.owner, owner);
+        assert_eq!(state.base.amount, 500);
+        assert_eq!(state.base.close_authority, COption::Some(owner));
+        // the default state of the mint is respected
+        assert_eq!(state.base.state, AccountState::Frozen);
+
+        // required by the mint
+        assert!(state.get_extension::<TransferFeeAmount>().is_ok());
+        assert!(state.get_extension::<TransferHookAccount>().is_ok());
+        assert!(state.get_extension::<NonTransferableAccount>().is_ok());
+        // requested
+        assert!(state.get_extension::<ImmutableOwner>().is_ok());
+        let memo = state.get_extension::<MemoTransfer>().unwrap();
+        assert!(bool::from(memo.require_incoming_transfer_memos));
+        let cpi_guard = state.get_extension::<CpiGuard>().unwrap();
+        assert!(bool::from(cpi_guard.lock_cpi));
+    }
+
+    #[test]
+    fn test_token_account_without_mint() {
+        let mut client = client();
+        let mint = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+
+        let account = token_account(&mut client, mint, owner, 1, None, None, 0, None, &[]);
+
+        let state = StateWithExtensions::<Account>::unpack(account.data()).unwrap();
+        assert_eq!(state.base.state, AccountState::Initialized);
+        assert!(state.get_extension_types().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,208 @@
+use std::collections::HashSet;
+use std::collections::VecDeque;
+
+use solana_sdk::clock::Slot;
+use solana_sdk::clock::MAX_PROCESSING_AGE;
+use solana_sdk::hash::hashv;
+use solana_sdk::hash::Hash;
+use solana_sdk::slot_hashes::SlotHash;
+use solana_sdk::transaction::TransactionError;
+
+#[derive(Clone)]
+struct BlockhashEntry {
+    slot: Slot,
+    blockhash: Hash,
+    // hashes of the messages processed with this blockhash
+    processed: HashSet<Hash>,
+}
+
+/// Queue of recent blockhashes, one blockhash per slot the client has been at.
+///
+/// Blockhashes are derived from the slot so the same slot always produces the same
+/// blockhash, which keeps fuzzing iterations reproducible.
+#[derive(Clone, Default)]
+pub(crate) struct BlockhashQueue {
+    entries: VecDeque<BlockhashEntry>,
+}
+
+impl BlockhashQueue {
+    pub(crate) fn new(slot: Slot) -> Self {
+        let mut queue = Self::default();
+        queue.register(slot);
+        queue
+    }
+
+    /// Registers the blockhash of the given slot and expires blockhashes which are too old.
+    /// After a warp back in time, blockhashes of the later slots are dropped and the blockhash
+    /// of an already registered slot is kept together with its processed messages.
+    pub(crate) fn register(&mut self, slot: Slot) {
+        self.entries.retain(|entry| entry.slot <= slot);
+        if self.entries.back().is_some_and(|entry| entry.slot == slot) {
+            return;
+        }
+
+        self.entries.push_back(BlockhashEntry {
+            slot,
+            blockhash: blockhash_for_slot(slot),
+            processed: HashSet::new(),
+        });
+
+        self.entries
+            .retain(|entry| slot.saturating_sub(entry.slot) <= MAX_PROCESSING_AGE as u64);
+    }
+
+    pub(crate) fn last_blockhash(&self) -> Hash {
+        self.entries
+            .back()
+            .map(|entry| entry.blockhash)
+            .unwrap_or_default()
+    }
+
+    /// Checks that the blockhash has not expired and, unless duplicates are allowed,
+    /// that the message was not already processed.
+    pub(crate) fn check(
+        &self,
+        blockhash: &Hash,
+        message_hash: &Hash,
+        allow_duplicate_txs: bool,
+    ) -> Result<(), TransactionError> {
+        let entry = self
+            .entries
+            .iter()
+            .find(|entry| entry.blockhash == *blockhash)
+            .ok_or(TransactionError::BlockhashNotFound)?;
+
+        if !allow_duplicate_txs && entry.processed.contains(message_hash) {
+            return Err(TransactionError::AlreadyProcessed);
+        }
+        Ok(())
+    }
+
+    /// Records the message as processed with the given blockhash.
+    pub(crate) fn record(&mut self, blockhash: &Hash, message_hash: Hash) {
+        if let Some(entry) = self
+            .entries
+            .iter_mut()
+            .find(|entry| entry.blockhash == *blockhash)
+        {
+            entry.processed.insert(message_hash);
+        }
+    }
+
+    /// Slot hashes for the SlotHashes sysvar, newest first.
+    pub(crate) fn slot_hashes(&self) -> Vec<SlotHash> {
+        self.entries
+            .iter()
+            .rev()
+            .map(|entry| (entry.slot, entry.blockhash))
+            .collect()
+    }
+}
+
+fn blockhash_for_slot(slot: Slot) -> Hash {
+    hashv(&[b"trident-blockhash", &slot.to_le_bytes()])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slots(queue: &BlockhashQueue) -> Vec<Slot> {
+        queue.slot_hashes().iter().map(|(slot, _)| *slot).collect()
+    }
+
+    #[test]
+    fn test_blockhashes_are_derived_from_slots() {
+        let queue = BlockhashQueue::new(5);
+        assert_eq!(
+            queue.last_blockhash(),
+            BlockhashQueue::new(5).last_blockhash()
+        );
+        assert_ne!(
+            queue.last_blockhash(),
+            BlockhashQueue::new(6).last_blockhash()
+        );
+        assert_eq!(BlockhashQueue::default().last_blockhash(), Hash::default());
+    }
+
+    #[test]
+    fn test_blockhashes_expire() {
+        let mut queue = BlockhashQueue::new(0);
+        let first = queue.last_blockhash();
+        let message = Hash::new_unique();
+
+        queue.register(MAX_PROCESSING_AGE as Slot);
+        assert_eq!(queue.check(&first, &message, false), Ok(()));
+
+        queue.register(MAX_PROCESSING_AGE as Slot + 1);
+        assert_eq!(
+            queue.check(&first, &message, false),
+            Err(TransactionError::BlockhashNotFound)
+        );
+        assert_eq!(
+            queue.check(&Hash::new_unique(), &message, false),
+            Err(TransactionError::BlockhashNotFound)
+        );
+        assert_eq!(
+            slots(&queue),
+            [MAX_PROCESSING_AGE as Slot + 1, MAX_PROCESSING_AGE as Slot]
+        );
+    }
+
+    #[test]
+    fn test_processed_messages() {
+        let mut queue = BlockhashQueue::new(1);
+        let blockhash = queue.last_blockhash();
+        let message = Hash::new_unique();
+
+        queue.record(&blockhash, message);
+        assert_eq!(
+            queue.check(&blockhash, &message, false),
+            Err(TransactionError::AlreadyProcessed)
+        );
+        assert_eq!(queue.check(&blockhash, &message, true), Ok(()));
+        assert_eq!(queue.check(&blockhash, &Hash::new_unique(), false), Ok(()));
+
+        // the same message with a newer blockhash is a different transaction
+        queue.register(2);
+        assert_eq!(
+            queue.check(&queue.last_blockhash(), &message, false),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_slots_are_registered_once() {
+        let mut queue = BlockhashQueue::new(1);
+        queue.register(1);
+        queue.register(2);
+        queue.register(2);
+        assert_eq!(slots(&queue), [2, 1]);
+    }
+
+    #[test]
+    fn test_warp_back() {
+        let mut queue = BlockhashQueue::new(1);
+        let message = Hash::new_unique();
+        queue.register(2);
+        let second = queue.last_blockhash();
+        queue.record(&second, message);
+        queue.register(3);
+        let third = queue.last_blockhash();
+
+        queue.register(2);
+        assert_eq!(slots(&queue), [2, 1]);
+        assert_eq!(queue.last_blockhash(), second);
+        assert_eq!(
+            queue.check(&second, &message, false),
+            Err(TransactionError::AlreadyProcessed)
+        );
+        assert_eq!(
+            queue.check(&third, &message, false),
+            Err(TransactionError::BlockhashNotFound)
+        );
+
+        queue.register(0);
+        assert_eq!(slots(&queue), [0]);
+    }
+}
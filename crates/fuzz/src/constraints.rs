@@ -0,0 +1,58 @@
+//! Value generators used by the `#[fuzz(...)]` field attributes of `#[fuzz_constraints]`.
+use std::ops::RangeInclusive;
+
+use arbitrary::unstructured::Int;
+use arbitrary::{Arbitrary, Unstructured};
+
+/// Integer in the given range
+pub fn in_range<T: Int>(
+    range: RangeInclusive<T>,
+) -> impl Fn(&mut Unstructured<'_>) -> arbitrary::Result<T> {
+    assert!(
+        range.start() <= range.end(),
+        "#[fuzz(range = ..)] requires a non-empty range, got {:?}",
+        range
+    );
+    move |u| u.int_in_range(range.clone())
+}
+
+/// One of the given values
+pub fn one_of<T: Clone>(values: Vec<T>) -> impl Fn(&mut Unstructured<'_>) -> arbitrary::Result<T> {
+    assert!(
+        !values.is_empty(),
+        "#[fuzz(one_of = [..])] requires at least one value"
+    );
+    move |u| u.choose(&values).cloned()
+}
+
+/// Vector or string with the length in the given range
+pub fn with_len<T: WithLen>(
+    len: RangeInclusive<usize>,
+) -> impl Fn(&mut Unstructured<'_>) -> arbitrary::Result<T> {
+    assert!(
+        len.start() <= len.end(),
+        "#[fuzz(len = ..)] requires a non-empty range, got {:?}",
+        len
+    );
+    move |u| {
+        let len = u.int_in_range(len.clone())?;
+        T::arbitrary_with_len(u, len)
+    }
+}
+
+/// Types which can be generated with the given length
+pub trait WithLen: Sized {
+    fn arbitrary_with_len(u: &mut Unstructured<'_>, len: usize) -> arbitrary::Result<Self>;
+}
+
+impl<T: for<'a> Arbitrary<'a>> WithLen for Vec<T> {
+    fn arbitrary_with_len(u: &mut Unstructured<'_>, len: usize) -> arbitrary::Result<Self> {
+        (0..len).map(|_| T::arbitrary(u)).collect()
+    }
+}
+
+impl WithLen for String {
+    fn arbitrary_with_len(u: &mut Unstructured<'_>, len: usize) -> arbitrary::Result<Self> {
+        (0..len).map(|_| char::arbitrary(u)).collect()
+    }
+}
@@ -0,0 +1,384 @@
+use arbitrary::Arbitrary;
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::transaction::TransactionError;
+
+// Anchor errors of the instruction dispatch and deserialization
+const ANCHOR_INSTRUCTION_MISSING: u32 = 100;
+const ANCHOR_INSTRUCTION_FALLBACK_NOT_FOUND: u32 = 101;
+const ANCHOR_INSTRUCTION_DID_NOT_DESERIALIZE: u32 = 102;
+
+/// Mutation of the Instruction data. Lengths are taken modulo the length of the data,
+/// indexes modulo the number of the discriminator bytes or the offsets in the `DataLayout`.
+#[derive(Arbitrary, Debug, Clone, PartialEq, Eq)]
+pub enum DataMutation {
+    /// Truncate the data to a shorter length
+    Truncate { len: u16 },
+    /// Append the bytes to the data
+    Extend { bytes: Vec<u8> },
+    /// Change a byte of the discriminator
+    CorruptDiscriminator { index: u8, delta: u8 },
+    /// Overwrite an enum tag of the `DataLayout` with the given value, e.g. an out-of-range tag
+    EnumTag { index: u8, tag: u8 },
+    /// Overwrite a length prefix of the `DataLayout` with the given value, e.g. an invalid length
+    /// of a vector or string
+    InvalidLength { index: u8, len: u32 },
+}
+
+/// Offsets of the enum tags and length prefixes in the Instruction data following the discriminator.
+/// Overwriting other bytes with arbitrary values usually produces valid data, e.g. of integers.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DataLayout {
+    /// Offsets of the single byte enum and `Option` tags
+    pub enum_tags: Vec<usize>,
+    /// Offsets of the four byte length prefixes of vectors and strings
+    pub length_prefixes: Vec<usize>,
+}
+
+/// Borsh layout of an Instruction argument, the `DataLayout` is found by walking the Instruction
+/// data with the layouts of the arguments
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DataType {
+    /// Value of a fixed size without tags or length prefixes, e.g. integers and public keys
+    Fixed(usize),
+    /// Single byte tag, followed by the value if the tag is 1
+    Option(Box<DataType>),
+    /// Four byte length prefix, followed by the items, e.g. vectors, strings and bytes
+    Vec(Box<DataType>),
+    /// Fixed number of items
+    Array(Box<DataType>, usize),
+    /// Fields following each other, e.g. of structs and tuples
+    Struct(Vec<DataType>),
+    /// Single byte tag, followed by the fields of the variant
+    Enum(Vec<Vec<DataType>>),
+    /// Value of an unknown layout, e.g. with custom serialization, the offsets following it are unknown
+    Unknown,
+}
+
+impl DataLayout {
+    /// Finds the enum tags and length prefixes in the Instruction data following the discriminator,
+    /// given the layouts of the Instruction arguments. The walk stops at a value of an unknown layout
+    /// or at malformed data, the offsets following it are not included.
+    pub fn from_data(data: &[u8], args: &[DataType]) -> Self {
+        let mut layout = DataLayout::default();
+        let mut offset = 0;
+        for arg in args {
+            if layout.walk(data, arg, &mut offset).is_none() {
+                break;
+            }
+        }
+        layout
+    }
+
+    fn walk(&mut self, data: &[u8], data_type: &DataType, offset: &mut usize) -> Option<()> {
+        match data_type {
+            DataType::Fixed(size) => advance(data, offset, *size),
+            DataType::Option(inner) => {
+                let tag = *data.get(*offset)?;
+                self.enum_tags.push(*offset);
+                *offset += 1;
+                match tag {
+                    0 => Some(()),
+                    1 => self.walk(data, inner, offset),
+                    _ => None,
+                }
+            }
+            DataType::Vec(item) => {
+                let prefix = data.get(*offset..offset.checked_add(4)?)?;
+                let len = u32::from_le_bytes(prefix.try_into().ok()?);
+                self.length_prefixes.push(*offset);
+                *offset += 4;
+                self.walk_items(data, item, usize::try_from(len).ok()?, offset)
+            }
+            DataType::Array(item, len) => self.walk_items(data, item, *len, offset),
+            DataType::Struct(fields) => fields
+                .iter()
+                .try_for_each(|field| self.walk(data, field, offset)),
+            DataType::Enum(variants) => {
+                let tag = *data.get(*offset)?;
+                self.enum_tags.push(*offset);
+                *offset += 1;
+                variants
+                    .get(usize::from(tag))?
+                    .iter()
+                    .try_for_each(|field| self.walk(data, field, offset))
+            }
+            DataType::Unknown => None,
+        }
+    }
+
+    fn walk_items(
+        &mut self,
+        data: &[u8],
+        item: &DataType,
+        len: usize,
+        offset: &mut usize,
+    ) -> Option<()> {
+        if let DataType::Fixed(size) = item {
+            return advance(data, offset, size.checked_mul(len)?);
+        }
+        // every item takes at least one byte, so a malformed length does not walk the items for long
+        if len > data.len().saturating_sub(*offset) {
+            return None;
+        }
+        (0..len).try_for_each(|_| self.walk(data, item, offset))
+    }
+}
+
+// Moves past a value of the given size, None if the data are shorter
+fn advance(data: &[u8], offset: &mut usize, size: usize) -> Option<()> {
+    let end = offset.checked_add(size)?;
+    if end > data.len() {
+        return None;
+    }
+    *offset = end;
+    Some(())
+}
+
+/// Outcome of an Instruction executed with mutated data
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DataMutationOutcome {
+    /// The malformed data were accepted
+    Succeeded,
+    /// The program panicked
+    Panicked,
+    /// The program cleanly rejected the malformed data
+    DeserializationError,
+    /// The program failed with another error
+    OtherError(TransactionError),
+}
+
+impl DataMutation {
+    /// Apply the mutation to the Instruction data, offsets outside of the data are skipped
+    pub fn apply(&self, data: &mut Vec<u8>, discriminator_len: usize, layout: &DataLayout) {
+        let discriminator_len = discriminator_len.min(data.len());
+        let offset = |offsets: &[usize], index: &u8| match offsets.is_empty() {
+            true => None,
+            false => Some(discriminator_len + offsets[usize::from(*index) % offsets.len()]),
+        };
+
+        match self {
+            DataMutation::Truncate { len } => {
+                if !data.is_empty() {
+                    data.truncate(usize::from(*len) % data.len());
+                }
+            }
+            DataMutation::Extend { bytes } => {
+                data.extend_from_slice(bytes);
+            }
+            DataMutation::CorruptDiscriminator { index, delta } => {
+                if discriminator_len > 0 {
+                    let i = usize::from(*index) % discriminator_len;
+                    data[i] = data[i].wrapping_add((*delta).max(1));
+                }
+            }
+            DataMutation::EnumTag { index, tag } => {
+                if let Some(byte) =
+                    offset(&layout.enum_tags, index).and_then(|offset| data.get_mut(offset))
+                {
+                    *byte = *tag;
+                }
+            }
+            DataMutation::InvalidLength { index, len } => {
+                if let Some(bytes) = offset(&layout.length_prefixes, index)
+                    .and_then(|offset| data.get_mut(offset..offset + 4))
+                {
+                    bytes.copy_from_slice(&len.to_le_bytes());
+                }
+            }
+        }
+    }
+}
+
+impl From<Result<(), TransactionError>> for DataMutationOutcome {
+    fn from(result: Result<(), TransactionError>) -> Self {
+        match result {
+            Ok(()) => DataMutationOutcome::Succeeded,
+            Err(e) => match e {
+                TransactionError::InstructionError(
+                    _,
+                    InstructionError::ProgramFailedToComplete,
+                ) => DataMutationOutcome::Panicked,
+                TransactionError::InstructionError(
+                    _,
+                    InstructionError::InvalidInstructionData
+                    | InstructionError::BorshIoError(_)
+                    | InstructionError::Custom(
+                        ANCHOR_INSTRUCTION_MISSING
+                        | ANCHOR_INSTRUCTION_FALLBACK_NOT_FOUND
+                        | ANCHOR_INSTRUCTION_DID_NOT_DESERIALIZE,
+                    ),
+                ) => DataMutationOutcome::DeserializationError,
+                e => DataMutationOutcome::OtherError(e),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // discriminator `[9, 9]`, followed by `amount: u16`, `side: Side` and `memo: String`
+    const DATA: [u8; 9] = [9, 9, 1, 0, 1, 0, 0, 0, 0];
+
+    fn layout() -> DataLayout {
+        DataLayout {
+            enum_tags: vec![2],
+            length_prefixes: vec![3],
+        }
+    }
+
+    fn mutate(mutation: DataMutation, layout: &DataLayout) -> Vec<u8> {
+        let mut data = DATA.to_vec();
+        mutation.apply(&mut data, 2, layout);
+        data
+    }
+
+    #[test]
+    fn test_length_mutations() {
+        assert_eq!(
+            mutate(DataMutation::Truncate { len: 13 }, &layout()),
+            DATA[..4]
+        );
+        assert_eq!(
+            mutate(DataMutation::Extend { bytes: vec![7, 7] }, &layout()),
+            [&DATA[..], &[7, 7]].concat()
+        );
+    }
+
+    #[test]
+    fn test_corrupt_discriminator() {
+        assert_eq!(
+            mutate(
+                DataMutation::CorruptDiscriminator { index: 3, delta: 0 },
+                &layout()
+            ),
+            [9, 10, 1, 0, 1, 0, 0, 0, 0]
+        );
+
+        let mut data = vec![1, 2];
+        DataMutation::CorruptDiscriminator { index: 0, delta: 1 }.apply(&mut data, 0, &layout());
+        assert_eq!(data, [1, 2]);
+    }
+
+    #[test]
+    fn test_layout_mutations() {
+        assert_eq!(
+            mutate(DataMutation::EnumTag { index: 5, tag: 7 }, &layout()),
+            [9, 9, 1, 0, 7, 0, 0, 0, 0]
+        );
+        assert_eq!(
+            mutate(
+                DataMutation::InvalidLength {
+                    index: 1,
+                    len: u32::MAX
+                },
+                &layout()
+            ),
+            [9, 9, 1, 0, 1, 255, 255, 255, 255]
+        );
+
+        // without known offsets, or with offsets outside of the data, the data are unchanged
+        let unknown = DataLayout::default();
+        assert_eq!(
+            mutate(DataMutation::EnumTag { index: 0, tag: 7 }, &unknown),
+            DATA
+        );
+        let outside = DataLayout {
+            enum_tags: vec![7],
+            length_prefixes: vec![4],
+        };
+        assert_eq!(
+            mutate(DataMutation::EnumTag { index: 0, tag: 7 }, &outside),
+            DATA
+        );
+        assert_eq!(
+            mutate(DataMutation::InvalidLength { index: 0, len: 0 }, &outside),
+            DATA
+        );
+    }
+
+    #[test]
+    fn test_layout_from_data() {
+        let side = DataType::Enum(vec![vec![], vec![DataType::Fixed(2)]]);
+        let args = [
+            DataType::Fixed(2),
+            DataType::Vec(Box::new(DataType::Fixed(1))),
+            DataType::Option(Box::new(side.clone())),
+            DataType::Vec(Box::new(side)),
+            DataType::Array(Box::new(DataType::Fixed(1)), 2),
+            DataType::Fixed(1),
+        ];
+
+        // `1u16`, `"ab"`, `Some(Side::Sell(3))`, `vec![Side::Buy]`, `[4, 5]` and `6`
+        let data = [
+            1, 0, 2, 0, 0, 0, b'a', b'b', 1, 1, 3, 0, 1, 0, 0, 0, 0, 4, 5, 6,
+        ];
+        assert_eq!(
+            DataLayout::from_data(&data, &args),
+            DataLayout {
+                enum_tags: vec![8, 9, 16],
+                length_prefixes: vec![2, 12],
+            }
+        );
+
+        // the offsets following malformed data are unknown
+        let mut malformed = data;
+        malformed[8] = 2;
+        assert_eq!(
+            DataLayout::from_data(&malformed, &args),
+            DataLayout {
+                enum_tags: vec![8],
+                length_prefixes: vec![2],
+            }
+        );
+        malformed[2] = 255;
+        assert_eq!(
+            DataLayout::from_data(&malformed, &args),
+            DataLayout {
+                enum_tags: vec![],
+                length_prefixes: vec![2],
+            }
+        );
+
+        // as well as the offsets following a value of an unknown layout
+        assert_eq!(
+            DataLayout::from_data(
+                &data,
+                &[
+                    DataType::Fixed(2),
+                    DataType::Unknown,
+                    DataType::Option(Box::new(DataType::Fixed(1)))
+                ]
+            ),
+            DataLayout::default()
+        );
+    }
+
+    #[test]
+    fn test_outcome() {
+        let instruction_error = |error| Err(TransactionError::InstructionError(0, error));
+
+        assert_eq!(
+            DataMutationOutcome::from(Ok(())),
+            DataMutationOutcome::Succeeded
+        );
+        assert_eq!(
+            DataMutationOutcome::from(instruction_error(InstructionError::ProgramFailedToComplete)),
+            DataMutationOutcome::Panicked
+        );
+        assert_eq!(
+            DataMutationOutcome::from(instruction_error(InstructionError::Custom(
+                ANCHOR_INSTRUCTION_DID_NOT_DESERIALIZE
+            ))),
+            DataMutationOutcome::DeserializationError
+        );
+        assert_eq!(
+            DataMutationOutcome::from(instruction_error(InstructionError::Custom(6000))),
+            DataMutationOutcome::OtherError(TransactionError::InstructionError(
+                0,
+                InstructionError::Custom(6000)
+            ))
+        );
+    }
+}
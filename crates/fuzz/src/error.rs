@@ -18,6 +18,10 @@ pub enum FuzzingError {
     Custom(u32),
     #[error("Fuzzing error with Custom Message: {0}\n")]
     CustomMessage(String),
+    #[error("Account {0} does not start with the expected discriminator\n")]
+    InvalidDiscriminator(Pubkey),
+    #[error("Failed to deserialize account {0}: {1}\n")]
+    DeserializationFailed(Pubkey, String),
 }
 
 impl FuzzClientError {
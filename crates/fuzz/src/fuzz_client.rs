@@ -6,27 +6,35 @@ use solana_sdk::instruction::Instruction;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::signature::Keypair;
 use solana_sdk::sysvar::Sysvar;
+use solana_sdk::transaction::Transaction;
 use solana_sdk::transaction::TransactionError;
+use solana_sdk::transaction::VersionedTransaction;
 
 use trident_config::TridentConfig;
 use trident_svm::utils::ProgramEntrypoint;
 
 /// A trait providing methods to read and write (manipulate) accounts
 pub trait FuzzClient {
+    /// Snapshot of the client state, see [`FuzzClient::take_snapshot`]
+    type StateSnapshot;
+
     fn new_client(programs: &[ProgramEntrypoint], config: &TridentConfig) -> Self;
     /// Get the cluster rent
     fn get_sysvar<T: Sysvar>(&self) -> T;
 
-    /// Warp to specific epoch
+    /// Set the sysvars to the initial state specified in the Trident.toml
+    fn set_initial_sysvars(&mut self, config: &TridentConfig);
+
+    /// Warp to specific epoch, the slot moves to the first slot of the epoch
     fn warp_to_epoch(&mut self, warp_epoch: u64);
 
     /// Warp to specific slot
     fn warp_to_slot(&mut self, warp_slot: u64);
 
-    /// Warp to specific timestamp
+    /// Warp to specific timestamp, the slot moves along with the time
     fn warp_to_timestamp(&mut self, warp_timestamp: i64);
 
-    /// Forward in time by the desired number of seconds
+    /// Forward in time by the desired number of seconds, the slot moves along with the time
     fn forward_in_time(&mut self, seconds: i64);
 
     /// Create or overwrite a custom account, subverting normal runtime checks.
@@ -38,7 +46,7 @@ pub trait FuzzClient {
     /// Get the account at the given address
     fn get_account(&mut self, key: &Pubkey) -> AccountSharedData;
 
-    /// Get last blockhash
+    /// Get last blockhash, the blockhash advances with slots
     fn get_last_blockhash(&self) -> Hash;
 
     /// Send a transaction and return until the transaction has been finalized or rejected.
@@ -47,6 +55,36 @@ pub trait FuzzClient {
         _instructions: &[Instruction],
     ) -> Result<(), TransactionError>;
 
-    // Clear Temp account created during fuzzing iteration
-    fn clear_accounts(&mut self);
+    /// Send a versioned (v0) transaction and return until the transaction has been finalized or rejected.
+    /// Accounts stored in the given address lookup tables are loaded through the tables, the resolved
+    /// message is executed as a legacy transaction.
+    fn process_instructions_with_lookup_tables(
+        &mut self,
+        instructions: &[Instruction],
+        lookup_tables: &[Pubkey],
+    ) -> Result<(), TransactionError>;
+
+    /// Process already built transaction. The transaction is rejected if its blockhash
+    /// has expired or if it was already processed and `allow_duplicate_txs` is not set.
+    fn process_transaction(&mut self, transaction: Transaction) -> Result<(), TransactionError>;
+
+    /// Process already built versioned transaction. Addresses of v0 messages are loaded
+    /// from the address lookup table accounts of the client.
+    ///
+    /// Note that v0 messages are only simulated: after the lookup, the message is flattened into
+    /// a legacy message and executed as a legacy transaction. The account order and the size
+    /// limits of v0 transactions are not preserved and writable reserved accounts (e.g. sysvars
+    /// or builtin programs) loaded through the tables are not demoted to readonly, so these v0
+    /// semantics are not exercised.
+    fn process_versioned_transaction(
+        &mut self,
+        transaction: VersionedTransaction,
+    ) -> Result<(), TransactionError>;
+
+    /// Take a snapshot of the accounts, sysvars and recent blockhashes of the client
+    fn take_snapshot(&self) -> Self::StateSnapshot;
+
+    /// Restore the client to the state of the snapshot. Accounts created after the
+    /// snapshot was taken are removed.
+    fn restore_snapshot(&mut self, snapshot: &Self::StateSnapshot);
 }
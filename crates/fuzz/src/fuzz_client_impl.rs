@@ -1,22 +1,223 @@
+use std::collections::HashSet;
+
 use solana_sdk::account::AccountSharedData;
+use solana_sdk::account::ReadableAccount;
+use solana_sdk::address_lookup_table;
+use solana_sdk::address_lookup_table::error::AddressLookupError;
+use solana_sdk::address_lookup_table::state::AddressLookupTable;
+use solana_sdk::address_lookup_table::AddressLookupTableAccount;
 use solana_sdk::clock::Clock;
+use solana_sdk::clock::DEFAULT_MS_PER_SLOT;
+use solana_sdk::epoch_schedule::EpochSchedule;
 use solana_sdk::hash::Hash;
+use solana_sdk::instruction::AccountMeta;
 use solana_sdk::instruction::Instruction;
+use solana_sdk::message::v0;
+use solana_sdk::message::v0::LoadedAddresses;
+use solana_sdk::message::v0::LoadedMessage;
+use solana_sdk::message::Message;
+use solana_sdk::message::VersionedMessage;
 use solana_sdk::pubkey::Pubkey;
+use solana_sdk::rent::Rent;
+use solana_sdk::signature::Signature;
 use solana_sdk::signer::Signer;
+use solana_sdk::slot_hashes::SlotHashes;
+use solana_sdk::sysvar::last_restart_slot::LastRestartSlot;
 use solana_sdk::sysvar::Sysvar;
+use solana_sdk::sysvar::SysvarId;
+use solana_sdk::transaction::Transaction;
+use solana_sdk::transaction::VersionedTransaction;
 
 use trident_config::TridentConfig;
 
-use trident_svm::trident_svm::TridentSVM;
 use trident_svm::utils::ProgramEntrypoint;
 use trident_svm::utils::SBFTargets;
 use trident_svm::utils::TridentAccountSharedData;
 
+use crate::blockhash_queue::BlockhashQueue;
 use crate::fuzz_client::FuzzClient;
+use crate::input_chain::execute_programs;
 use solana_sdk::transaction::TransactionError;
 
-impl FuzzClient for TridentSVM<'_> {
+/// Trident SVM extended with the state the fuzzing client needs on top of the plain SVM,
+/// such as the queue of recent blockhashes.
+pub struct TridentClient<'a> {
+    svm: trident_svm::trident_svm::TridentSVM<'a>,
+    blockhash_queue: BlockhashQueue,
+    allow_duplicate_txs: bool,
+    // addresses of the accounts which could have been modified, these are part of snapshots
+    tracked_accounts: HashSet<Pubkey>,
+}
+
+/// Former name of the [`TridentClient`].
+#[deprecated(since = "0.3.1", note = "renamed to `TridentClient`")]
+pub type TridentSVM<'a> = TridentClient<'a>;
+
+/// Snapshot of the [`TridentClient`] state, which can be restored cheaply.
+pub struct TridentClientSnapshot {
+    accounts: Vec<(Pubkey, AccountSharedData)>,
+    clock: Clock,
+    rent: Rent,
+    epoch_schedule: EpochSchedule,
+    last_restart_slot: Option<LastRestartSlot>,
+    blockhash_queue: BlockhashQueue,
+    tracked_accounts: HashSet<Pubkey>,
+}
+
+impl TridentClient<'_> {
+    fn set_clock(&mut self, clock: &Clock) {
+        self.svm.set_sysvar(clock);
+
+        self.blockhash_queue.register(clock.slot);
+        self.svm
+            .set_sysvar(&SlotHashes::new(&self.blockhash_queue.slot_hashes()));
+    }
+
+    /// Moves the clock by the given number of seconds, the slot moves along with the time
+    /// so the recent blockhashes expire same as on-chain.
+    fn move_clock(&mut self, seconds: i64) {
+        let mut clock = self.get_sysvar::<Clock>();
+
+        let slots = seconds.unsigned_abs().saturating_mul(1000) / DEFAULT_MS_PER_SLOT;
+        clock.slot = if seconds < 0 {
+            clock.slot.saturating_sub(slots)
+        } else {
+            clock.slot.saturating_add(slots)
+        };
+        clock.unix_timestamp = clock.unix_timestamp.saturating_add(seconds);
+        self.set_clock(&clock);
+    }
+
+    fn get_lookup_table_raw_account(
+        &self,
+        address: &Pubkey,
+    ) -> Result<AccountSharedData, TransactionError> {
+        // closed accounts are kept with zero lamports
+        self.svm
+            .get_account(address)
+            .filter(|account| account.lamports() > 0)
+            .ok_or(TransactionError::AddressLookupTableNotFound)
+    }
+
+    fn get_lookup_table_account(
+        &self,
+        address: &Pubkey,
+    ) -> Result<AddressLookupTableAccount, TransactionError> {
+        let account = self.get_lookup_table_raw_account(address)?;
+        let lookup_table = deserialize_lookup_table(&account)?;
+
+        Ok(AddressLookupTableAccount {
+            key: *address,
+            addresses: lookup_table.addresses.to_vec(),
+        })
+    }
+
+    /// Simulates the address lookup of v0 messages, as the SVM only executes legacy transactions.
+    /// The addresses are loaded from the lookup tables, which have to be active and contain the
+    /// looked up indexes, and a legacy message with the same instructions and account privileges
+    /// is built. The account order and size limits of the v0 message are not preserved.
+    fn resolve_v0_message(&self, message: v0::Message) -> Result<Message, TransactionError> {
+        message.sanitize()?;
+
+        let current_slot = self.get_sysvar::<Clock>().slot;
+        let slot_hashes = SlotHashes::new(&self.blockhash_queue.slot_hashes());
+
+        let mut loaded_addresses = LoadedAddresses::default();
+        for lookup in &message.address_table_lookups {
+            let account = self.get_lookup_table_raw_account(&lookup.account_key)?;
+            let lookup_table = deserialize_lookup_table(&account)?;
+
+            loaded_addresses.writable.extend(
+                lookup_table
+                    .lookup(current_slot, &lookup.writable_indexes, &slot_hashes)
+                    .map_err(lookup_error)?,
+            );
+            loaded_addresses.readonly.extend(
+                lookup_table
+                    .lookup(current_slot, &lookup.readonly_indexes, &slot_hashes)
+                    .map_err(lookup_error)?,
+            );
+        }
+
+        let loaded_message =
+            LoadedMessage::new_borrowed(&message, &loaded_addresses, &HashSet::new());
+        let account_keys = loaded_message.account_keys();
+
+        // indexes are valid, the message was sanitized against the number of loaded addresses
+        let instructions: Vec<Instruction> = message
+            .instructions
+            .iter()
+            .map(|instruction| Instruction {
+                program_id: account_keys[instruction.program_id_index as usize],
+                accounts: instruction
+                    .accounts
+                    .iter()
+                    .map(|index| AccountMeta {
+                        pubkey: account_keys[*index as usize],
+                        is_signer: loaded_message.is_signer(*index as usize),
+                        is_writable: loaded_message.is_writable(*index as usize),
+                    })
+                    .collect(),
+                data: instruction.data.clone(),
+            })
+            .collect();
+
+        Ok(Message::new_with_blockhash(
+            &instructions,
+            message.account_keys.first(),
+            &message.recent_blockhash,
+        ))
+    }
+
+    fn execute_transaction(
+        &mut self,
+        transaction: Transaction,
+        message_hash: Hash,
+    ) -> Result<(), TransactionError> {
+        let blockhash = transaction.message.recent_blockhash;
+        let account_keys = transaction.message.account_keys.clone();
+
+        self.blockhash_queue
+            .check(&blockhash, &message_hash, self.allow_duplicate_txs)?;
+
+        let result = execute_programs(|| self.svm.process_transaction_with_settle(transaction));
+
+        // only transactions which were executed are remembered, same as in the status cache
+        if matches!(
+            result,
+            Ok(()) | Err(TransactionError::InstructionError(_, _))
+        ) {
+            self.blockhash_queue.record(&blockhash, message_hash);
+            self.tracked_accounts.extend(account_keys);
+        }
+        result
+    }
+}
+
+fn deserialize_lookup_table(
+    account: &AccountSharedData,
+) -> Result<AddressLookupTable<'_>, TransactionError> {
+    if account.owner() != &address_lookup_table::program::ID {
+        return Err(TransactionError::InvalidAddressLookupTableOwner);
+    }
+    AddressLookupTable::deserialize(account.data())
+        .map_err(|_| TransactionError::InvalidAddressLookupTableData)
+}
+
+fn lookup_error(error: AddressLookupError) -> TransactionError {
+    match error {
+        AddressLookupError::LookupTableAccountNotFound => {
+            TransactionError::AddressLookupTableNotFound
+        }
+        AddressLookupError::InvalidAccountOwner => TransactionError::InvalidAddressLookupTableOwner,
+        AddressLookupError::InvalidAccountData => TransactionError::InvalidAddressLookupTableData,
+        AddressLookupError::InvalidLookupIndex => TransactionError::InvalidAddressLookupTableIndex,
+    }
+}
+
+impl FuzzClient for TridentClient<'_> {
+    type StateSnapshot = TridentClientSnapshot;
+
     fn new_client(programs: &[ProgramEntrypoint], config: &TridentConfig) -> Self {
         let sbf_programs =
             config
@@ -46,49 +247,84 @@ impl FuzzClient for TridentSVM<'_> {
                     permanent_accounts
                 });
 
-        TridentSVM::new_with_syscalls(programs, &sbf_programs, &permanent_accounts)
+        let mut client = TridentClient {
+            svm: trident_svm::trident_svm::TridentSVM::new_with_syscalls(
+                programs,
+                &sbf_programs,
+                &permanent_accounts,
+            ),
+            blockhash_queue: BlockhashQueue::default(),
+            allow_duplicate_txs: config.get_allow_duplicate_txs(),
+            tracked_accounts: HashSet::new(),
+        };
+
+        // permanent accounts are modified by transactions same as temp accounts
+        client
+            .tracked_accounts
+            .extend(config.accounts().iter().map(|account| account.pubkey));
+        client.tracked_accounts.insert(client.payer().pubkey());
+
+        client.set_initial_sysvars(config);
+        client
+    }
+    fn set_initial_sysvars(&mut self, config: &TridentConfig) {
+        let sysvars = config.sysvars();
+        let clock = sysvars.get_clock();
+
+        // start with a fresh blockhash queue, so already processed transactions are forgotten
+        self.blockhash_queue = BlockhashQueue::new(clock.slot);
+        self.set_clock(&clock);
+
+        self.svm.set_sysvar(&sysvars.get_rent());
+        self.svm.set_sysvar(&sysvars.get_epoch_schedule());
+
+        if let Some(last_restart_slot) = sysvars.get_last_restart_slot() {
+            self.svm.set_sysvar(&last_restart_slot);
+        }
     }
     fn warp_to_epoch(&mut self, warp_epoch: u64) {
         let mut clock = self.get_sysvar::<Clock>();
+        let epoch_schedule = self.get_sysvar::<EpochSchedule>();
 
+        // the slot is kept if it already is in the epoch
+        if epoch_schedule.get_epoch(clock.slot) != warp_epoch {
+            clock.slot = epoch_schedule.get_first_slot_in_epoch(warp_epoch);
+        }
         clock.epoch = warp_epoch;
-        self.set_sysvar(&clock);
+        self.set_clock(&clock);
     }
 
     fn warp_to_slot(&mut self, warp_slot: u64) {
         let mut clock = self.get_sysvar::<Clock>();
 
         clock.slot = warp_slot;
-        self.set_sysvar(&clock);
+        self.set_clock(&clock);
     }
     fn warp_to_timestamp(&mut self, warp_timestamp: i64) {
-        let mut clock = self.get_sysvar::<Clock>();
+        let clock = self.get_sysvar::<Clock>();
 
-        clock.unix_timestamp = warp_timestamp;
-        self.set_sysvar(&clock);
+        self.move_clock(warp_timestamp.saturating_sub(clock.unix_timestamp));
     }
 
     fn forward_in_time(&mut self, seconds: i64) {
-        let mut clock = self.get_sysvar::<Clock>();
-
-        clock.unix_timestamp = clock.unix_timestamp.saturating_add(seconds);
-        self.set_sysvar(&clock);
+        self.move_clock(seconds);
     }
 
     fn set_account_custom(&mut self, address: &Pubkey, account: &AccountSharedData) {
-        self.add_temp_account(address, account);
+        self.svm.add_temp_account(address, account);
+        self.tracked_accounts.insert(*address);
     }
 
     fn payer(&self) -> solana_sdk::signature::Keypair {
-        self.get_payer()
+        self.svm.get_payer()
     }
 
     fn get_account(&mut self, key: &Pubkey) -> AccountSharedData {
-        trident_svm::trident_svm::TridentSVM::get_account(self, key).unwrap_or_default()
+        self.svm.get_account(key).unwrap_or_default()
     }
 
     fn get_last_blockhash(&self) -> Hash {
-        panic!("Not yet implemented for TridentSVM");
+        self.blockhash_queue.last_blockhash()
     }
 
     fn process_instructions(
@@ -99,20 +335,183 @@ impl FuzzClient for TridentSVM<'_> {
         // But we do not pay for TX currently so has to be manually updated
         // tx.message.header.num_required_signatures = 1;
         // tx.message.header.num_readonly_signed_accounts = 0;
-        let tx = solana_sdk::transaction::Transaction::new_with_payer(
+        let message = Message::new_with_blockhash(
             instructions,
             Some(&self.payer().pubkey()),
+            &self.get_last_blockhash(),
         );
+        let tx = Transaction::new_unsigned(message);
+
+        self.process_transaction(tx)
+    }
+
+    fn process_instructions_with_lookup_tables(
+        &mut self,
+        instructions: &[Instruction],
+        lookup_tables: &[Pubkey],
+    ) -> Result<(), TransactionError> {
+        let lookup_table_accounts = lookup_tables
+            .iter()
+            .map(|address| self.get_lookup_table_account(address))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let message = v0::Message::try_compile(
+            &self.payer().pubkey(),
+            instructions,
+            &lookup_table_accounts,
+            self.get_last_blockhash(),
+        )
+        .map_err(|_| TransactionError::SanitizeFailure)?;
+
+        let tx = VersionedTransaction {
+            signatures: vec![Signature::default(); message.header.num_required_signatures as usize],
+            message: VersionedMessage::V0(message),
+        };
+
+        self.process_versioned_transaction(tx)
+    }
+
+    fn process_transaction(&mut self, transaction: Transaction) -> Result<(), TransactionError> {
+        let message_hash = transaction.message.hash();
+
+        self.execute_transaction(transaction, message_hash)
+    }
+
+    fn process_versioned_transaction(
+        &mut self,
+        transaction: VersionedTransaction,
+    ) -> Result<(), TransactionError> {
+        // the hash of the versioned message is remembered, not the hash of the resolved one
+        let message_hash = transaction.message.hash();
+
+        let message = match transaction.message {
+            VersionedMessage::Legacy(message) => message,
+            VersionedMessage::V0(message) => self.resolve_v0_message(message)?,
+        };
 
-        self.process_transaction_with_settle(tx)?;
-        Ok(())
+        self.execute_transaction(Transaction::new_unsigned(message), message_hash)
     }
 
     fn get_sysvar<T: Sysvar>(&self) -> T {
-        trident_svm::trident_svm::TridentSVM::get_sysvar::<T>(self)
+        self.svm.get_sysvar::<T>()
     }
 
-    fn clear_accounts(&mut self) {
-        self.clear_accounts();
+    fn take_snapshot(&self) -> TridentClientSnapshot {
+        let accounts = self
+            .tracked_accounts
+            .iter()
+            .filter_map(|address| {
+                self.svm
+                    .get_account(address)
+                    .map(|account| (*address, account))
+            })
+            .collect();
+
+        let last_restart_slot = self
+            .svm
+            .get_account(&LastRestartSlot::id())
+            .map(|_| self.svm.get_sysvar::<LastRestartSlot>());
+
+        TridentClientSnapshot {
+            accounts,
+            clock: self.svm.get_sysvar(),
+            rent: self.svm.get_sysvar(),
+            epoch_schedule: self.svm.get_sysvar(),
+            last_restart_slot,
+            blockhash_queue: self.blockhash_queue.clone(),
+            tracked_accounts: self.tracked_accounts.clone(),
+        }
+    }
+
+    fn restore_snapshot(&mut self, snapshot: &TridentClientSnapshot) {
+        // drop all temp accounts, then write back the snapshot, settle takes care
+        // of updating the permanent accounts in place
+        self.svm.clear_accounts();
+        self.svm.settle_accounts(&snapshot.accounts);
+
+        self.blockhash_queue = snapshot.blockhash_queue.clone();
+        self.tracked_accounts = snapshot.tracked_accounts.clone();
+
+        self.svm.set_sysvar(&snapshot.clock);
+        self.svm
+            .set_sysvar(&SlotHashes::new(&self.blockhash_queue.slot_hashes()));
+        self.svm.set_sysvar(&snapshot.rent);
+        self.svm.set_sysvar(&snapshot.epoch_schedule);
+        if let Some(last_restart_slot) = &snapshot.last_restart_slot {
+            self.svm.set_sysvar(last_restart_slot);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client() -> TridentClient<'static> {
+        let config: TridentConfig = toml::from_str("[fuzz]").unwrap();
+        TridentClient::new_client(&[], &config)
+    }
+
+    #[test]
+    fn test_warp_to_epoch_moves_slot() {
+        let mut client = client();
+        let initial_blockhash = client.get_last_blockhash();
+        let epoch_schedule = client.get_sysvar::<EpochSchedule>();
+
+        client.warp_to_epoch(20);
+        let clock = client.get_sysvar::<Clock>();
+        assert_eq!(clock.epoch, 20);
+        assert_eq!(clock.slot, epoch_schedule.get_first_slot_in_epoch(20));
+        assert_ne!(client.get_last_blockhash(), initial_blockhash);
+        assert_eq!(
+            client
+                .get_sysvar::<SlotHashes>()
+                .first()
+                .map(|(slot, _)| *slot),
+            Some(clock.slot)
+        );
+
+        // back in time, the blockhashes of the later slots are dropped
+        client.warp_to_epoch(0);
+        assert_eq!(client.get_sysvar::<Clock>().slot, 0);
+        assert_eq!(client.get_last_blockhash(), initial_blockhash);
+    }
+
+    #[test]
+    fn test_time_warps_expire_blockhashes() {
+        let mut client = client();
+        let initial = client.get_sysvar::<Clock>();
+        let initial_blockhash = client.get_last_blockhash();
+
+        // 400 ms per slot
+        client.forward_in_time(2);
+        let clock = client.get_sysvar::<Clock>();
+        assert_eq!(clock.slot, initial.slot + 5);
+        assert_eq!(clock.unix_timestamp, initial.unix_timestamp + 2);
+
+        client.warp_to_timestamp(initial.unix_timestamp + 3_600);
+        let clock = client.get_sysvar::<Clock>();
+        assert_eq!(clock.slot, initial.slot + 9_000);
+        assert_eq!(clock.unix_timestamp, initial.unix_timestamp + 3_600);
+
+        let transfer = solana_sdk::system_instruction::transfer(
+            &client.payer().pubkey(),
+            &Pubkey::new_unique(),
+            1,
+        );
+        let transaction = Transaction::new_signed_with_payer(
+            &[transfer],
+            Some(&client.payer().pubkey()),
+            &[&client.payer()],
+            initial_blockhash,
+        );
+        assert_eq!(
+            client.process_transaction(transaction),
+            Err(TransactionError::BlockhashNotFound)
+        );
+
+        client.forward_in_time(-3_600);
+        assert_eq!(client.get_sysvar::<Clock>().slot, initial.slot);
+        assert_eq!(client.get_last_blockhash(), initial_blockhash);
     }
 }
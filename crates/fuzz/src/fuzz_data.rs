@@ -6,8 +6,11 @@ use std::cell::RefCell;
 use std::error::Error;
 use std::fmt::Display;
 
+use crate::accounts_storage::{new_fuzz_accounts, AccountStorages};
 use crate::fuzz_client::FuzzClient;
 use crate::fuzz_test_executor::FuzzTestExecutor;
+use crate::input_chain::InputChain;
+use crate::transaction_executor::set_mutations_input;
 use trident_config::TridentConfig;
 
 pub struct FuzzData<T, U> {
@@ -15,6 +18,9 @@ pub struct FuzzData<T, U> {
     pub ixs: Vec<T>,
     pub post_ixs: Vec<T>,
     pub accounts: RefCell<U>,
+    /// Bytes of the fuzzer input left after the instructions, the account and data mutations
+    /// are drawn from them if enabled in Trident.toml
+    pub mutations_input: Vec<u8>,
 }
 
 pub struct FuzzDataIterator<'a, T> {
@@ -44,13 +50,70 @@ impl<'a, T> Iterator for FuzzDataIterator<'a, T> {
     }
 }
 
+/// The state fuzzing iterations start from.
+pub struct InitialState<S, U> {
+    snapshot: S,
+    // accounts storages matching the snapshot, set once the pre_ixs were snapshotted
+    accounts: Option<U>,
+    stateful: Option<StatefulState<U>>,
+}
+
+// In the stateful mode the state carries over between iterations and is reset periodically.
+struct StatefulState<U> {
+    reset_interval: u64,
+    iterations_since_reset: u64,
+    // accounts storages of the previous iteration, None if the next iteration resets the state
+    carried_accounts: Option<U>,
+    input_chain: InputChain,
+}
+
+impl<S, U> InitialState<S, U> {
+    pub fn new<C: FuzzClient<StateSnapshot = S>>(client: &C, config: &TridentConfig) -> Self {
+        Self {
+            snapshot: client.take_snapshot(),
+            accounts: None,
+            stateful: config.stateful().map(|stateful| StatefulState {
+                reset_interval: stateful.get_reset_interval(),
+                iterations_since_reset: 0,
+                carried_accounts: None,
+                input_chain: InputChain::new(stateful.get_crash_chains_dir()),
+            }),
+        }
+    }
+
+    /// Record the raw input of the upcoming iteration. In the stateful mode the inputs
+    /// since the last reset are stored together in case of a crash.
+    pub fn record_input(&mut self, input: &[u8]) {
+        if let Some(stateful) = &mut self.stateful {
+            if stateful.carried_accounts.is_none() {
+                stateful.input_chain.clear();
+            }
+            stateful.input_chain.push(input);
+        }
+    }
+
+    fn carry_over(&mut self, accounts: &RefCell<U>)
+    where
+        U: Default,
+    {
+        if let Some(stateful) = &mut self.stateful {
+            stateful.iterations_since_reset += 1;
+            if stateful.iterations_since_reset < stateful.reset_interval {
+                stateful.carried_accounts = Some(accounts.take());
+            }
+        }
+    }
+}
+
 impl<T, U> FuzzData<T, U>
 where
     T: FuzzTestExecutor<U> + Display,
+    U: Clone + Default,
 {
-    pub fn run_with_runtime(
+    pub fn run_with_runtime<C: FuzzClient>(
         &self,
-        client: &mut impl FuzzClient,
+        client: &mut C,
+        initial_state: &mut InitialState<C::StateSnapshot, U>,
         config: &TridentConfig,
     ) -> core::result::Result<(), Box<dyn Error + 'static>> {
         // solana_logger::setup_with_default("off");
@@ -72,18 +135,61 @@ where
             eprintln!("------ End of Instructions sequence ------ ");
         }
 
-        for fuzz_ix in &mut self.iter() {
+        set_mutations_input(&self.mutations_input);
+
+        let carried_accounts = initial_state
+            .stateful
+            .as_mut()
+            .and_then(|stateful| stateful.carried_accounts.take());
+
+        if let Some(accounts) = carried_accounts {
+            // stateful mode, continue from the state the previous iteration ended with
+            self.accounts.replace(accounts);
+        } else {
+            // every iteration starts from the same state, e.g. accounts and warps do not leak between iterations
+            client.restore_snapshot(&initial_state.snapshot);
+            if let Some(stateful) = &mut initial_state.stateful {
+                stateful.iterations_since_reset = 0;
+            }
+
+            if let Some(accounts) = &initial_state.accounts {
+                // the pre_ixs were already executed, their result is part of the snapshot
+                self.accounts.replace(accounts.clone());
+            } else {
+                if !self.execute_ixs(self.pre_ixs.iter(), client, config) {
+                    return Ok(());
+                }
+
+                if config.get_snapshot_pre_ixs() {
+                    initial_state.snapshot = client.take_snapshot();
+                    initial_state.accounts = Some(self.accounts.borrow().clone());
+                }
+            }
+        }
+
+        self.execute_ixs(self.ixs.iter().chain(self.post_ixs.iter()), client, config);
+
+        initial_state.carry_over(&self.accounts);
+        Ok(())
+    }
+
+    // Returns false if any of the instructions failed, the following instructions are skipped.
+    fn execute_ixs<'a>(
+        &'a self,
+        ixs: impl Iterator<Item = &'a T>,
+        client: &mut impl FuzzClient,
+        config: &TridentConfig,
+    ) -> bool {
+        for fuzz_ix in ixs {
             // #[cfg(feature = "fuzzing_debug")]
             println!("\x1b[34mCurrently processing\x1b[0m: {}", fuzz_ix);
 
             if fuzz_ix.run_fuzzer(&self.accounts, client, config).is_err() {
                 // for now skip following instructions in case of error and move to the next fuzz iteration
-                client.clear_accounts();
-                return Ok(());
+                return false;
             }
         }
-        client.clear_accounts();
-        Ok(())
+        true
     }
 }
 
@@ -110,14 +216,151 @@ pub trait FuzzDataBuilder<T: for<'a> Arbitrary<'a>> {
     }
 }
 
-pub fn build_ix_fuzz_data<U: for<'a> Arbitrary<'a>, T: FuzzDataBuilder<U>, V: Default>(
+/// Builds the instructions of an iteration from the fuzzer input, the FuzzAccounts are built
+/// with the config, see [`AccountStorages`]
+pub fn build_ix_fuzz_data<U: for<'a> Arbitrary<'a>, T: FuzzDataBuilder<U>, V: AccountStorages>(
     _data_builder: T,
     u: &mut arbitrary::Unstructured,
+    config: &TridentConfig,
 ) -> arbitrary::Result<FuzzData<U, V>> {
     Ok(FuzzData {
         pre_ixs: T::pre_ixs(u)?,
         ixs: T::ixs(u)?,
         post_ixs: T::post_ixs(u)?,
-        accounts: RefCell::new(V::default()),
+        accounts: RefCell::new(new_fuzz_accounts(config)),
+        mutations_input: u.bytes(u.len())?.to_vec(),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::FuzzClientErrorWithOrigin;
+    use crate::fuzz_client_impl::TridentClient;
+    use solana_sdk::account::{AccountSharedData, ReadableAccount};
+    use solana_sdk::clock::Clock;
+    use solana_sdk::hash::Hash;
+    use solana_sdk::pubkey::Pubkey;
+
+    const SETUP_ACCOUNT: Pubkey = Pubkey::new_from_array([1; 32]);
+    const ITERATION_ACCOUNT: Pubkey = Pubkey::new_from_array([2; 32]);
+
+    // state observed at the start of an iteration
+    #[derive(Debug, PartialEq)]
+    struct Observed {
+        setup_lamports: u64,
+        iteration_lamports: u64,
+        slot: u64,
+        unix_timestamp: i64,
+        last_blockhash: Hash,
+    }
+
+    thread_local! {
+        static SETUP_RUNS: RefCell<u64> = const { RefCell::new(0) };
+        static OBSERVED: RefCell<Vec<Observed>> = const { RefCell::new(Vec::new()) };
+    }
+
+    enum TestIx {
+        Setup,
+        Iteration,
+    }
+
+    impl Display for TestIx {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                TestIx::Setup => write!(f, "Setup"),
+                TestIx::Iteration => write!(f, "Iteration"),
+            }
+        }
+    }
+
+    impl FuzzTestExecutor<()> for TestIx {
+        fn run_fuzzer(
+            &self,
+            _accounts: &RefCell<()>,
+            client: &mut impl FuzzClient,
+            _config: &TridentConfig,
+        ) -> core::result::Result<(), FuzzClientErrorWithOrigin> {
+            match self {
+                TestIx::Setup => {
+                    SETUP_RUNS.with(|runs| *runs.borrow_mut() += 1);
+                    client.set_account_custom(
+                        &SETUP_ACCOUNT,
+                        &AccountSharedData::new(1, 0, &Pubkey::default()),
+                    );
+                    client.warp_to_slot(10);
+                }
+                TestIx::Iteration => {
+                    let clock = client.get_sysvar::<Clock>();
+                    let observed = Observed {
+                        setup_lamports: client.get_account(&SETUP_ACCOUNT).lamports(),
+                        iteration_lamports: client.get_account(&ITERATION_ACCOUNT).lamports(),
+                        slot: clock.slot,
+                        unix_timestamp: clock.unix_timestamp,
+                        last_blockhash: client.get_last_blockhash(),
+                    };
+                    OBSERVED.with(|all| all.borrow_mut().push(observed));
+
+                    // everything below has to be undone before the next iteration
+                    client.set_account_custom(
+                        &ITERATION_ACCOUNT,
+                        &AccountSharedData::new(5, 0, &Pubkey::default()),
+                    );
+                    client.warp_to_slot(500);
+                    client.forward_in_time(1_000);
+                }
+            }
+            Ok(())
+        }
+    }
+
+    fn run_iterations(manifest: &str) -> (u64, Vec<Observed>) {
+        SETUP_RUNS.with(|runs| runs.replace(0));
+        OBSERVED.with(|all| all.borrow_mut().clear());
+
+        let config: TridentConfig = toml::from_str(manifest).unwrap();
+        let mut client = TridentClient::new_client(&[], &config);
+        let mut initial_state = InitialState::new(&client, &config);
+
+        for _ in 0..3 {
+            let fuzz_data = FuzzData {
+                pre_ixs: vec![TestIx::Setup],
+                ixs: vec![TestIx::Iteration],
+                post_ixs: vec![],
+                accounts: RefCell::new(()),
+                mutations_input: vec![],
+            };
+            fuzz_data
+                .run_with_runtime(&mut client, &mut initial_state, &config)
+                .unwrap();
+        }
+
+        (
+            SETUP_RUNS.with(|runs| *runs.borrow()),
+            OBSERVED.with(|all| all.take()),
+        )
+    }
+
+    fn assert_same_start(observed: &[Observed]) {
+        assert_eq!(observed.len(), 3);
+        assert_eq!(observed[0].setup_lamports, 1);
+        assert_eq!(observed[0].iteration_lamports, 0);
+        assert_eq!(observed[0].slot, 10);
+        assert!(observed.iter().all(|o| *o == observed[0]));
+    }
+
+    #[test]
+    fn test_restore_resets_state_between_iterations() {
+        let (setup_runs, observed) = run_iterations("[fuzz]");
+        assert_eq!(setup_runs, 3);
+        assert_same_start(&observed);
+    }
+
+    #[test]
+    fn test_restore_snapshot_of_pre_ixs() {
+        let (setup_runs, observed) = run_iterations("[fuzz]\nsnapshot_pre_ixs = true");
+        // the pre_ixs are executed only once, the next iterations start from their snapshot
+        assert_eq!(setup_runs, 1);
+        assert_same_start(&observed);
+    }
+}
@@ -7,37 +7,70 @@ macro_rules! fuzz_trident {
             $client:ident: $client_dty:ident,
             $config:ident: $config_dty:ident
         |) => {
+        let mut initial_state = InitialState::new(&$client, &$config);
+
+        // crash chains of the stateful mode are replayed in a single process, in order
+        if let Some(crash_chain) = load_crash_chain() {
+            // same as the fuzzers, any panic including those of the programs is a crash
+            let prev_hook = std::panic::take_hook();
+            std::panic::set_hook(Box::new(move |panic_info| {
+                prev_hook(panic_info);
+                std::process::abort();
+            }));
+
+            for input in &crash_chain {
+                let mut $buf: FuzzData<$ix_dty, _> = {
+                    use arbitrary::Unstructured;
+
+                    let mut buf = Unstructured::new(input);
+                    if let Ok(fuzz_data) = build_ix_fuzz_data($dty {}, &mut buf, &$config) {
+                        fuzz_data
+                    } else {
+                        continue;
+                    }
+                };
+                initial_state.record_input(input);
+                let _ = $buf.run_with_runtime(&mut $client, &mut initial_state, &$config);
+            }
+            eprintln!("This crash chain didn't trigger any panics...");
+            std::process::exit(2);
+        }
+
         if cfg!(honggfuzz) {
             loop {
                 fuzz_honggfuzz(|$buf| {
+                    let input = $buf;
                     let mut $buf: FuzzData<$ix_dty, _> = {
                         use arbitrary::Unstructured;
 
                         let mut buf = Unstructured::new($buf);
-                        if let Ok(fuzz_data) = build_ix_fuzz_data($dty {}, &mut buf) {
+                        if let Ok(fuzz_data) = build_ix_fuzz_data($dty {}, &mut buf, &$config) {
                             fuzz_data
                         } else {
                             return;
                         }
                     };
+                    initial_state.record_input(input);
                     // TODO: the function might not need to return anything
-                    let _ = $buf.run_with_runtime(&mut $client, &$config);
+                    let _ = $buf.run_with_runtime(&mut $client, &mut initial_state, &$config);
                 });
             }
         } else if cfg!(afl) {
             fuzz_afl(true, |$buf| {
+                let input = $buf;
                 let mut $buf: FuzzData<$ix_dty, _> = {
                     use arbitrary::Unstructured;
 
                     let mut buf = Unstructured::new($buf);
-                    if let Ok(fuzz_data) = build_ix_fuzz_data($dty {}, &mut buf) {
+                    if let Ok(fuzz_data) = build_ix_fuzz_data($dty {}, &mut buf, &$config) {
                         fuzz_data
                     } else {
                         return;
                     }
                 };
+                initial_state.record_input(input);
                 // TODO: the function might not need to return anything
-                let _ = $buf.run_with_runtime(&mut $client, &$config);
+                let _ = $buf.run_with_runtime(&mut $client, &mut initial_state, &$config);
             });
         } else {
             panic!("Select Honggfuzz or AFL for fuzzing!!!")
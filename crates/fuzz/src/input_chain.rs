@@ -0,0 +1,169 @@
+use std::cell::Cell;
+use std::cell::RefCell;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use solana_sdk::hash::hashv;
+use trident_config::constants::CRASH_CHAIN_ENV;
+
+thread_local! {
+    // set while the SVM executes programs, which catches their panics
+    static EXECUTING_PROGRAMS: Cell<bool> = const { Cell::new(false) };
+    // chain stored on a panic of a program, it is removed if the SVM caught the panic
+    static PENDING_CHAIN: RefCell<Option<PathBuf>> = const { RefCell::new(None) };
+}
+
+/// Executes the programs in the SVM. A panic of a program is a crash only if the fuzzer
+/// aborts on it, otherwise the panic is caught by the SVM and its chain is removed.
+pub(crate) fn execute_programs<R>(execute: impl FnOnce() -> R) -> R {
+    EXECUTING_PROGRAMS.with(|executing| executing.set(true));
+    let result = execute();
+    EXECUTING_PROGRAMS.with(|executing| executing.set(false));
+
+    if let Some(chain_dir) = PENDING_CHAIN.with(RefCell::take) {
+        let _ = fs::remove_dir_all(chain_dir);
+    }
+    result
+}
+
+/// Inputs executed since the last state reset. In the stateful mode a crash can depend
+/// on all of them, so the whole chain is stored once the fuzzed program panics.
+pub(crate) struct InputChain {
+    inputs: Arc<Mutex<Vec<Vec<u8>>>>,
+    crash_chains_dir: PathBuf,
+    panic_hook_installed: bool,
+}
+
+impl InputChain {
+    pub(crate) fn new(crash_chains_dir: PathBuf) -> Self {
+        Self {
+            inputs: Arc::new(Mutex::new(Vec::new())),
+            crash_chains_dir,
+            panic_hook_installed: false,
+        }
+    }
+
+    pub(crate) fn push(&mut self, input: &[u8]) {
+        // the fuzzers set their own panic hook before the first input is executed,
+        // so our hook has to be installed afterwards and call theirs at the end
+        if !self.panic_hook_installed {
+            install_panic_hook(self.inputs.clone(), self.crash_chains_dir.clone());
+            self.panic_hook_installed = true;
+        }
+
+        if let Ok(mut inputs) = self.inputs.lock() {
+            inputs.push(input.to_vec());
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        if let Ok(mut inputs) = self.inputs.lock() {
+            inputs.clear();
+        }
+    }
+}
+
+fn install_panic_hook(inputs: Arc<Mutex<Vec<Vec<u8>>>>, crash_chains_dir: PathBuf) {
+    let prev_hook = std::panic::take_hook();
+
+    std::panic::set_hook(Box::new(move |panic_info| {
+        if let Ok(inputs) = inputs.try_lock() {
+            let chain_dir = chain_dir(&crash_chains_dir, &inputs);
+            // the same chain might have already crashed in another fuzzing process
+            if !chain_dir.exists() {
+                match store_chain(&chain_dir, &inputs) {
+                    // the fuzzers abort in their panic hook, otherwise the SVM catches the panic
+                    Ok(()) if EXECUTING_PROGRAMS.with(Cell::get) => {
+                        PENDING_CHAIN.with(|pending| pending.replace(Some(chain_dir)));
+                    }
+                    Ok(()) => eprintln!(
+                        "\x1b[31mCrash chain\x1b[0m: inputs leading to the crash stored in {}",
+                        chain_dir.display()
+                    ),
+                    Err(err) => {
+                        eprintln!("\x1b[31mUnable to store the crash chain\x1b[0m: {}", err)
+                    }
+                }
+            }
+        }
+        prev_hook(panic_info);
+    }));
+}
+
+/// Inputs of the crash chain to replay, from the directory in the `TRIDENT_CRASH_CHAIN`
+/// environment variable. The inputs are sorted by their file names.
+pub fn load_crash_chain() -> Option<Vec<Vec<u8>>> {
+    let chain_dir = PathBuf::from(std::env::var_os(CRASH_CHAIN_ENV)?);
+
+    let mut input_files: Vec<PathBuf> = fs::read_dir(&chain_dir)
+        .unwrap_or_else(|err| panic!("failed to read the crash chain {:?}: {}", chain_dir, err))
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file())
+        .collect();
+    input_files.sort();
+
+    Some(
+        input_files
+            .iter()
+            .map(|input_file| {
+                fs::read(input_file).unwrap_or_else(|err| {
+                    panic!("failed to read the input {:?}: {}", input_file, err)
+                })
+            })
+            .collect(),
+    )
+}
+
+fn chain_dir(crash_chains_dir: &Path, inputs: &[Vec<u8>]) -> PathBuf {
+    let chain_hash = hashv(&inputs.iter().map(Vec::as_slice).collect::<Vec<_>>());
+    crash_chains_dir.join(format!("chain-{}", chain_hash))
+}
+
+/// Stores the inputs as numbered files, the last one is the input which caused the crash.
+fn store_chain(chain_dir: &Path, inputs: &[Vec<u8>]) -> io::Result<()> {
+    fs::create_dir_all(chain_dir)?;
+    for (index, input) in inputs.iter().enumerate() {
+        fs::write(chain_dir.join(format!("{:06}.fuzz", index)), input)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crash_chain_is_replayed_in_order() {
+        let crash_chains_dir = std::env::temp_dir().join("trident_test_crash_chains");
+        let inputs: Vec<Vec<u8>> = (0..12u8).map(|index| vec![index; 3]).collect();
+
+        let chain_dir = chain_dir(&crash_chains_dir, &inputs);
+        store_chain(&chain_dir, &inputs).unwrap();
+
+        std::env::set_var(CRASH_CHAIN_ENV, &chain_dir);
+        assert_eq!(load_crash_chain(), Some(inputs));
+        std::env::remove_var(CRASH_CHAIN_ENV);
+        assert_eq!(load_crash_chain(), None);
+
+        fs::remove_dir_all(&chain_dir).unwrap();
+    }
+
+    #[test]
+    fn test_caught_panic_removes_chain() {
+        let crash_chains_dir = std::env::temp_dir().join("trident_test_caught_panic");
+        let chain_dir = chain_dir(&crash_chains_dir, &[vec![1, 2, 3]]);
+
+        // same as the panic hook during the program execution
+        execute_programs(|| {
+            store_chain(&chain_dir, &[vec![1, 2, 3]]).unwrap();
+            PENDING_CHAIN.with(|pending| pending.replace(Some(chain_dir.clone())));
+        });
+
+        assert!(!chain_dir.exists());
+        assert!(PENDING_CHAIN.with(|pending| pending.borrow().is_none()));
+    }
+}
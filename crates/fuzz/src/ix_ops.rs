@@ -1,9 +1,12 @@
 #![allow(dead_code)]
 
+use crate::account_mutations::AccountMutation;
+use crate::data_mutations::{DataLayout, DataMutation, DataMutationOutcome};
 use crate::error::*;
 use crate::fuzz_client::FuzzClient;
 use crate::snapshot::SnapshotAccount;
 use solana_sdk::instruction::AccountMeta;
+use solana_sdk::pubkey::Pubkey;
 use solana_sdk::signature::Keypair;
 use solana_sdk::transaction::TransactionError;
 
@@ -43,6 +46,79 @@ pub trait IxOps {
         fuzz_accounts: &mut Self::IxAccounts,
     ) -> Result<(Vec<Keypair>, Vec<AccountMeta>), FuzzingError>;
 
+    /// Provides address lookup tables for the fuzzed instruction. By default, `None` is returned and the instruction
+    /// is sent in a legacy transaction. If you return `Some`, the instruction is sent in a versioned (v0) transaction
+    /// and the accounts stored in the lookup tables are loaded through them.
+    #[allow(unused_variables)]
+    fn get_lookup_tables(
+        &self,
+        client: &mut impl FuzzClient,
+        fuzz_accounts: &mut Self::IxAccounts,
+    ) -> Result<Option<Vec<Pubkey>>, FuzzingError> {
+        Ok(None)
+    }
+
+    /// Provides additional accounts captured in the snapshots before and after the instruction, e.g. accounts
+    /// modified only through CPI or PDAs derived inside the program. They are passed to `check` after the
+    /// instruction accounts. By default, no additional accounts are captured.
+    #[allow(unused_variables)]
+    fn get_watched_accounts(
+        &self,
+        client: &mut impl FuzzClient,
+        fuzz_accounts: &mut Self::IxAccounts,
+    ) -> Result<Vec<Pubkey>, FuzzingError> {
+        Ok(vec![])
+    }
+
+    /// A method to handle an instruction which succeeded with mutated account metas. If `account_mutations` is
+    /// enabled in Trident.toml, the instruction is first executed with the account metas returned by `get_accounts`
+    /// mutated by the fuzzer and the state is restored afterwards. Such an instruction might be missing an account
+    /// constraint, by default a crash is detected. Return `Ok(())` for the mutations your program accepts on purpose.
+    #[allow(unused_variables)]
+    fn account_mutations_handler(&self, mutations: &[AccountMutation]) -> Result<(), FuzzingError> {
+        Err(FuzzingError::with_message(&format!(
+            "Instruction succeeded with mutated accounts: {:?}",
+            mutations
+        )))
+    }
+
+    /// Provides the offsets of the enum tags and length prefixes in the instruction `data` following the
+    /// discriminator, usually with `DataLayout::from_data` and the layouts of the instruction arguments.
+    /// `EnumTag` and `InvalidLength` mutations are applied only at these offsets. By default, no offsets are
+    /// known and these mutations are skipped.
+    #[allow(unused_variables)]
+    fn get_data_layout(&self, data: &[u8]) -> DataLayout {
+        DataLayout::default()
+    }
+
+    /// A method to handle the outcome of an instruction executed with malformed data. If `data_mutations` is
+    /// enabled in Trident.toml, the instruction is first executed with the data returned by `get_data` mutated
+    /// by the fuzzer and the state is restored afterwards. By default, a crash
+    /// is detected if the instruction succeeded or the program panicked, a clean deserialization error or
+    /// any other error is accepted. Success with only appended bytes is accepted, as Anchor programs
+    /// ignore trailing bytes of the instruction data.
+    fn data_mutations_handler(
+        &self,
+        mutations: &[DataMutation],
+        outcome: DataMutationOutcome,
+    ) -> Result<(), FuzzingError> {
+        let only_extended = mutations
+            .iter()
+            .all(|mutation| matches!(mutation, DataMutation::Extend { .. }));
+        match outcome {
+            DataMutationOutcome::Succeeded if only_extended => Ok(()),
+            DataMutationOutcome::Succeeded | DataMutationOutcome::Panicked => {
+                Err(FuzzingError::with_message(&format!(
+                    "Instruction {:?} with malformed data: {:?}",
+                    outcome, mutations
+                )))
+            }
+            DataMutationOutcome::DeserializationError | DataMutationOutcome::OtherError(_) => {
+                Ok(())
+            }
+        }
+    }
+
     /// A method to implement custom invariants checks for a given instruction. This method is called after each
     /// successfully executed instruction and by default does nothing. You can override this behavior by providing
     /// your own implementation. You can access the snapshots of account states before and after the transaction for comparison.
@@ -1,7 +1,13 @@
+pub mod account_mutations;
 pub mod accounts_storage;
+mod blockhash_queue;
+pub mod constraints;
+pub mod data_mutations;
 pub mod error;
 pub mod fuzz_data;
 pub mod fuzz_stats;
+mod input_chain;
+pub mod remaining_accounts;
 pub mod snapshot;
 pub type AccountId = u8;
 pub mod fuzz_client;
@@ -28,6 +34,7 @@ pub mod fuzzing {
     pub use solana_sdk::signer::Signer;
     pub use solana_sdk::transaction::Transaction;
     pub use solana_sdk::transaction::TransactionError;
+    pub use solana_sdk::transaction::VersionedTransaction;
 
     pub use afl::fuzz as fuzz_afl;
     pub use arbitrary;
@@ -36,6 +43,7 @@ pub mod fuzzing {
 
     /// trident derive
     pub use trident_derive_displayix::DisplayIx;
+    pub use trident_derive_fuzz_constraints::fuzz_constraints;
     pub use trident_derive_fuzz_test_executor::FuzzTestExecutor;
     pub use trident_svm::processor;
 
@@ -48,7 +56,14 @@ pub mod fuzzing {
     pub use super::*;
 
     /// trident methods
+    pub use super::account_mutations::AccountMutation;
     pub use super::accounts_storage::*;
+    pub use super::constraints;
+    pub use super::data_mutations::DataLayout;
+    pub use super::data_mutations::DataMutation;
+    pub use super::data_mutations::DataMutationOutcome;
+    pub use super::data_mutations::DataType;
+    pub use super::remaining_accounts::RemainingAccount;
     pub use trident_config::TridentConfig;
 
     pub use super::error::*;
@@ -57,16 +72,21 @@ pub mod fuzzing {
     pub use super::fuzz_data::*;
     pub use super::fuzz_stats::FuzzingStatistics;
     pub use super::fuzz_test_executor::FuzzTestExecutor;
+    pub use super::input_chain::load_crash_chain;
     pub use super::ix_ops::IxOps;
     pub use super::snapshot::Snapshot;
     pub use super::snapshot::SnapshotAccount;
+    pub use super::snapshot::{diff_fields, FieldDiff};
     pub use super::transaction_executor::TransactionExecutor;
 
+    pub use super::fuzz_client_impl::TridentClient;
+    #[allow(deprecated)]
+    pub use super::fuzz_client_impl::TridentSVM;
     pub use std::cell::RefCell;
     pub use std::collections::HashMap;
-    pub use trident_svm::trident_svm::TridentSVM;
     pub use trident_svm::utils::ProgramEntrypoint;
 
+    pub use super::accounts_storage::AtaStore;
     pub use super::accounts_storage::KeypairStore;
     pub use super::accounts_storage::PdaStore;
 }
@@ -0,0 +1,82 @@
+use arbitrary::Arbitrary;
+use solana_sdk::instruction::AccountMeta;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signer::Signer;
+
+use crate::accounts_storage::derive_keypair;
+use crate::AccountId;
+
+/// Maximal number of remaining accounts passed to an Instruction
+pub const MAX_REMAINING_ACCOUNTS: usize = 8;
+
+/// Account passed to the program after the accounts of the Instruction,
+/// the address is picked from the pool of known addresses by the AccountId.
+#[derive(Arbitrary, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RemainingAccount {
+    pub account_id: AccountId,
+    pub is_writable: bool,
+}
+
+impl RemainingAccount {
+    /// AccountMeta of the remaining account, remaining accounts are never signers
+    pub fn to_account_meta(&self, addresses: &[Pubkey]) -> AccountMeta {
+        let pubkey = pick_address(addresses, self.account_id);
+        match self.is_writable {
+            true => AccountMeta::new(pubkey, false),
+            false => AccountMeta::new_readonly(pubkey, false),
+        }
+    }
+}
+
+/// Address selected by the AccountId, derived from the AccountId if there are no addresses
+pub(crate) fn pick_address(addresses: &[Pubkey], account_id: AccountId) -> Pubkey {
+    if addresses.is_empty() {
+        derive_keypair(0, "address-pool", account_id).pubkey()
+    } else {
+        addresses[usize::from(account_id) % addresses.len()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remaining_account_meta() {
+        let addresses = [Pubkey::new_unique(), Pubkey::new_unique()];
+
+        let writable = RemainingAccount {
+            account_id: 3,
+            is_writable: true,
+        };
+        assert_eq!(
+            writable.to_account_meta(&addresses),
+            AccountMeta::new(addresses[1], false)
+        );
+
+        let readonly = RemainingAccount {
+            account_id: 4,
+            is_writable: false,
+        };
+        assert_eq!(
+            readonly.to_account_meta(&addresses),
+            AccountMeta::new_readonly(addresses[0], false)
+        );
+    }
+
+    #[test]
+    fn test_pick_address() {
+        let addresses = [
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        assert_eq!(pick_address(&addresses, 0), addresses[0]);
+        assert_eq!(pick_address(&addresses, 5), addresses[2]);
+        assert_eq!(pick_address(&addresses, u8::MAX), addresses[0]);
+
+        // without addresses the address is derived from the AccountId
+        assert_eq!(pick_address(&[], 7), pick_address(&[], 7));
+        assert_ne!(pick_address(&[], 7), pick_address(&[], 8));
+    }
+}
@@ -1,10 +1,14 @@
 #![allow(dead_code)] // The Snapshot is constructed in the FuzzTestExecutor macro and is generated automatically
 
+use std::fmt::{Debug, Display};
+
+use borsh::BorshDeserialize;
 use solana_sdk::account::{AccountSharedData, ReadableAccount};
 use solana_sdk::clock::Epoch;
 use solana_sdk::instruction::AccountMeta;
 use solana_sdk::pubkey::Pubkey;
 
+use crate::accounts_storage::Discriminator;
 use crate::fuzz_client::FuzzClient;
 
 use crate::error::*;
@@ -25,10 +29,10 @@ impl SnapshotAccount {
         self.account.data()
     }
     pub fn data_no_discriminator(&self) -> &[u8] {
-        if self.account.data().len() > 8 {
+        if self.account.data().len() >= 8 {
             &self.account.data()[8..]
         } else {
-            panic!("Account does not contain more than 8 bytes")
+            panic!("Account does not contain the 8 bytes discriminator")
         }
     }
     pub fn lamports(&self) -> u64 {
@@ -43,11 +47,345 @@ impl SnapshotAccount {
     pub fn rent_epoch(&self) -> Epoch {
         self.account.rent_epoch()
     }
+    /// Deserializes the account data into `T`, the data have to start with the discriminator of `T`.
+    /// Trailing bytes after the serialized `T` are allowed as the accounts are usually allocated
+    /// with extra space.
+    pub fn try_deserialize<T: BorshDeserialize + Discriminator>(&self) -> Result<T, FuzzingError> {
+        self.try_deserialize_with_discriminator(T::DISCRIMINATOR)
+    }
+    /// Same as [`Self::try_deserialize`] with the given `discriminator`, e.g. `Account::DISCRIMINATOR`
+    /// of an Anchor account
+    pub fn try_deserialize_with_discriminator<T: BorshDeserialize>(
+        &self,
+        discriminator: &[u8],
+    ) -> Result<T, FuzzingError> {
+        let data = self
+            .data()
+            .strip_prefix(discriminator)
+            .ok_or(FuzzingError::InvalidDiscriminator(self.address))?;
+        T::deserialize(&mut &data[..])
+            .map_err(|e| FuzzingError::DeserializationFailed(self.address, e.to_string()))
+    }
+    /// Deserializes this and the `after` version of the account into `T` and compares them field by field
+    pub fn diff<T: BorshDeserialize + Discriminator + Debug>(
+        &self,
+        after: &SnapshotAccount,
+    ) -> Result<Vec<FieldDiff>, FuzzingError> {
+        self.diff_with_discriminator::<T>(after, T::DISCRIMINATOR)
+    }
+    /// Same as [`Self::diff`] with the given `discriminator`
+    pub fn diff_with_discriminator<T: BorshDeserialize + Debug>(
+        &self,
+        after: &SnapshotAccount,
+        discriminator: &[u8],
+    ) -> Result<Vec<FieldDiff>, FuzzingError> {
+        let before = self.try_deserialize_with_discriminator::<T>(discriminator)?;
+        let after = after.try_deserialize_with_discriminator::<T>(discriminator)?;
+        Ok(diff_fields(&before, &after))
+    }
+}
+
+/// Field of a typed account which differs between two versions of the account
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldDiff {
+    /// Path to the field, e.g. `config.fees[1]`
+    pub field: String,
+    pub before: String,
+    pub after: String,
+}
+
+impl Display for FieldDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {} -> {}", self.field, self.before, self.after)
+    }
+}
+
+/// Compares two values field by field, based on the value tree parsed from their pretty printed
+/// `Debug` output. Fields present only in one of the values (e.g. different lengths of vectors or
+/// enum variants) are reported with `-` in place of the missing value. Entries of maps and sets
+/// are matched by their keys, so the iteration order of e.g. `HashMap` does not matter.
+pub fn diff_fields<T: Debug>(before: &T, after: &T) -> Vec<FieldDiff> {
+    let before = parse_debug(&format!("{before:#?}"));
+    let after = parse_debug(&format!("{after:#?}"));
+
+    let mut diffs = vec![];
+    diff_nodes("", Some(&before), Some(&after), &mut diffs);
+    diffs
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Container {
+    // structs and struct variants
+    Struct,
+    // vectors, arrays, tuples and tuple variants
+    Sequence,
+    // maps and sets
+    Braces,
+}
+
+// Value parsed from the pretty printed `Debug` output
+enum DebugNode {
+    Leaf(String),
+    Container {
+        // the struct name or the enum variant, empty for vectors, tuples, maps and sets
+        type_name: String,
+        container: Container,
+        // path segments of the items with their values, the items of maps and sets are
+        // sorted by their keys
+        items: Vec<(String, DebugNode)>,
+    },
+}
+
+impl DebugNode {
+    // Value reported for the node itself, unnamed containers are described by their items
+    // unless they are empty
+    fn header(&self) -> Option<String> {
+        match self {
+            DebugNode::Leaf(value) => Some(value.clone()),
+            DebugNode::Container { type_name, .. } if !type_name.is_empty() => {
+                Some(type_name.clone())
+            }
+            DebugNode::Container {
+                container, items, ..
+            } if items.is_empty() => match container {
+                Container::Braces => Some("{}".to_string()),
+                _ => Some("[]".to_string()),
+            },
+            DebugNode::Container { .. } => None,
+        }
+    }
+
+    fn items(&self) -> &[(String, DebugNode)] {
+        match self {
+            DebugNode::Leaf(_) => &[],
+            DebugNode::Container { items, .. } => items,
+        }
+    }
+}
+
+fn diff_nodes(
+    field: &str,
+    before: Option<&DebugNode>,
+    after: Option<&DebugNode>,
+    diffs: &mut Vec<FieldDiff>,
+) {
+    match (before, after) {
+        (
+            Some(DebugNode::Container {
+                type_name: before_name,
+                container: before_container,
+                items: before_items,
+            }),
+            Some(DebugNode::Container {
+                type_name: after_name,
+                container: after_container,
+                items: after_items,
+            }),
+        ) if before_container == after_container => {
+            if before_name != after_name {
+                diffs.push(FieldDiff {
+                    field: field.to_string(),
+                    before: before_name.clone(),
+                    after: after_name.clone(),
+                });
+            }
+            diff_items(field, before_items, after_items, diffs);
+        }
+        _ => {
+            // leaves, values present only on one side or enum variants of a different kind
+            let before_header = before.and_then(DebugNode::header);
+            let after_header = after.and_then(DebugNode::header);
+            if before_header != after_header {
+                diffs.push(FieldDiff {
+                    field: field.to_string(),
+                    before: before_header.unwrap_or_else(|| "-".to_string()),
+                    after: after_header.unwrap_or_else(|| "-".to_string()),
+                });
+            }
+            diff_items(
+                field,
+                before.map(DebugNode::items).unwrap_or_default(),
+                after.map(DebugNode::items).unwrap_or_default(),
+                diffs,
+            );
+        }
+    }
+}
+
+// Matches the items by their path segments, the items present only after follow the others
+fn diff_items(
+    field: &str,
+    before: &[(String, DebugNode)],
+    after: &[(String, DebugNode)],
+    diffs: &mut Vec<FieldDiff>,
+) {
+    let mut segments: Vec<&str> = before.iter().map(|(segment, _)| segment.as_str()).collect();
+    segments.extend(
+        after
+            .iter()
+            .map(|(segment, _)| segment.as_str())
+            .filter(|segment| !before.iter().any(|(s, _)| s == segment)),
+    );
+
+    for segment in segments {
+        let mut item_field = field.to_string();
+        if !item_field.is_empty() && !segment.starts_with('[') {
+            item_field.push('.');
+        }
+        item_field.push_str(segment);
+        diff_nodes(
+            &item_field,
+            find_item(before, segment),
+            find_item(after, segment),
+            diffs,
+        );
+    }
+}
+
+fn find_item<'a>(items: &'a [(String, DebugNode)], segment: &str) -> Option<&'a DebugNode> {
+    items
+        .iter()
+        .find(|(s, _)| s == segment)
+        .map(|(_, node)| node)
+}
+
+// Parses the pretty printed `Debug` output into a value tree
+fn parse_debug(debug: &str) -> DebugNode {
+    let mut lines = debug.lines();
+    let first = lines.next().unwrap_or_default().trim();
+    parse_node(first, &mut lines)
+}
+
+// Parses the value starting on the current line, the items of an opened container are read
+// from the following lines up to its closing line
+fn parse_node<'a>(value: &str, lines: &mut impl Iterator<Item = &'a str>) -> DebugNode {
+    let Some(container) = opened_container(value) else {
+        let empty = match value {
+            "[]" => Some(Container::Sequence),
+            "{}" => Some(Container::Braces),
+            _ => None,
+        };
+        return match empty {
+            Some(container) => DebugNode::Container {
+                type_name: String::default(),
+                container,
+                items: vec![],
+            },
+            None => DebugNode::Leaf(value.to_string()),
+        };
+    };
+
+    let type_name = value[..value.len() - 1].trim().to_string();
+    let mut items: Vec<(String, DebugNode)> = vec![];
+    while let Some(line) = lines.next() {
+        let line = line.trim().trim_end_matches(',');
+        if line.starts_with(['}', ']', ')']) {
+            break;
+        }
+
+        let item = match container {
+            Container::Struct => match split_entry(line) {
+                Some((name, value)) if is_field_name(name) => (
+                    name.trim_start_matches("r#").to_string(),
+                    parse_node(value, lines),
+                ),
+                _ => (format!("[{}]", items.len()), parse_node(line, lines)),
+            },
+            Container::Braces => match split_entry(line) {
+                Some((key, value)) => (format!("[{}]", key), parse_node(value, lines)),
+                // items of sets are their own keys
+                None => {
+                    let item = match opened_container(line) {
+                        Some(_) => compact_debug(line, lines),
+                        None => line.to_string(),
+                    };
+                    (format!("[{}]", item), DebugNode::Leaf(item))
+                }
+            },
+            Container::Sequence => (format!("[{}]", items.len()), parse_node(line, lines)),
+        };
+        items.push(item);
+    }
+    if container == Container::Braces {
+        items.sort_by(|(a, _), (b, _)| a.cmp(b));
+    }
+
+    DebugNode::Container {
+        type_name,
+        container,
+        items,
+    }
 }
+
+// Container opened at the end of the line
+fn opened_container(value: &str) -> Option<Container> {
+    match value.strip_suffix(['{', '[', '(']) {
+        Some(type_name) if value.ends_with('{') && type_name.trim().is_empty() => {
+            Some(Container::Braces)
+        }
+        Some(_) if value.ends_with('{') => Some(Container::Struct),
+        Some(_) => Some(Container::Sequence),
+        None => None,
+    }
+}
+
+// Single line `Debug` output of the item opened on the line, e.g. `Config { fee: 1 }`
+fn compact_debug<'a>(line: &str, lines: &mut impl Iterator<Item = &'a str>) -> String {
+    let mut compact = line.to_string();
+    let mut depth = 1;
+    for line in lines.by_ref() {
+        let line = line.trim().trim_end_matches(',');
+        if line.starts_with(['}', ']', ')']) {
+            depth -= 1;
+            if line.starts_with('}') {
+                compact.push(' ');
+            }
+        } else if compact.ends_with('{') {
+            compact.push(' ');
+        } else if !compact.ends_with(['[', '(']) {
+            compact.push_str(", ");
+        }
+        compact.push_str(line);
+        if opened_container(line).is_some() {
+            depth += 1;
+        }
+        if depth == 0 {
+            break;
+        }
+    }
+    compact
+}
+
+// Splits the line at the first `: ` outside of string and char literals
+fn split_entry(line: &str) -> Option<(&str, &str)> {
+    let mut quote = None;
+    let mut escaped = false;
+    for (index, c) in line.char_indices() {
+        match quote {
+            Some(_) if escaped => escaped = false,
+            Some(_) if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if line[index..].starts_with(": ") => {
+                return Some((&line[..index], &line[index + 2..]));
+            }
+            None => {}
+        }
+    }
+    None
+}
+
+fn is_field_name(name: &str) -> bool {
+    let name = name.trim_start_matches("r#");
+    !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
 pub struct Snapshot {
     before: Vec<SnapshotAccount>,
     after: Vec<SnapshotAccount>,
     metas: Vec<AccountMeta>,
+    watched: Vec<Pubkey>,
 }
 
 impl Snapshot {
@@ -56,6 +394,18 @@ impl Snapshot {
             before: Default::default(),
             after: Default::default(),
             metas: metas.to_vec(),
+            watched: Default::default(),
+        }
+    }
+    /// Registers additional accounts to be captured after the Instruction accounts,
+    /// accounts already passed to the Instruction are not captured twice
+    pub fn watch(&mut self, addresses: &[Pubkey]) {
+        for address in addresses {
+            if !self.metas.iter().any(|meta| meta.pubkey == *address)
+                && !self.watched.contains(address)
+            {
+                self.watched.push(*address);
+            }
         }
     }
     pub fn capture_before(
@@ -85,10 +435,12 @@ impl Snapshot {
         let snapshot_accounts = self
             .metas
             .iter()
-            .map(|meta| {
-                let account = client.get_account(&meta.pubkey);
+            .map(|meta| &meta.pubkey)
+            .chain(self.watched.iter())
+            .map(|address| {
+                let account = client.get_account(address);
                 SnapshotAccount {
-                    address: meta.pubkey,
+                    address: *address,
                     account,
                 }
             })
@@ -108,3 +460,187 @@ impl Snapshot {
         (self.get_before(), self.get_after())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashMap, HashSet};
+
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    struct Fee {
+        r#type: u8,
+        amounts: Vec<u64>,
+    }
+
+    #[derive(Debug, Clone)]
+    enum State {
+        Active,
+        Paused(u8, u8),
+        Closed { reason: String },
+    }
+
+    #[derive(Debug, Clone)]
+    struct Config {
+        authority: Pubkey,
+        fee: Fee,
+        state: State,
+        limits: (u16, u16),
+        fees: Vec<Fee>,
+        members: HashMap<String, Fee>,
+        flags: HashSet<u32>,
+        levels: BTreeMap<u8, u8>,
+        previous: HashSet<Fee>,
+    }
+
+    fn fee(r#type: u8, amounts: &[u64]) -> Fee {
+        Fee {
+            r#type,
+            amounts: amounts.to_vec(),
+        }
+    }
+
+    fn config() -> Config {
+        Config {
+            authority: Pubkey::new_from_array([1; 32]),
+            fee: fee(1, &[10, 20]),
+            state: State::Active,
+            limits: (1, 2),
+            fees: vec![fee(2, &[])],
+            members: (0..16)
+                .map(|index| (format!("member: {}", index), fee(index, &[])))
+                .collect(),
+            flags: (0..16).collect(),
+            levels: [(1, 2)].into_iter().collect(),
+            previous: (0..16).map(|index| fee(index, &[1])).collect(),
+        }
+    }
+
+    fn diffs(before: &Config, after: &Config) -> Vec<String> {
+        diff_fields(before, after)
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_equal_values_have_no_diff() {
+        // maps and sets are rebuilt, so their iteration order differs
+        let before = config();
+        let after = config();
+        assert_eq!(diffs(&before, &after), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_nested_structs_and_vectors() {
+        let before = config();
+        let mut after = config();
+        after.authority = Pubkey::new_from_array([2; 32]);
+        after.fee.r#type = 3;
+        after.fee.amounts.pop();
+        after.limits.1 = 5;
+        after.fees.push(fee(4, &[1]));
+
+        assert_eq!(
+            diffs(&before, &after),
+            [
+                format!("authority: {:?} -> {:?}", before.authority, after.authority),
+                "fee.type: 1 -> 3".to_string(),
+                "fee.amounts[1]: 20 -> -".to_string(),
+                "limits[1]: 2 -> 5".to_string(),
+                "fees[1]: - -> Fee".to_string(),
+                "fees[1].type: - -> 4".to_string(),
+                "fees[1].amounts[0]: - -> 1".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_empty_containers() {
+        let before = config();
+        let mut after = config();
+        after.fees[0].amounts.push(3);
+        after.fees.push(fee(5, &[]));
+        after.members.clear();
+
+        let mut expected = vec![
+            "fees[0].amounts[0]: - -> 3".to_string(),
+            "fees[1]: - -> Fee".to_string(),
+            "fees[1].type: - -> 5".to_string(),
+            "fees[1].amounts: - -> []".to_string(),
+        ];
+        let mut members: Vec<_> = before.members.keys().collect();
+        members.sort();
+        for member in members {
+            expected.extend([
+                format!("members[{:?}]: Fee -> -", member),
+                format!(
+                    "members[{:?}].type: {} -> -",
+                    member, before.members[member].r#type
+                ),
+                format!("members[{:?}].amounts: [] -> -", member),
+            ]);
+        }
+        assert_eq!(diffs(&before, &after), expected);
+
+        // an emptied map is not reported as a changed value
+        assert!(diffs(&after, &before)
+            .iter()
+            .all(|diff| !diff.starts_with("members: ")));
+    }
+
+    #[test]
+    fn test_enum_variants() {
+        let mut before = config();
+        let mut after = config();
+        after.state = State::Paused(1, 2);
+        assert_eq!(
+            diffs(&before, &after),
+            [
+                "state: Active -> Paused",
+                "state[0]: - -> 1",
+                "state[1]: - -> 2"
+            ]
+        );
+
+        before.state = State::Paused(1, 2);
+        after.state = State::Closed {
+            reason: "expired".to_string(),
+        };
+        assert_eq!(
+            diffs(&before, &after),
+            [
+                "state: Paused -> Closed",
+                "state[0]: 1 -> -",
+                "state[1]: 2 -> -",
+                "state.reason: - -> \"expired\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_maps_and_sets_are_diffed_by_key() {
+        let before = config();
+        let mut after = config();
+        after.members.get_mut("member: 3").unwrap().amounts.push(7);
+        after.members.remove("member: 5");
+        after.flags.remove(&4);
+        after.flags.insert(100);
+        after.levels.insert(1, 3);
+        after.previous.remove(&fee(2, &[1]));
+
+        assert_eq!(
+            diffs(&before, &after),
+            [
+                "members[\"member: 3\"].amounts[0]: - -> 7",
+                "members[\"member: 5\"]: Fee -> -",
+                "members[\"member: 5\"].type: 5 -> -",
+                "members[\"member: 5\"].amounts: [] -> -",
+                "flags[4]: 4 -> -",
+                "flags[100]: - -> 100",
+                "levels[1]: 2 -> 3",
+                "previous[Fee { type: 2, amounts: [1] }]: Fee { type: 2, amounts: [1] } -> -",
+            ]
+        );
+    }
+}
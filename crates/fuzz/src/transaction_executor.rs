@@ -1,7 +1,16 @@
 use std::cell::RefCell;
 
+use arbitrary::Arbitrary;
+use arbitrary::Unstructured;
+
+use solana_sdk::instruction::AccountMeta;
 use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::TransactionError;
 
+use crate::account_mutations::AccountMutation;
+use crate::accounts_storage::AccountStorages;
+use crate::data_mutations::{DataMutation, DataMutationOutcome};
 use crate::error::FuzzClientError;
 use crate::error::FuzzClientErrorWithOrigin;
 use crate::error::Origin;
@@ -12,6 +21,29 @@ use crate::snapshot::Snapshot;
 
 use trident_config::TridentConfig;
 
+thread_local! {
+    // remaining bytes of `FuzzData::mutations_input` of the current iteration
+    static MUTATIONS_INPUT: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Sets the bytes the account and data mutations of the following instructions are drawn from
+pub(crate) fn set_mutations_input(input: &[u8]) {
+    MUTATIONS_INPUT.with(|mutations_input| *mutations_input.borrow_mut() = input.to_vec());
+}
+
+// Draws the mutations of an instruction, the consumed bytes are not reused by the following
+// instructions. No mutations are drawn once the input is exhausted.
+fn draw_mutations<T: for<'a> Arbitrary<'a>>() -> Vec<T> {
+    MUTATIONS_INPUT.with(|mutations_input| {
+        let mut input = mutations_input.borrow_mut();
+        let mut u = Unstructured::new(&input);
+        let mutations = Vec::<T>::arbitrary(&mut u).unwrap_or_default();
+        let consumed = input.len() - u.len();
+        input.drain(..consumed);
+        mutations
+    })
+}
+
 pub struct TransactionExecutor;
 
 impl TransactionExecutor {
@@ -24,6 +56,7 @@ impl TransactionExecutor {
     ) -> core::result::Result<(), FuzzClientErrorWithOrigin>
     where
         I: IxOps,
+        I::IxAccounts: AccountStorages,
     {
         // Obtain the program id
         let program_id = ix.get_program_id();
@@ -40,8 +73,49 @@ impl TransactionExecutor {
             .map_err(|e| e.with_origin(Origin::Instruction(instruction_name.to_owned())))
             .expect("Accounts calculation expect");
 
+        // Obtain the additional accounts to be captured in the snapshot,
+        // both of the instruction and of every instruction
+        let mut watched_accounts = ix
+            .get_watched_accounts(client, &mut accounts.borrow_mut())
+            .map_err(|e| e.with_origin(Origin::Instruction(instruction_name.to_owned())))
+            .expect("Watched accounts calculation expect");
+        watched_accounts.extend(accounts.borrow().watched_accounts());
+
+        // Obtain the address lookup tables, if the instruction is sent in a versioned transaction
+        let lookup_tables = ix
+            .get_lookup_tables(client, &mut accounts.borrow_mut())
+            .map_err(|e| e.with_origin(Origin::Instruction(instruction_name.to_owned())))
+            .expect("Lookup tables calculation expect");
+
+        // Execute the instruction with mutated account metas first, if enabled
+        if config.get_account_mutations() {
+            Self::process_account_mutations(
+                instruction_name,
+                client,
+                ix,
+                program_id,
+                &data,
+                &account_metas,
+                lookup_tables.as_deref(),
+            );
+        }
+
+        // Execute the instruction with malformed data first, if enabled
+        if config.get_data_mutations() {
+            Self::process_data_mutations(
+                instruction_name,
+                client,
+                ix,
+                program_id,
+                &data,
+                &account_metas,
+                lookup_tables.as_deref(),
+            );
+        }
+
         // Initializes the snapshot from the account metas
         let mut snapshot = Snapshot::new(&account_metas);
+        snapshot.watch(&watched_accounts);
 
         // Capture the accounts before the instruction is executed
         snapshot.capture_before(client).unwrap();
@@ -60,7 +134,7 @@ impl TransactionExecutor {
             stats_logger.increase_invoked(instruction_name.to_owned());
 
             // Execute the instruction
-            let tx_result = client.process_instructions(&[ixx]);
+            let tx_result = Self::process_instruction(client, ixx, lookup_tables.as_deref());
 
             // Check the result of the instruction execution
             match tx_result {
@@ -106,7 +180,7 @@ impl TransactionExecutor {
             }
         } else {
             // If stats are not enabled, execute the instruction directly
-            let tx_result = client.process_instructions(&[ixx]);
+            let tx_result = Self::process_instruction(client, ixx, lookup_tables.as_deref());
             match tx_result {
                 Ok(_) => {
                     // Capture the accounts after the instruction is executed
@@ -138,4 +212,300 @@ impl TransactionExecutor {
         }
         Ok(())
     }
+
+    // The instruction is executed with the mutated account metas and the state is restored afterwards,
+    // success indicates a possibly missing account constraint.
+    fn process_account_mutations<I>(
+        instruction_name: &str,
+        client: &mut impl FuzzClient,
+        ix: &I,
+        program_id: Pubkey,
+        data: &[u8],
+        account_metas: &[AccountMeta],
+        lookup_tables: Option<&[Pubkey]>,
+    ) where
+        I: IxOps,
+    {
+        let mutations: Vec<AccountMutation> = draw_mutations();
+        if mutations.is_empty() {
+            return;
+        }
+
+        let state = client.take_snapshot();
+
+        let mut mutated_account_metas = account_metas.to_vec();
+        for mutation in &mutations {
+            mutation.apply(client, &mut mutated_account_metas);
+        }
+
+        // mutations such as swapping an account with itself do not change anything
+        if mutated_account_metas != account_metas {
+            let ixx = Instruction {
+                program_id,
+                accounts: mutated_account_metas,
+                data: data.to_vec(),
+            };
+
+            if Self::process_instruction(client, ixx, lookup_tables).is_ok() {
+                if let Err(e) = ix
+                    .account_mutations_handler(&mutations)
+                    .map_err(|e| e.with_origin(Origin::Instruction(instruction_name.to_owned())))
+                {
+                    eprintln!("\x1b[31mCRASH DETECTED!\x1b[0m The {} instruction succeeded with mutated accounts!",instruction_name.to_owned());
+                    panic!("{}", e)
+                }
+            }
+        }
+
+        client.restore_snapshot(&state);
+    }
+
+    // The instruction is executed with the malformed data and the state is restored afterwards,
+    // the outcome is classified by the data mutations handler.
+    fn process_data_mutations<I>(
+        instruction_name: &str,
+        client: &mut impl FuzzClient,
+        ix: &I,
+        program_id: Pubkey,
+        data: &[u8],
+        account_metas: &[AccountMeta],
+        lookup_tables: Option<&[Pubkey]>,
+    ) where
+        I: IxOps,
+    {
+        let mutations: Vec<DataMutation> = draw_mutations();
+        if mutations.is_empty() {
+            return;
+        }
+
+        let state = client.take_snapshot();
+
+        let discriminator_len = ix.get_discriminator().len().min(data.len());
+        let layout = ix.get_data_layout(&data[discriminator_len..]);
+        let mut mutated_data = data.to_vec();
+        for mutation in &mutations {
+            mutation.apply(&mut mutated_data, discriminator_len, &layout);
+        }
+
+        // mutations such as extending the data by no bytes do not change anything
+        if mutated_data != data {
+            let ixx = Instruction {
+                program_id,
+                accounts: account_metas.to_vec(),
+                data: mutated_data,
+            };
+
+            let outcome =
+                DataMutationOutcome::from(Self::process_instruction(client, ixx, lookup_tables));
+            if let Err(e) = ix
+                .data_mutations_handler(&mutations, outcome)
+                .map_err(|e| e.with_origin(Origin::Instruction(instruction_name.to_owned())))
+            {
+                eprintln!("\x1b[31mCRASH DETECTED!\x1b[0m The {} instruction did not reject malformed data!",instruction_name.to_owned());
+                panic!("{}", e)
+            }
+        }
+
+        client.restore_snapshot(&state);
+    }
+
+    fn process_instruction(
+        client: &mut impl FuzzClient,
+        ix: Instruction,
+        lookup_tables: Option<&[Pubkey]>,
+    ) -> Result<(), TransactionError> {
+        match lookup_tables {
+            Some(lookup_tables) => {
+                client.process_instructions_with_lookup_tables(&[ix], lookup_tables)
+            }
+            None => client.process_instructions(&[ix]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use solana_sdk::account::ReadableAccount;
+    use solana_sdk::signature::Keypair;
+    use solana_sdk::signer::Signer;
+
+    use crate::error::FuzzingError;
+    use crate::fuzz_client_impl::TridentClient;
+    use crate::snapshot::SnapshotAccount;
+
+    thread_local! {
+        static CAPTURED: RefCell<Vec<Pubkey>> = const { RefCell::new(Vec::new()) };
+        static ACCEPTED_MUTATIONS: RefCell<Vec<AccountMutation>> = const { RefCell::new(Vec::new()) };
+        static DATA_MUTATIONS: RefCell<Vec<(Vec<DataMutation>, DataMutationOutcome)>> = const { RefCell::new(Vec::new()) };
+    }
+
+    struct TestAccounts {
+        treasury: Pubkey,
+    }
+
+    impl AccountStorages for TestAccounts {
+        fn new(_config: &TridentConfig) -> Self {
+            Self {
+                treasury: Pubkey::new_unique(),
+            }
+        }
+
+        fn watched_accounts(&self) -> Vec<Pubkey> {
+            vec![self.treasury]
+        }
+    }
+
+    // transfers lamports from the payer, the treasury is not passed to the instruction
+    struct Transfer {
+        receiver: Pubkey,
+    }
+
+    impl Transfer {
+        fn instruction(&self, client: &impl FuzzClient) -> Instruction {
+            solana_sdk::system_instruction::transfer(
+                &client.payer().pubkey(),
+                &self.receiver,
+                solana_sdk::native_token::LAMPORTS_PER_SOL,
+            )
+        }
+    }
+
+    impl IxOps for Transfer {
+        type IxAccounts = TestAccounts;
+
+        fn get_discriminator(&self) -> Vec<u8> {
+            vec![2, 0, 0, 0]
+        }
+
+        fn get_program_id(&self) -> Pubkey {
+            solana_sdk::system_program::ID
+        }
+
+        fn get_data(
+            &self,
+            client: &mut impl FuzzClient,
+            _fuzz_accounts: &mut TestAccounts,
+        ) -> Result<Vec<u8>, FuzzingError> {
+            Ok(self.instruction(client).data)
+        }
+
+        fn get_accounts(
+            &self,
+            client: &mut impl FuzzClient,
+            _fuzz_accounts: &mut TestAccounts,
+        ) -> Result<(Vec<Keypair>, Vec<AccountMeta>), FuzzingError> {
+            Ok((vec![client.payer()], self.instruction(client).accounts))
+        }
+
+        fn account_mutations_handler(
+            &self,
+            mutations: &[AccountMutation],
+        ) -> Result<(), FuzzingError> {
+            ACCEPTED_MUTATIONS.with(|accepted| accepted.borrow_mut().extend_from_slice(mutations));
+            Ok(())
+        }
+
+        fn data_mutations_handler(
+            &self,
+            mutations: &[DataMutation],
+            outcome: DataMutationOutcome,
+        ) -> Result<(), FuzzingError> {
+            DATA_MUTATIONS.with(|all| all.borrow_mut().push((mutations.to_vec(), outcome)));
+            Ok(())
+        }
+
+        fn check(
+            &self,
+            pre_ix: &[SnapshotAccount],
+            _post_ix: &[SnapshotAccount],
+            _ix_data: Vec<u8>,
+        ) -> Result<(), FuzzingError> {
+            CAPTURED.with(|captured| {
+                *captured.borrow_mut() = pre_ix.iter().map(|account| account.pubkey()).collect()
+            });
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_globally_watched_accounts_are_captured() {
+        let config: TridentConfig = toml::from_str("[fuzz]").unwrap();
+        let mut client = TridentClient::new_client(&[], &config);
+        let accounts = RefCell::new(TestAccounts::new(&config));
+        let treasury = accounts.borrow().treasury;
+        let ix = Transfer {
+            receiver: Pubkey::new_unique(),
+        };
+
+        TransactionExecutor::process_transaction("Transfer", &mut client, &ix, &config, &accounts)
+            .unwrap();
+
+        let captured = CAPTURED.with(|captured| captured.take());
+        assert_eq!(
+            captured,
+            vec![client.payer().pubkey(), ix.receiver, treasury]
+        );
+    }
+
+    #[test]
+    fn test_account_mutations_are_drawn_from_the_mutations_input() {
+        let config: TridentConfig = toml::from_str("[fuzz]\naccount_mutations = true").unwrap();
+        let mut client = TridentClient::new_client(&[], &config);
+        let accounts = RefCell::new(TestAccounts::new(&config));
+        let ix = Transfer {
+            receiver: Pubkey::new_unique(),
+        };
+
+        // one `LookAlike { index: 1 }` mutation, the look-alike receiver accepts the transfer
+        set_mutations_input(&[1, 0, 0, 0, 0x40, 1, 0]);
+        TransactionExecutor::process_transaction("Transfer", &mut client, &ix, &config, &accounts)
+            .unwrap();
+        assert_eq!(
+            ACCEPTED_MUTATIONS.with(|accepted| accepted.take()),
+            [AccountMutation::LookAlike { index: 1 }]
+        );
+        // the state of the mutated instruction was restored
+        assert_eq!(
+            client.get_account(&ix.receiver).lamports(),
+            solana_sdk::native_token::LAMPORTS_PER_SOL
+        );
+
+        // the input is consumed, the following instructions are not mutated
+        let ix = Transfer {
+            receiver: Pubkey::new_unique(),
+        };
+        TransactionExecutor::process_transaction("Transfer", &mut client, &ix, &config, &accounts)
+            .unwrap();
+        assert!(ACCEPTED_MUTATIONS
+            .with(|accepted| accepted.take())
+            .is_empty());
+    }
+
+    #[test]
+    fn test_data_mutations_are_drawn_from_the_mutations_input() {
+        let config: TridentConfig = toml::from_str("[fuzz]\ndata_mutations = true").unwrap();
+        let mut client = TridentClient::new_client(&[], &config);
+        let accounts = RefCell::new(TestAccounts::new(&config));
+        let ix = Transfer {
+            receiver: Pubkey::new_unique(),
+        };
+
+        // one `Truncate { len: 0 }` mutation, the system program rejects the empty data
+        set_mutations_input(&[1, 0, 0, 0, 0, 0, 0, 0]);
+        TransactionExecutor::process_transaction("Transfer", &mut client, &ix, &config, &accounts)
+            .unwrap();
+        assert_eq!(
+            DATA_MUTATIONS.with(|all| all.take()),
+            [(
+                vec![DataMutation::Truncate { len: 0 }],
+                DataMutationOutcome::DeserializationError
+            )]
+        );
+        assert_eq!(
+            client.get_account(&ix.receiver).lamports(),
+            solana_sdk::native_token::LAMPORTS_PER_SOL
+        );
+    }
 }
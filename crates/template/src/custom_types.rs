@@ -3,10 +3,11 @@ use std::collections::HashMap;
 use quote::format_ident;
 use syn::{parse_quote, Variant};
 use trident_idl_spec::{
-    idl_type_to_syn_type, Idl, IdlDefinedFields, IdlEnumVariant, IdlField, IdlType, IdlTypeDef,
-    IdlTypeDefTy,
+    Idl, IdlDefinedFields, IdlEnumVariant, IdlField, IdlType, IdlTypeDef, IdlTypeDefTy,
 };
 
+use crate::syn_types::{idl_generics_to_syn_generics, idl_type_to_syn_type};
+
 // New function to generate custom types
 pub(crate) fn get_types(idl: &Idl, program_accounts: HashMap<String, Vec<u8>>) -> Vec<syn::Item> {
     idl.types.iter().fold(Vec::new(), |mut types, type_def| {
@@ -21,7 +22,7 @@ pub(crate) fn get_types(idl: &Idl, program_accounts: HashMap<String, Vec<u8>>) -
             } => {
                 process_enum(type_def, enum_variants, &mut types, &program_accounts);
             }
-            IdlTypeDefTy::Type { alias: _ } => process_type(),
+            IdlTypeDefTy::Type { alias } => process_type(type_def, alias, &mut types),
         }
         types
     })
@@ -68,6 +69,18 @@ fn process_struct(
             types.push(type_item);
         }
     }
+
+    // program accounts can be stored with their discriminator
+    if let Some(discriminator) = is_program_account {
+        let generics = idl_generics_to_syn_generics(&type_def.generics);
+        let (impl_generics, type_generics, _) = generics.split_for_impl();
+        let discriminator_impl: syn::Item = parse_quote! {
+            impl #impl_generics Discriminator for #type_ident #type_generics {
+                const DISCRIMINATOR: &'static [u8] = &[#(#discriminator),*];
+            }
+        };
+        types.push(discriminator_impl);
+    }
 }
 
 fn process_enum(
@@ -76,6 +89,8 @@ fn process_enum(
     types: &mut Vec<syn::Item>,
     program_accounts: &HashMap<String, Vec<u8>>,
 ) {
+    let generics = idl_generics_to_syn_generics(&type_def.generics);
+
     let enum_variants = enum_variants
         .iter()
         .fold(Vec::new(), |mut variants, variant| {
@@ -112,20 +127,52 @@ fn process_enum(
             variants
         });
 
+    let is_program_account = program_accounts.get(&type_def.name);
+
     let type_name = &type_def.name;
     let type_ident = format_ident!("{}", type_name);
 
-    let type_item: syn::Item = parse_quote! {
-        #[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize, Clone)]
-        pub enum #type_ident {
-            #(#enum_variants),*
-        }
+    let type_item: syn::Item = match is_program_account {
+        Some(_) => parse_quote! {
+            #[derive(Debug, BorshDeserialize, BorshSerialize, Clone)]
+            pub enum #type_ident #generics {
+                #(#enum_variants),*
+            }
+        },
+        None => parse_quote! {
+            #[fuzz_constraints]
+            #[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize, Clone)]
+            pub enum #type_ident #generics {
+                #(#enum_variants),*
+            }
+        },
     };
     types.push(type_item);
+
+    // program accounts can be stored with their discriminator
+    if let Some(discriminator) = is_program_account {
+        let (impl_generics, type_generics, _) = generics.split_for_impl();
+        let discriminator_impl: syn::Item = parse_quote! {
+            impl #impl_generics Discriminator for #type_ident #type_generics {
+                const DISCRIMINATOR: &'static [u8] = &[#(#discriminator),*];
+            }
+        };
+        types.push(discriminator_impl);
+    }
 }
 
-fn process_type() {
-    panic!("Parsing Type is not implemented yet")
+fn process_type(type_def: &IdlTypeDef, alias: &IdlType, types: &mut Vec<syn::Item>) {
+    let type_name = &type_def.name;
+    let type_ident = format_ident!("{}", type_name);
+    let generics = idl_generics_to_syn_generics(&type_def.generics);
+
+    // aliases are not program accounts so Public Keys are turned into AccountIds
+    let alias_type = idl_type_to_syn_type(alias, true);
+
+    let type_item: syn::Item = parse_quote! {
+        pub type #type_ident #generics = #alias_type;
+    };
+    types.push(type_item);
 }
 
 fn process_struct_named(
@@ -139,6 +186,8 @@ fn process_struct_named(
     let type_name = &type_def.name;
     let type_ident = format_ident!("{}", type_name);
 
+    let generics = idl_generics_to_syn_generics(&type_def.generics);
+
     let fields = idl_fields
         .iter()
         .fold(Vec::new(), |mut named_fields, field| {
@@ -151,13 +200,14 @@ fn process_struct_named(
     let struct_definition: syn::Item = match is_program_account {
         Some(_) => parse_quote! {
             #[derive(Debug, BorshDeserialize, BorshSerialize, Clone)]
-            pub struct #type_ident {
+            pub struct #type_ident #generics {
                 #(#fields),*
             }
         },
         None => parse_quote! {
+            #[fuzz_constraints]
             #[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize, Clone)]
-            pub struct #type_ident {
+            pub struct #type_ident #generics {
                 #(#fields),*
             }
         },
@@ -173,11 +223,11 @@ fn process_struct_field(
     let field_name = &field.name;
     let field_ident = format_ident!("{}", field_name);
 
-    let (field_type, _is_custom) = match is_program_account {
+    let field_type = match is_program_account {
         // if it is program account Public Keys are not turned into AccountIds
-        Some(_) => idl_type_to_syn_type(&field.ty, 0, false),
+        Some(_) => idl_type_to_syn_type(&field.ty, false),
         // if it is not program account Public Keys are turned into AccountIds
-        None => idl_type_to_syn_type(&field.ty, 0, true),
+        None => idl_type_to_syn_type(&field.ty, true),
     };
 
     // we create the field
@@ -198,15 +248,16 @@ fn process_struct_tuple(
     let type_name = &type_def.name;
     let type_ident = format_ident!("{}", type_name);
 
+    let generics = idl_generics_to_syn_generics(&type_def.generics);
+
     let tuple_fields: Vec<syn::Type> = idl_types
         .iter()
         .map(|idl_type| {
             // processing each IDL Type in the tuple
-            let (field_type, _is_custom) = match is_program_account {
-                Some(_) => idl_type_to_syn_type(idl_type, 0, false),
-                None => idl_type_to_syn_type(idl_type, 0, true),
-            };
-            field_type
+            match is_program_account {
+                Some(_) => idl_type_to_syn_type(idl_type, false),
+                None => idl_type_to_syn_type(idl_type, true),
+            }
         })
         .collect();
 
@@ -214,11 +265,12 @@ fn process_struct_tuple(
     let struct_definition: syn::Item = match is_program_account {
         Some(_) => parse_quote! {
             #[derive(Debug, BorshDeserialize, BorshSerialize)]
-            struct #type_ident(#(pub #tuple_fields),*);
+            struct #type_ident #generics (#(pub #tuple_fields),*);
         },
         None => parse_quote! {
+            #[fuzz_constraints]
             #[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize, Clone)]
-            struct #type_ident(#(pub #tuple_fields),*);
+            struct #type_ident #generics (#(pub #tuple_fields),*);
         },
     };
 
@@ -267,11 +319,10 @@ fn process_enum_variant_fields_tuple(
         .iter()
         .map(|idl_type| {
             // processing each IDL Type in the tuple
-            let (syn_type, _is_custom) = match is_program_account {
-                Some(_) => idl_type_to_syn_type(idl_type, 0, false),
-                None => idl_type_to_syn_type(idl_type, 0, true),
-            };
-            syn_type
+            match is_program_account {
+                Some(_) => idl_type_to_syn_type(idl_type, false),
+                None => idl_type_to_syn_type(idl_type, true),
+            }
         })
         .collect();
     let variant = parse_quote!(#variant_ident(#(#tuple_fields),*));
@@ -0,0 +1,187 @@
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use trident_idl_spec::{
+    Idl, IdlConst, IdlInstructionAccount, IdlInstructionAccountItem, IdlSeed, IdlType,
+};
+
+use crate::get_ix_discriminator::{gen_discriminator, process_discriminator};
+use crate::idl_origin::is_anchor_idl;
+
+const ACCOUNT_NAMESPACE: &str = "account";
+const EVENT_NAMESPACE: &str = "event";
+
+/// Generates the fuzzing dictionary in the AFL format, which is also accepted by Honggfuzz.
+/// The dictionary contains discriminators, constants, error codes, constant seeds and
+/// boundary values of the integer arguments of each program.
+pub fn generate_dictionary(idls: &[Idl]) -> String {
+    let mut dictionary = Dictionary::default();
+
+    for idl in idls {
+        let program_name = &idl.metadata.name;
+        // only Anchor derives missing discriminators from the names
+        let is_anchor = is_anchor_idl(idl);
+
+        for instruction in &idl.instructions {
+            dictionary.add(
+                &format!("{program_name}_ix_{}", instruction.name),
+                &process_discriminator(idl, instruction),
+            );
+        }
+        for account in &idl.accounts {
+            let discriminator = match account.discriminator.is_empty() && is_anchor {
+                true => gen_discriminator(ACCOUNT_NAMESPACE, &account.name).to_vec(),
+                false => account.discriminator.clone(),
+            };
+            dictionary.add(
+                &format!("{program_name}_account_{}", account.name),
+                &discriminator,
+            );
+        }
+        for event in &idl.events {
+            let discriminator = match event.discriminator.is_empty() && is_anchor {
+                true => gen_discriminator(EVENT_NAMESPACE, &event.name).to_vec(),
+                false => event.discriminator.clone(),
+            };
+            dictionary.add(
+                &format!("{program_name}_event_{}", event.name),
+                &discriminator,
+            );
+        }
+        for constant in &idl.constants {
+            if let Some(value) = constant_bytes(constant) {
+                dictionary.add(&format!("{program_name}_const_{}", constant.name), &value);
+            }
+        }
+        for error in &idl.errors {
+            dictionary.add(
+                &format!("{program_name}_error_{}", error.name),
+                &error.code.to_le_bytes(),
+            );
+        }
+        for instruction in &idl.instructions {
+            let mut seeds = vec![];
+            collect_seeds(&instruction.accounts, &mut seeds);
+            for (account_name, seed) in seeds {
+                dictionary.add(
+                    &format!("{program_name}_seed_{}_{account_name}", instruction.name),
+                    &seed,
+                );
+            }
+        }
+        for instruction in &idl.instructions {
+            for argument in &instruction.args {
+                add_boundaries(&argument.ty, &mut dictionary);
+            }
+        }
+    }
+
+    dictionary.entries.join("\n") + "\n"
+}
+
+#[derive(Default)]
+struct Dictionary {
+    entries: Vec<String>,
+    values: HashSet<(String, Vec<u8>)>,
+}
+
+impl Dictionary {
+    // Every byte is escaped so the values are not mangled by quotes or non-printable characters
+    fn add(&mut self, name: &str, value: &[u8]) {
+        if value.is_empty() || !self.values.insert((name.to_string(), value.to_vec())) {
+            return;
+        }
+        let escaped = value.iter().fold(String::new(), |mut escaped, byte| {
+            let _ = write!(escaped, "\\x{byte:02x}");
+            escaped
+        });
+        self.entries.push(format!("{name}=\"{escaped}\""));
+    }
+}
+
+fn collect_seeds(accounts: &[IdlInstructionAccountItem], seeds: &mut Vec<(String, Vec<u8>)>) {
+    for account in accounts {
+        match account {
+            IdlInstructionAccountItem::Single(account) => collect_account_seeds(account, seeds),
+            IdlInstructionAccountItem::Composite(accounts) => {
+                collect_seeds(&accounts.accounts, seeds)
+            }
+        }
+    }
+}
+
+fn collect_account_seeds(account: &IdlInstructionAccount, seeds: &mut Vec<(String, Vec<u8>)>) {
+    if let Some(pda) = &account.pda {
+        for seed in &pda.seeds {
+            if let IdlSeed::Const(seed) = seed {
+                seeds.push((account.name.clone(), seed.value.clone()));
+            }
+        }
+    }
+}
+
+// Constant values are stored as strings in the IDL, e.g. `100`, `"\"seed\""` or `[1, 2, 3]`
+fn constant_bytes(constant: &IdlConst) -> Option<Vec<u8>> {
+    let value = constant.value.replace('_', "");
+    match &constant.ty {
+        IdlType::U8 => value.parse::<u8>().ok().map(|v| v.to_le_bytes().to_vec()),
+        IdlType::I8 => value.parse::<i8>().ok().map(|v| v.to_le_bytes().to_vec()),
+        IdlType::U16 => value.parse::<u16>().ok().map(|v| v.to_le_bytes().to_vec()),
+        IdlType::I16 => value.parse::<i16>().ok().map(|v| v.to_le_bytes().to_vec()),
+        IdlType::U32 => value.parse::<u32>().ok().map(|v| v.to_le_bytes().to_vec()),
+        IdlType::I32 => value.parse::<i32>().ok().map(|v| v.to_le_bytes().to_vec()),
+        IdlType::U64 => value.parse::<u64>().ok().map(|v| v.to_le_bytes().to_vec()),
+        IdlType::I64 => value.parse::<i64>().ok().map(|v| v.to_le_bytes().to_vec()),
+        IdlType::U128 => value.parse::<u128>().ok().map(|v| v.to_le_bytes().to_vec()),
+        IdlType::I128 => value.parse::<i128>().ok().map(|v| v.to_le_bytes().to_vec()),
+        IdlType::String => Some(constant.value.trim_matches('"').as_bytes().to_vec()),
+        _ => match constant.value.trim().strip_prefix('[') {
+            // byte arrays and byte strings
+            Some(bytes) => bytes
+                .trim_end_matches(']')
+                .split(',')
+                .map(|byte| byte.trim().parse::<u8>().ok())
+                .collect(),
+            None => Some(constant.value.as_bytes().to_vec()),
+        },
+    }
+}
+
+macro_rules! boundaries {
+    ($dictionary:expr, $name:literal, $ty:ty, unsigned) => {{
+        let (zero, one): ($ty, $ty) = (0, 1);
+        $dictionary.add(concat!($name, "_zero"), &zero.to_le_bytes());
+        $dictionary.add(concat!($name, "_one"), &one.to_le_bytes());
+        $dictionary.add(concat!($name, "_max"), &<$ty>::MAX.to_le_bytes());
+        $dictionary.add(
+            concat!($name, "_max_minus_one"),
+            &(<$ty>::MAX - one).to_le_bytes(),
+        );
+    }};
+    ($dictionary:expr, $name:literal, $ty:ty, signed) => {{
+        let (zero, minus_one): ($ty, $ty) = (0, -1);
+        $dictionary.add(concat!($name, "_zero"), &zero.to_le_bytes());
+        $dictionary.add(concat!($name, "_minus_one"), &minus_one.to_le_bytes());
+        $dictionary.add(concat!($name, "_min"), &<$ty>::MIN.to_le_bytes());
+        $dictionary.add(concat!($name, "_max"), &<$ty>::MAX.to_le_bytes());
+    }};
+}
+
+fn add_boundaries(idl_type: &IdlType, dictionary: &mut Dictionary) {
+    match idl_type {
+        IdlType::U8 => boundaries!(dictionary, "u8", u8, unsigned),
+        IdlType::I8 => boundaries!(dictionary, "i8", i8, signed),
+        IdlType::U16 => boundaries!(dictionary, "u16", u16, unsigned),
+        IdlType::I16 => boundaries!(dictionary, "i16", i16, signed),
+        IdlType::U32 => boundaries!(dictionary, "u32", u32, unsigned),
+        IdlType::I32 => boundaries!(dictionary, "i32", i32, signed),
+        IdlType::U64 => boundaries!(dictionary, "u64", u64, unsigned),
+        IdlType::I64 => boundaries!(dictionary, "i64", i64, signed),
+        IdlType::U128 => boundaries!(dictionary, "u128", u128, unsigned),
+        IdlType::I128 => boundaries!(dictionary, "i128", i128, signed),
+        IdlType::Option(inner) | IdlType::Vec(inner) | IdlType::Array(inner, _) => {
+            add_boundaries(inner, dictionary);
+        }
+        _ => {}
+    }
+}
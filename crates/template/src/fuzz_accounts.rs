@@ -2,7 +2,7 @@ use trident_idl_spec::{
     Idl, IdlInstructionAccount, IdlInstructionAccountItem, IdlInstructionAccounts,
 };
 
-use quote::format_ident;
+use quote::{format_ident, ToTokens};
 use std::collections::HashMap;
 use syn::parse_quote;
 
@@ -52,6 +52,41 @@ pub(crate) fn get_fuzz_accounts(
     sorted_accounts.into_iter().map(|(_, v)| v).collect()
 }
 
+// Generate default Account Storages, the limits are taken from the Trident Manifest
+pub(crate) fn get_fuzz_accounts_defaults(fuzz_accounts: &[syn::FnArg]) -> Vec<syn::FieldValue> {
+    fuzz_accounts
+        .iter()
+        .filter_map(|account| match account {
+            syn::FnArg::Typed(pat_type) => {
+                let name = &pat_type.pat;
+                let name_str = name.to_token_stream().to_string();
+                Some(parse_quote!(#name: AccountsStorage::from_manifest(#name_str, config)))
+            }
+            syn::FnArg::Receiver(_) => None,
+        })
+        .collect()
+}
+
+// Generate the pool of known addresses from all Account Storages
+pub(crate) fn get_fuzz_accounts_address_pool(fuzz_accounts: &[syn::FnArg]) -> syn::Expr {
+    let storages: Vec<syn::Expr> = fuzz_accounts
+        .iter()
+        .filter_map(|account| match account {
+            syn::FnArg::Typed(pat_type) => {
+                let name = &pat_type.pat;
+                Some(parse_quote!(self.#name.pubkeys()))
+            }
+            syn::FnArg::Receiver(_) => None,
+        })
+        .collect();
+
+    if storages.is_empty() {
+        parse_quote!(vec![])
+    } else {
+        parse_quote!([#(#storages),*].concat())
+    }
+}
+
 fn process_composite_account(
     idl_instruction_accounts: &IdlInstructionAccounts,
     fuzz_accounts: &mut HashMap<syn::Ident, syn::FnArg>,
@@ -96,6 +131,9 @@ fn process_single_account(
         FuzzAccountsType::Pda => {
             process_pda_account(idl_instruction_account, fuzz_accounts);
         }
+        FuzzAccountsType::Ata => {
+            process_ata_account(idl_instruction_account, fuzz_accounts);
+        }
         FuzzAccountsType::Constant => {
             // No need to store and fuzz constant addresses
         }
@@ -125,6 +163,16 @@ fn process_pda_account(
     fuzz_accounts.entry(name).or_insert(account);
 }
 
+fn process_ata_account(
+    idl_instruction_account: &IdlInstructionAccount,
+    fuzz_accounts: &mut HashMap<syn::Ident, syn::FnArg>,
+) {
+    let name: syn::Ident = format_ident!("{}", &idl_instruction_account.name);
+
+    let account = parse_quote! { #name: AccountsStorage<AtaStore> };
+    fuzz_accounts.entry(name).or_insert(account);
+}
+
 fn process_unknown_account(
     idl_instruction_account: &IdlInstructionAccount,
     fuzz_accounts: &mut HashMap<syn::Ident, syn::FnArg>,
@@ -7,10 +7,12 @@ use trident_idl_spec::{
 };
 
 use super::{
-    get_fuzz_accounts, get_instruction_inputs, get_instruction_ixops, get_instruction_variants,
-    get_types,
+    get_fuzz_accounts, get_fuzz_accounts_address_pool, get_fuzz_accounts_defaults,
+    get_instruction_inputs, get_instruction_ixops, get_instruction_variants, get_types,
+};
+use crate::instruction_account::{
+    is_associated_token_account, InstructionAccount, InstructionAccountType,
 };
-use crate::instruction_account::{InstructionAccount, InstructionAccountType};
 
 // Main function to generate source code from IDLs
 pub fn generate_source_code(idls: &[Idl]) -> String {
@@ -33,6 +35,9 @@ pub fn generate_source_code(idls: &[Idl]) -> String {
         all_types.extend(get_types(idl, program_accounts));
     }
 
+    let all_fuzz_accounts_defaults = get_fuzz_accounts_defaults(&all_fuzz_accounts);
+    let address_pool = get_fuzz_accounts_address_pool(&all_fuzz_accounts);
+
     // Define the Rust module with all generated code
     let module_definition = quote! {
         use trident_fuzz::fuzzing::*;
@@ -51,11 +56,31 @@ pub fn generate_source_code(idls: &[Idl]) -> String {
 
         /// Check supported AccountsStorages at
         /// https://ackee.xyz/trident/docs/latest/features/account-storages/
-        #[derive(Default)]
+        #[derive(Clone)]
         pub struct FuzzAccounts {
             #(#all_fuzz_accounts),*
         }
 
+        /// Limit the number of accounts per Account Storage with
+        /// `AccountsStorage::new(max_accounts)`, or set the limits in
+        /// `[fuzz.account_storages.<name>]` of Trident.toml
+        impl AccountStorages for FuzzAccounts {
+            fn new(config: &TridentConfig) -> Self {
+                Self {
+                    #(#all_fuzz_accounts_defaults),*
+                }
+            }
+        }
+
+        /// Addresses Public Key arguments without a matching Account Storage
+        /// are picked from, extend the pool with other known addresses.
+        /// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#public-key-arguments
+        impl AddressPool for FuzzAccounts {
+            fn address_pool(&self) -> Vec<Pubkey> {
+                #address_pool
+            }
+        }
+
         #(#all_types)*
     };
 
@@ -158,6 +183,17 @@ fn evaluate_account_type(
             idl_instruction_account.writable,
             idl_instruction_account.signer,
         )
+    // If the account is derived by the associated token program, it is an ATA account
+    } else if let Some(idl_pda) = idl_instruction_account
+        .pda
+        .as_ref()
+        .filter(|idl_pda| is_associated_token_account(idl_pda))
+    {
+        InstructionAccountType::Ata(
+            idl_pda.clone(),
+            idl_instruction_account.writable,
+            idl_instruction_account.signer,
+        )
     // If the account is a PDA, it is a PDA account
     } else if let Some(idl_pda) = &idl_instruction_account.pda {
         InstructionAccountType::Pda(
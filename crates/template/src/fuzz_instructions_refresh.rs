@@ -0,0 +1,691 @@
+use std::collections::HashSet;
+
+use proc_macro2::{extra::DelimSpan, LineColumn};
+use quote::ToTokens;
+use syn::{Expr, Fields, ImplItem, Item, ItemImpl, ItemStruct, Stmt};
+
+// Methods of IxOps derived purely from the IDL, these are always regenerated
+const DERIVED_IXOPS_METHODS: [&str; 2] = ["get_discriminator", "get_program_id"];
+
+/// Result of merging the regenerated fuzz instructions into the existing ones.
+pub struct RefreshedSourceCode {
+    /// The refreshed source code, not formatted.
+    pub source_code: String,
+    /// User code which was kept, but the regenerated code differs from it.
+    pub conflicts: Vec<RefreshConflict>,
+    /// Instructions which are no longer part of the IDL and were removed.
+    pub removed_instructions: Vec<RemovedInstruction>,
+}
+
+/// Current and regenerated code which differ, one of them was kept.
+pub struct RefreshConflict {
+    /// Location of the conflict, e.g. `Initialize::get_accounts`.
+    pub item: String,
+    pub current: String,
+    pub generated: String,
+    /// The current code still contained the generated `todo!()`s and was replaced,
+    /// otherwise the current code was kept.
+    pub regenerated: bool,
+}
+
+impl RefreshConflict {
+    /// Line diff from the current to the generated code.
+    pub fn diff(&self) -> String {
+        line_diff(&self.item, &self.current, &self.generated)
+    }
+}
+
+/// Instruction removed together with the user code of its items.
+pub struct RemovedInstruction {
+    pub name: String,
+    pub code: String,
+}
+
+impl RemovedInstruction {
+    /// Line diff removing the code of the instruction.
+    pub fn diff(&self) -> String {
+        line_diff(&self.name, &self.code, "")
+    }
+}
+
+fn line_diff(item: &str, current: &str, generated: &str) -> String {
+    let mut diff = format!("--- current {item}\n+++ generated {item}\n");
+    for line in diff::lines(current, generated) {
+        match line {
+            diff::Result::Left(line) => diff.push_str(&format!("-{line}\n")),
+            diff::Result::Both(line, _) => diff.push_str(&format!(" {line}\n")),
+            diff::Result::Right(line) => diff.push_str(&format!("+{line}\n")),
+        }
+    }
+    diff
+}
+
+/// Merges the `generated` fuzz instructions into the `current` ones.
+///
+/// Parts derived from the IDL (instruction variants, instruction inputs, discriminators,
+/// program IDs and custom types) are regenerated, while the user code in `IxOps`
+/// implementations, Account Storages and other items is preserved.
+pub fn refresh_source_code(current: &str, generated: &str) -> syn::Result<RefreshedSourceCode> {
+    let current_file: syn::File = syn::parse_str(current)?;
+    let generated_file: syn::File = syn::parse_str(generated)?;
+
+    let refresh = Refresh {
+        current: Source::new(current, &current_file),
+        generated: Source::new(generated, &generated_file),
+    };
+    let mut conflicts = vec![];
+
+    let generated_instructions = instruction_names(&generated_file);
+    let removed_names: Vec<String> = instruction_names(&current_file)
+        .into_iter()
+        .filter(|name| !generated_instructions.contains(name))
+        .collect();
+    let removed_items: HashSet<String> = removed_names
+        .iter()
+        .flat_map(|name| instruction_items(name))
+        .collect();
+
+    // the code of removed instructions is kept for review
+    let removed_instructions = removed_names
+        .into_iter()
+        .map(|name| {
+            let code: Vec<String> = instruction_items(&name)
+                .iter()
+                .filter_map(|key| refresh.current.get(key))
+                .map(|item| refresh.current.text(item.range.clone()))
+                .collect();
+            RemovedInstruction {
+                name,
+                code: code.join("\n"),
+            }
+        })
+        .collect();
+
+    let mut items = vec![];
+    let last_use = refresh
+        .generated
+        .items
+        .iter()
+        .rposition(|item| matches!(item.item, Item::Use(_)));
+
+    for (index, generated_item) in refresh.generated.items.iter().enumerate() {
+        let item = match refresh.current.get(&generated_item.key) {
+            Some(current_item) => refresh.merge_item(current_item, generated_item, &mut conflicts),
+            None => refresh.generated.text(generated_item.range.clone()),
+        };
+        items.push(item);
+
+        // imports added by the user are kept next to the generated ones
+        if Some(index) == last_use {
+            items.extend(
+                refresh
+                    .current_only()
+                    .filter(|item| matches!(item.item, Item::Use(_)))
+                    .map(|item| refresh.current.text(item.range.clone())),
+            );
+        }
+    }
+
+    // helper functions, types and implementations written by the user
+    items.extend(
+        refresh
+            .current_only()
+            .filter(|item| !matches!(item.item, Item::Use(_)) || last_use.is_none())
+            .filter(|item| !removed_items.contains(&item.key))
+            .map(|item| refresh.current.text(item.range.clone())),
+    );
+
+    Ok(RefreshedSourceCode {
+        source_code: items.join("\n"),
+        conflicts,
+        removed_instructions,
+    })
+}
+
+struct Refresh<'a> {
+    current: Source<'a>,
+    generated: Source<'a>,
+}
+
+impl Refresh<'_> {
+    // Items of the current source code without a generated counterpart
+    fn current_only(&self) -> impl Iterator<Item = &SourceItem<'_>> {
+        self.current
+            .items
+            .iter()
+            .filter(|item| self.generated.get(&item.key).is_none())
+    }
+
+    fn merge_item(
+        &self,
+        current: &SourceItem,
+        generated: &SourceItem,
+        conflicts: &mut Vec<RefreshConflict>,
+    ) -> String {
+        match (current.item, generated.item) {
+            (Item::Struct(current_struct), Item::Struct(generated_struct)) => {
+                // user defined Account Storages are kept
+                let keep_current_fields = generated_struct.ident == "FuzzAccounts";
+                self.merge_struct(
+                    current_struct,
+                    generated_struct,
+                    generated,
+                    keep_current_fields,
+                )
+            }
+            (Item::Impl(current_impl), Item::Impl(generated_impl)) => {
+                match trait_name(generated_impl).as_deref() {
+                    Some("IxOps") => {
+                        self.merge_ixops(current_impl, generated_impl, generated, conflicts)
+                    }
+                    Some("AccountStorages") => {
+                        self.merge_account_storages(current_impl, current, generated_impl)
+                    }
+                    Some("AddressPool") => self.merge_address_pool(current_impl, current),
+                    Some("Discriminator") => self.generated.text(generated.range.clone()),
+                    _ => self.current.text(current.range.clone()),
+                }
+            }
+            (Item::Use(_), _) | (Item::Fn(_), _) | (Item::Mod(_), _) | (Item::Const(_), _) => {
+                self.current.text(current.range.clone())
+            }
+            // enums, type aliases and other derived items are regenerated
+            _ => self.generated.text(generated.range.clone()),
+        }
+    }
+
+    // Fields are taken from the generated struct, unless the current field has the same
+    // type, or the generated type is yet to be specified by the user
+    fn merge_struct(
+        &self,
+        current_struct: &ItemStruct,
+        generated_struct: &ItemStruct,
+        generated: &SourceItem,
+        keep_current_fields: bool,
+    ) -> String {
+        let (Fields::Named(current_fields), Fields::Named(generated_fields)) =
+            (&current_struct.fields, &generated_struct.fields)
+        else {
+            return self.generated.text(generated.range.clone());
+        };
+
+        let current_texts = self.current.children(
+            &current_fields.brace_token.span,
+            current_fields.named.iter(),
+        );
+        let generated_texts = self.generated.children(
+            &generated_fields.brace_token.span,
+            generated_fields.named.iter(),
+        );
+
+        let mut fields = vec![];
+        for (generated_field, generated_text) in generated_fields.named.iter().zip(generated_texts)
+        {
+            let current_field = current_fields
+                .named
+                .iter()
+                .zip(&current_texts)
+                .find(|(current_field, _)| current_field.ident == generated_field.ident);
+
+            match current_field {
+                Some((current_field, current_text))
+                    if tokens(&current_field.ty) == tokens(&generated_field.ty)
+                        || tokens(&generated_field.ty).contains("todo") =>
+                {
+                    fields.push(current_text.clone())
+                }
+                _ => fields.push(generated_text),
+            }
+        }
+
+        if keep_current_fields {
+            fields.extend(
+                current_fields
+                    .named
+                    .iter()
+                    .zip(current_texts)
+                    .filter(|(current_field, _)| {
+                        !generated_fields
+                            .named
+                            .iter()
+                            .any(|generated_field| generated_field.ident == current_field.ident)
+                    })
+                    .map(|(_, current_text)| current_text),
+            );
+        }
+
+        self.generated.splice(
+            generated.range.clone(),
+            &generated_fields.brace_token.span,
+            &comma_separated(&fields),
+        )
+    }
+
+    // Derived methods are regenerated, the other methods written by the user are kept
+    fn merge_ixops(
+        &self,
+        current_impl: &ItemImpl,
+        generated_impl: &ItemImpl,
+        generated: &SourceItem,
+        conflicts: &mut Vec<RefreshConflict>,
+    ) -> String {
+        let instruction = tokens(&generated_impl.self_ty);
+        let accounts_changed = self.struct_changed(&format!("struct {instruction}Accounts"));
+        let data_changed = self.struct_changed(&format!("struct {instruction}Data"));
+
+        let current_texts = self
+            .current
+            .children(&current_impl.brace_token.span, current_impl.items.iter());
+        let generated_texts = self.generated.children(
+            &generated_impl.brace_token.span,
+            generated_impl.items.iter(),
+        );
+
+        let mut methods = vec![];
+        for (generated_item, generated_text) in generated_impl.items.iter().zip(generated_texts) {
+            let ImplItem::Fn(generated_method) = generated_item else {
+                methods.push(generated_text);
+                continue;
+            };
+            let method_name = generated_method.sig.ident.to_string();
+            let current_method =
+                current_impl
+                    .items
+                    .iter()
+                    .zip(&current_texts)
+                    .find(|(current_item, _)| {
+                        method_ident(current_item) == Some(&generated_method.sig.ident)
+                    });
+
+            match current_method {
+                // methods still containing the generated `todo!()`s were not completed by the user
+                Some((current_item, current_text))
+                    if !DERIVED_IXOPS_METHODS.contains(&method_name.as_str())
+                        && tokens(current_item).contains("todo !") =>
+                {
+                    if tokens(current_item) != tokens(generated_item) {
+                        conflicts.push(RefreshConflict {
+                            item: format!("{instruction}::{method_name}"),
+                            current: current_text.clone(),
+                            generated: generated_text.clone(),
+                            regenerated: true,
+                        });
+                    }
+                    methods.push(generated_text);
+                }
+                Some((current_item, current_text))
+                    if !DERIVED_IXOPS_METHODS.contains(&method_name.as_str()) =>
+                {
+                    let changed_inputs = match method_name.as_str() {
+                        "get_accounts" => accounts_changed,
+                        "get_data" => data_changed,
+                        _ => false,
+                    };
+                    if changed_inputs && tokens(current_item) != tokens(generated_item) {
+                        conflicts.push(RefreshConflict {
+                            item: format!("{instruction}::{method_name}"),
+                            current: current_text.clone(),
+                            generated: generated_text,
+                            regenerated: false,
+                        });
+                    }
+                    methods.push(current_text.clone());
+                }
+                _ => methods.push(generated_text),
+            }
+        }
+
+        // methods with default implementations overridden by the user
+        methods.extend(
+            current_impl
+                .items
+                .iter()
+                .zip(current_texts)
+                .filter(|(current_item, _)| {
+                    method_ident(current_item).is_some_and(|current_ident| {
+                        !generated_impl.items.iter().any(|generated_item| {
+                            method_ident(generated_item) == Some(current_ident)
+                        })
+                    })
+                })
+                .map(|(_, current_text)| current_text),
+        );
+
+        self.generated.splice(
+            generated.range.clone(),
+            &generated_impl.brace_token.span,
+            &methods.join("\n"),
+        )
+    }
+
+    // Initializers of the current Account Storages are kept, the new ones are added
+    fn merge_account_storages(
+        &self,
+        current_impl: &ItemImpl,
+        current: &SourceItem,
+        generated_impl: &ItemImpl,
+    ) -> String {
+        let (Some(current_fields), Some(generated_fields)) = (
+            self_initializer(current_impl),
+            self_initializer(generated_impl),
+        ) else {
+            return self.current.text(current.range.clone());
+        };
+
+        let current_texts = self.current.children(
+            &current_fields.brace_token.span,
+            current_fields.fields.iter(),
+        );
+        let generated_texts = self.generated.children(
+            &generated_fields.brace_token.span,
+            generated_fields.fields.iter(),
+        );
+
+        let mut fields = vec![];
+        for (generated_field, generated_text) in generated_fields.fields.iter().zip(generated_texts)
+        {
+            let current_field = current_fields
+                .fields
+                .iter()
+                .zip(&current_texts)
+                .find(|(current_field, _)| current_field.member == generated_field.member);
+            match current_field {
+                Some((_, current_text)) => fields.push(current_text.clone()),
+                None => fields.push(generated_text),
+            }
+        }
+
+        // initializers of the Account Storages defined by the user
+        fields.extend(
+            current_fields
+                .fields
+                .iter()
+                .zip(current_texts)
+                .filter(|(current_field, _)| {
+                    !generated_fields
+                        .fields
+                        .iter()
+                        .any(|generated_field| generated_field.member == current_field.member)
+                })
+                .map(|(_, current_text)| current_text),
+        );
+
+        self.current.splice(
+            current.range.clone(),
+            &current_fields.brace_token.span,
+            &comma_separated(&fields),
+        )
+    }
+
+    // Unless customized, the address pool is extended with the new Account Storages
+    fn merge_address_pool(&self, current_impl: &ItemImpl, current: &SourceItem) -> String {
+        let current_storages = self.current.get("struct FuzzAccounts").map(storage_names);
+        let generated_storages = self.generated.get("struct FuzzAccounts").map(storage_names);
+        let address_pool = current_impl.items.iter().find_map(|item| match item {
+            ImplItem::Fn(method) if method.sig.ident == "address_pool" => Some(method),
+            _ => None,
+        });
+
+        let (Some(current_storages), Some(generated_storages), Some(address_pool)) =
+            (current_storages, generated_storages, address_pool)
+        else {
+            return self.current.text(current.range.clone());
+        };
+
+        // the trailing comma of the array is not relevant
+        let normalize = |tokens: String| tokens.replace(' ', "").replace(",]", "]");
+        let current_body = format!("{{ {} }}", address_pool_body(&current_storages));
+        if normalize(current_body) != normalize(tokens(&address_pool.block)) {
+            return self.current.text(current.range.clone());
+        }
+
+        // same order as the Account Storages in the merged FuzzAccounts
+        let current_only: Vec<String> = current_storages
+            .into_iter()
+            .filter(|storage| !generated_storages.contains(storage))
+            .collect();
+        let storages = [generated_storages, current_only].concat();
+
+        self.current.splice(
+            current.range.clone(),
+            &address_pool.block.brace_token.span,
+            &address_pool_body(&storages),
+        )
+    }
+
+    // The fields of the struct differ between the current and the generated source code
+    fn struct_changed(&self, key: &str) -> bool {
+        let fields = |item: Option<&SourceItem>| match item.map(|item| item.item) {
+            Some(Item::Struct(item_struct)) => Some(
+                item_struct
+                    .fields
+                    .iter()
+                    .map(|field| (tokens(&field.ident), tokens(&field.ty)))
+                    .collect::<Vec<_>>(),
+            ),
+            _ => None,
+        };
+        fields(self.current.get(key)) != fields(self.generated.get(key))
+    }
+}
+
+// Parsed source code with byte ranges of its items, including leading comments
+struct Source<'a> {
+    text: &'a str,
+    line_offsets: Vec<usize>,
+    items: Vec<SourceItem<'a>>,
+}
+
+struct SourceItem<'a> {
+    key: String,
+    item: &'a Item,
+    range: std::ops::Range<usize>,
+}
+
+impl<'a> Source<'a> {
+    fn new(text: &'a str, file: &'a syn::File) -> Self {
+        let line_offsets = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(offset, _)| offset + 1))
+            .collect();
+        let mut source = Self {
+            text,
+            line_offsets,
+            items: vec![],
+        };
+
+        let mut previous_end = 0;
+        for item in &file.items {
+            let (start, end) = source.token_range(item);
+            source.items.push(SourceItem {
+                key: item_key(item),
+                item,
+                range: source.leading_start(previous_end, start)..end,
+            });
+            previous_end = end;
+        }
+        source
+    }
+
+    fn get(&self, key: &str) -> Option<&SourceItem<'a>> {
+        self.items.iter().find(|item| item.key == key)
+    }
+
+    fn text(&self, range: std::ops::Range<usize>) -> String {
+        self.text[range].to_string()
+    }
+
+    // Texts of the items enclosed in the delimiter, e.g. fields of a struct
+    fn children<T: ToTokens>(
+        &self,
+        delimiter: &DelimSpan,
+        children: impl Iterator<Item = T>,
+    ) -> Vec<String> {
+        let mut previous_end = self.offset(delimiter.open().end());
+        children
+            .map(|child| {
+                let (start, end) = self.token_range(&child);
+                let text = self.text(self.leading_start(previous_end, start)..end);
+                previous_end = end;
+                text
+            })
+            .collect()
+    }
+
+    // Replaces the content enclosed in the delimiter within the range
+    fn splice(
+        &self,
+        range: std::ops::Range<usize>,
+        delimiter: &DelimSpan,
+        content: &str,
+    ) -> String {
+        let open = self.offset(delimiter.open().end());
+        let close = self.offset(delimiter.close().start());
+        format!(
+            "{}\n{}\n{}",
+            &self.text[range.start..open],
+            content,
+            &self.text[close..range.end]
+        )
+    }
+
+    fn token_range(&self, tokens: &impl ToTokens) -> (usize, usize) {
+        let tokens: Vec<_> = tokens.to_token_stream().into_iter().collect();
+        match (tokens.first(), tokens.last()) {
+            (Some(first), Some(last)) => (
+                self.offset(first.span().start()),
+                self.offset(last.span().end()),
+            ),
+            _ => (0, 0),
+        }
+    }
+
+    // Start of the comments preceding the item, including the indentation
+    fn leading_start(&self, previous_end: usize, start: usize) -> usize {
+        let gap = &self.text[previous_end..start];
+        let content_start = gap
+            .find(|c: char| !c.is_whitespace() && c != ',')
+            .unwrap_or(gap.len());
+        match gap[..content_start].rfind('\n') {
+            Some(newline) => previous_end + newline + 1,
+            None => previous_end + content_start,
+        }
+    }
+
+    fn offset(&self, position: LineColumn) -> usize {
+        let line_start = self.line_offsets[position.line - 1];
+        self.text[line_start..]
+            .char_indices()
+            .nth(position.column)
+            .map_or(self.text.len(), |(offset, _)| line_start + offset)
+    }
+}
+
+// Items are matched between the current and the generated source code by the key
+fn item_key(item: &Item) -> String {
+    match item {
+        Item::Struct(item_struct) => format!("struct {}", item_struct.ident),
+        Item::Enum(item_enum) => format!("enum {}", item_enum.ident),
+        Item::Type(item_type) => format!("type {}", item_type.ident),
+        Item::Fn(item_fn) => format!("fn {}", item_fn.sig.ident),
+        Item::Impl(item_impl) => match &item_impl.trait_ {
+            Some(_) => format!(
+                "impl {} for {}",
+                trait_name(item_impl).unwrap_or_default(),
+                tokens(&item_impl.self_ty)
+            ),
+            None => format!("impl {}", tokens(&item_impl.self_ty)),
+        },
+        _ => tokens(item),
+    }
+}
+
+// Keys of the items generated for the instruction
+fn instruction_items(name: &str) -> [String; 4] {
+    [
+        format!("struct {name}"),
+        format!("struct {name}Accounts"),
+        format!("struct {name}Data"),
+        format!("impl IxOps for {name}"),
+    ]
+}
+
+fn trait_name(item_impl: &ItemImpl) -> Option<String> {
+    let (_, path, _) = item_impl.trait_.as_ref()?;
+    path.segments
+        .last()
+        .map(|segment| segment.ident.to_string())
+}
+
+fn method_ident(item: &ImplItem) -> Option<&syn::Ident> {
+    match item {
+        ImplItem::Fn(method) => Some(&method.sig.ident),
+        _ => None,
+    }
+}
+
+// Variants of the FuzzInstruction enum
+fn instruction_names(file: &syn::File) -> Vec<String> {
+    file.items
+        .iter()
+        .find_map(|item| match item {
+            Item::Enum(item_enum) if item_enum.ident == "FuzzInstruction" => Some(
+                item_enum
+                    .variants
+                    .iter()
+                    .map(|variant| variant.ident.to_string())
+                    .collect(),
+            ),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+// The `Self { .. }` expression returned from `new()`
+fn self_initializer(item_impl: &ItemImpl) -> Option<&syn::ExprStruct> {
+    item_impl.items.iter().find_map(|item| match item {
+        ImplItem::Fn(method) if method.sig.ident == "new" => match method.block.stmts.last() {
+            Some(Stmt::Expr(Expr::Struct(expr_struct), None)) => Some(expr_struct),
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+// Names of the Account Storages in FuzzAccounts
+fn storage_names(item: &SourceItem) -> Vec<String> {
+    match item.item {
+        Item::Struct(item_struct) => item_struct
+            .fields
+            .iter()
+            .filter(|field| tokens(&field.ty).starts_with("AccountsStorage"))
+            .map(|field| tokens(&field.ident))
+            .collect(),
+        _ => vec![],
+    }
+}
+
+// Same as generated by `get_fuzz_accounts_address_pool`
+fn address_pool_body(storages: &[String]) -> String {
+    if storages.is_empty() {
+        "vec![]".to_string()
+    } else {
+        let pubkeys: Vec<String> = storages
+            .iter()
+            .map(|storage| format!("self.{storage}.pubkeys()"))
+            .collect();
+        format!("[{}].concat()", pubkeys.join(", "))
+    }
+}
+
+// Fields with the trailing comma, as formatted by rustfmt
+fn comma_separated(fields: &[String]) -> String {
+    fields
+        .iter()
+        .map(|field| format!("{field},"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn tokens(tokens: &impl ToTokens) -> String {
+    tokens.to_token_stream().to_string()
+}
@@ -4,9 +4,14 @@ use syn::{parse_quote, Block};
 
 use trident_idl_spec::{
     IdlInstruction, IdlInstructionAccount, IdlInstructionAccountItem, IdlInstructionAccounts,
+    IdlPda, IdlSeed, IdlType,
 };
 
-use crate::instruction_account::{InstructionAccount, InstructionAccountType};
+use crate::get_data::resolve_pubkey;
+use crate::instruction_account::{
+    is_optional_account, InstructionAccount, InstructionAccountType, TOKEN_2022_PROGRAM_ID,
+    TOKEN_PROGRAM_ID,
+};
 
 pub(crate) fn get_accounts(
     instruction: &IdlInstruction,
@@ -18,7 +23,7 @@ pub(crate) fn get_accounts(
         match account {
             IdlInstructionAccountItem::Composite(idl_instruction_accounts) => {
                 process_composite_account(
-                    &instruction.name,
+                    instruction,
                     idl_instruction_accounts,
                     instructions_accounts,
                     &mut account_implementations,
@@ -26,7 +31,7 @@ pub(crate) fn get_accounts(
             }
             IdlInstructionAccountItem::Single(idl_instruction_account) => {
                 process_single_account(
-                    &instruction.name,
+                    instruction,
                     idl_instruction_account,
                     instructions_accounts,
                     &mut account_implementations,
@@ -39,7 +44,7 @@ pub(crate) fn get_accounts(
 }
 
 fn process_composite_account(
-    instruction: &str,
+    instruction: &IdlInstruction,
     accounts: &IdlInstructionAccounts,
     instructions_accounts: &HashMap<String, InstructionAccount>,
     account_implementations: &mut Vec<Block>,
@@ -67,41 +72,94 @@ fn process_composite_account(
 }
 
 fn process_single_account(
-    instruction: &str,
-    account: &IdlInstructionAccount,
+    instruction: &IdlInstruction,
+    idl_account: &IdlInstructionAccount,
     instructions_accounts: &HashMap<String, InstructionAccount>,
     account_implementations: &mut Vec<Block>,
 ) {
     let account = instructions_accounts
-        .get(&account.name)
+        .get(&idl_account.name)
         .expect("Account not found in types databse");
 
     let account_name = &account.account_name;
     let account_name_ident = format_ident!("{}", account_name);
 
+    // optional accounts are unwrapped within the match below
+    let optional = is_optional_account(idl_account);
+    let account_id: syn::Expr = match optional {
+        true => parse_quote!(account_id),
+        false => parse_quote!(self.accounts.#account_name_ident),
+    };
+
     let kind = account
         .kind
-        .get(instruction)
+        .get(&instruction.name)
         .unwrap_or(&InstructionAccountType::Unknown);
 
     let account_implementation = match kind {
         InstructionAccountType::Keypair(writable, signer) => {
-            process_keypair_account(&account_name_ident, *writable, *signer)
+            process_keypair_account(&account_name_ident, &account_id, *writable, *signer)
         }
 
-        InstructionAccountType::Pda(_idl_pda, writable, signer) => {
-            process_pda_account(&account_name_ident, *writable, *signer)
-        }
+        InstructionAccountType::Pda(idl_pda, writable, signer) => process_pda_account(
+            &account_name_ident,
+            &account_id,
+            idl_pda,
+            instruction,
+            instructions_accounts,
+            *writable,
+            *signer,
+        ),
+
+        InstructionAccountType::Ata(idl_pda, writable, signer) => process_ata_account(
+            &account_name_ident,
+            &account_id,
+            idl_pda,
+            instruction,
+            instructions_accounts,
+            *writable,
+            *signer,
+        ),
 
         InstructionAccountType::Constant(address, writable, signer) => {
             process_constant_account(address, *writable, *signer)
         }
         InstructionAccountType::Unknown => process_unknown_account(&account_name_ident),
     };
-    account_implementations.push(account_implementation);
+
+    match optional {
+        // if the optional account is absent, the program id is passed instead
+        true => account_implementations.push(parse_quote!({
+            match self.accounts.#account_name_ident {
+                Some(account_id) => #account_implementation
+                None => account_metas.push(AccountMeta::new_readonly(self.get_program_id(), false)),
+            }
+        })),
+        false => account_implementations.push(account_implementation),
+    }
+}
+
+// Find the account of the instruction by its name, including the nested accounts
+fn find_account<'a>(
+    accounts: &'a [IdlInstructionAccountItem],
+    name: &str,
+) -> Option<&'a IdlInstructionAccount> {
+    accounts.iter().find_map(|account| match account {
+        IdlInstructionAccountItem::Single(idl_account) => {
+            (idl_account.name == name).then_some(idl_account)
+        }
+        IdlInstructionAccountItem::Composite(idl_accounts) => {
+            find_account(&idl_accounts.accounts, name)
+        }
+    })
 }
 
-fn process_keypair_account(account_name: &syn::Ident, writable: bool, signer: bool) -> syn::Block {
+fn process_keypair_account(
+    account_name: &syn::Ident,
+    account_id: &syn::Expr,
+    writable: bool,
+    signer: bool,
+) -> syn::Block {
     match (writable, signer) {
         // If the account is writable and also signer
         // It has to be used with AccountMeta::new
@@ -110,7 +168,7 @@ fn process_keypair_account(account_name: &syn::Ident, writable: bool, signer: bo
             parse_quote!(
                 {
                     let #account_name = fuzz_accounts.#account_name.get_or_create_account(
-                        self.accounts.#account_name,
+                        #account_id,
                         client,
                         500 * LAMPORTS_PER_SOL,
                     );
@@ -126,7 +184,7 @@ fn process_keypair_account(account_name: &syn::Ident, writable: bool, signer: bo
             parse_quote!(
                 {
                     let #account_name = fuzz_accounts.#account_name.get_or_create_account(
-                        self.accounts.#account_name,
+                        #account_id,
                         client,
                         500 * LAMPORTS_PER_SOL,
                     );
@@ -142,7 +200,7 @@ fn process_keypair_account(account_name: &syn::Ident, writable: bool, signer: bo
             parse_quote!(
                 {
                     let #account_name = fuzz_accounts.#account_name.get_or_create_account(
-                        self.accounts.#account_name,
+                        #account_id,
                         client,
                         500 * LAMPORTS_PER_SOL,
                     );
@@ -158,7 +216,7 @@ fn process_keypair_account(account_name: &syn::Ident, writable: bool, signer: bo
             parse_quote!(
                 {
                     let #account_name = fuzz_accounts.#account_name.get_or_create_account(
-                        self.accounts.#account_name,
+                        #account_id,
                         client,
                         500 * LAMPORTS_PER_SOL,
                     );
@@ -170,19 +228,27 @@ fn process_keypair_account(account_name: &syn::Ident, writable: bool, signer: bo
     }
 }
 
-fn process_pda_account(account_name: &syn::Ident, writable: bool, _signer: bool) -> syn::Block {
-    match writable {
+fn process_pda_account(
+    account_name: &syn::Ident,
+    account_id: &syn::Expr,
+    idl_pda: &IdlPda,
+    instruction: &IdlInstruction,
+    instructions_accounts: &HashMap<String, InstructionAccount>,
+    writable: bool,
+    _signer: bool,
+) -> syn::Block {
+    let mut seed_accounts = SeedAccounts::new(instruction, instructions_accounts, account_name);
+    let address = seed_accounts.pda_address(account_name, account_id, idl_pda);
+    let statements = &seed_accounts.statements;
+
+    let account_implementation = match writable {
         // if the account is writable
         // It has to be used with AccountMeta::new
         true => {
             parse_quote!(
                 {
-                    let #account_name = fuzz_accounts.#account_name.get_or_create_account(
-                        self.accounts.#account_name,
-                        client,
-                        &[todo!()],
-                        &self.get_program_id(),
-                    );
+                    #(#statements)*
+                    let #account_name = #address;
                     account_metas.push(AccountMeta::new(#account_name, false));
                 }
 
@@ -193,16 +259,317 @@ fn process_pda_account(account_name: &syn::Ident, writable: bool, _signer: bool)
         false => {
             parse_quote!(
                 {
-                    let #account_name = fuzz_accounts.#account_name.get_or_create_account(
-                        self.accounts.#account_name,
-                        client,
-                        &[todo!()],
-                        &self.get_program_id(),
-                    );
+                    #(#statements)*
+                    let #account_name = #address;
+                    account_metas.push(AccountMeta::new_readonly(#account_name, false));
+                }
+            )
+        }
+    };
+
+    seed_accounts.if_present(account_implementation)
+}
+
+fn process_ata_account(
+    account_name: &syn::Ident,
+    account_id: &syn::Expr,
+    idl_pda: &IdlPda,
+    instruction: &IdlInstruction,
+    instructions_accounts: &HashMap<String, InstructionAccount>,
+    writable: bool,
+    _signer: bool,
+) -> syn::Block {
+    let mut seed_accounts = SeedAccounts::new(instruction, instructions_accounts, account_name);
+    let address = seed_accounts.ata_address(account_name, account_id, idl_pda);
+    let statements = &seed_accounts.statements;
+
+    let account_implementation = match writable {
+        // if the account is writable
+        // It has to be used with AccountMeta::new
+        true => {
+            parse_quote!(
+                {
+                    #(#statements)*
+                    let #account_name = #address;
+                    account_metas.push(AccountMeta::new(#account_name, false));
+                }
+
+            )
+        }
+        // if the account is not writable
+        // It has to be used with AccountMeta::new_readonly
+        false => {
+            parse_quote!(
+                {
+                    #(#statements)*
+                    let #account_name = #address;
                     account_metas.push(AccountMeta::new_readonly(#account_name, false));
                 }
             )
         }
+    };
+
+    seed_accounts.if_present(account_implementation)
+}
+
+// Accounts referenced by the seeds of a PDA might come later in the instruction, so they
+// are created by statements preceding the derivation of the PDA
+struct SeedAccounts<'a> {
+    instruction: &'a IdlInstruction,
+    instructions_accounts: &'a HashMap<String, InstructionAccount>,
+    // accounts being derived, references back to them are not created again
+    resolving: Vec<String>,
+    // optional accounts referenced by the seeds, their AccountIds are bound by a match
+    // surrounding the statements
+    optional: Vec<syn::Ident>,
+    statements: Vec<syn::Stmt>,
+}
+
+impl<'a> SeedAccounts<'a> {
+    fn new(
+        instruction: &'a IdlInstruction,
+        instructions_accounts: &'a HashMap<String, InstructionAccount>,
+        account_name: &syn::Ident,
+    ) -> Self {
+        Self {
+            instruction,
+            instructions_accounts,
+            resolving: vec![account_name.to_string()],
+            optional: vec![],
+            statements: vec![],
+        }
+    }
+
+    // The account is derived only if the optional accounts referenced by the seeds are present,
+    // otherwise it is passed as absent
+    fn if_present(&self, account_implementation: syn::Block) -> syn::Block {
+        let accounts = &self.optional;
+        let account_ids: Vec<syn::Ident> = accounts.iter().map(optional_account_id).collect();
+        match accounts.as_slice() {
+            [] => account_implementation,
+            [account] => parse_quote!({
+                match self.accounts.#account {
+                    Some(#(#account_ids)*) => #account_implementation
+                    None => account_metas.push(AccountMeta::new_readonly(self.get_program_id(), false)),
+                }
+            }),
+            _ => parse_quote!({
+                match (#(self.accounts.#accounts),*) {
+                    (#(Some(#account_ids)),*) => #account_implementation
+                    _ => account_metas.push(AccountMeta::new_readonly(self.get_program_id(), false)),
+                }
+            }),
+        }
+    }
+
+    fn pda_address(
+        &mut self,
+        account_name: &syn::Ident,
+        account_id: &syn::Expr,
+        idl_pda: &IdlPda,
+    ) -> syn::Expr {
+        let seeds: Vec<syn::Expr> = idl_pda
+            .seeds
+            .iter()
+            .map(|seed| self.process_seed(seed))
+            .collect();
+
+        // if the program is not specified, the PDA is derived from the program itself
+        let program_id: syn::Expr = match &idl_pda.program {
+            Some(IdlSeed::Const(program)) => match <[u8; 32]>::try_from(program.value.as_slice()) {
+                Ok(program) => parse_quote!(Pubkey::new_from_array([#(#program),*])),
+                Err(_) => parse_quote!(todo!()),
+            },
+            Some(IdlSeed::Account(program)) => self
+                .account_seed_pubkey(&program.path)
+                .unwrap_or_else(|| parse_quote!(todo!())),
+            Some(IdlSeed::Arg(_)) => parse_quote!(todo!()),
+            None => parse_quote!(self.get_program_id()),
+        };
+
+        parse_quote!(fuzz_accounts.#account_name.get_or_create_account(
+            #account_id,
+            client,
+            &[#(#seeds),*],
+            &#program_id,
+        ))
+    }
+
+    fn ata_address(
+        &mut self,
+        account_name: &syn::Ident,
+        account_id: &syn::Expr,
+        idl_pda: &IdlPda,
+    ) -> syn::Expr {
+        // the wallet and the mint are the first and the third seed of the associated token account
+        let mut ata_seed_pubkey = |seed: Option<&IdlSeed>| -> syn::Expr {
+            match seed {
+                Some(IdlSeed::Account(seed)) => self
+                    .account_seed_pubkey(&seed.path)
+                    .unwrap_or_else(|| parse_quote!(todo!())),
+                _ => parse_quote!(todo!()),
+            }
+        };
+        let wallet = ata_seed_pubkey(idl_pda.seeds.first());
+        let mint = ata_seed_pubkey(idl_pda.seeds.get(2));
+
+        // the token program is the second seed of the associated token account
+        let token_program: syn::Expr = match idl_pda.seeds.get(1) {
+            Some(IdlSeed::Const(seed)) if seed.value == TOKEN_PROGRAM_ID => {
+                parse_quote!(pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"))
+            }
+            Some(IdlSeed::Const(seed)) if seed.value == TOKEN_2022_PROGRAM_ID => {
+                parse_quote!(pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb"))
+            }
+            _ => parse_quote!(todo!()),
+        };
+
+        parse_quote!(fuzz_accounts.#account_name.get_or_create_account(
+            #account_id,
+            client,
+            #wallet,
+            #mint,
+            &#token_program,
+            0,
+        ))
+    }
+
+    fn process_seed(&mut self, seed: &IdlSeed) -> syn::Expr {
+        match seed {
+            // readable constant seeds are used as string literals, the rest as byte arrays
+            IdlSeed::Const(seed) => {
+                if seed
+                    .value
+                    .iter()
+                    .all(|byte| *byte == b' ' || byte.is_ascii_graphic())
+                {
+                    let value = String::from_utf8_lossy(&seed.value).to_string();
+                    parse_quote!(#value.as_bytes())
+                } else {
+                    let value = &seed.value;
+                    parse_quote!(&[#(#value),*])
+                }
+            }
+            // argument seeds are read from the instruction data
+            IdlSeed::Arg(seed) => {
+                arg_seed(&seed.path, self.instruction, self.instructions_accounts)
+            }
+            // account seeds are public keys of the referenced accounts
+            IdlSeed::Account(seed) => match self.account_seed_pubkey(&seed.path) {
+                Some(pubkey) => parse_quote!(#pubkey.as_ref()),
+                None => parse_quote!(todo!()),
+            },
+        }
+    }
+
+    fn account_seed_pubkey(&mut self, path: &str) -> Option<syn::Expr> {
+        // fields of accounts (e.g. `config.authority`) require the account data
+        let account = self.instructions_accounts.get(path)?;
+        let account_ident = format_ident!("{}", account.account_name);
+
+        // the AccountIds of optional accounts are bound only if the accounts are present
+        let account_id: syn::Expr =
+            match find_account(&self.instruction.accounts, path).is_some_and(is_optional_account) {
+                true => {
+                    if !self.optional.contains(&account_ident) {
+                        self.optional.push(account_ident.clone());
+                    }
+                    let account_id = optional_account_id(&account_ident);
+                    parse_quote!(#account_id)
+                }
+                false => parse_quote!(self.accounts.#account_ident),
+            };
+
+        // accounts referencing each other cannot be created in order
+        if self.resolving.contains(&account.account_name) {
+            return match account.kind.get(&self.instruction.name) {
+                Some(InstructionAccountType::Keypair(_, _)) => Some(parse_quote!(
+                    fuzz_accounts.#account_ident.get(#account_id).pubkey()
+                )),
+                Some(
+                    InstructionAccountType::Pda(_, _, _) | InstructionAccountType::Ata(_, _, _),
+                ) => Some(parse_quote!(fuzz_accounts.#account_ident.get(#account_id))),
+                _ => None,
+            };
+        }
+
+        // the referenced account is obtained from its account storage
+        let address: syn::Expr = match account.kind.get(&self.instruction.name) {
+            Some(InstructionAccountType::Keypair(_, _)) => parse_quote!(
+                fuzz_accounts.#account_ident.get_or_create_account(
+                    #account_id,
+                    client,
+                    500 * LAMPORTS_PER_SOL,
+                ).pubkey()
+            ),
+            Some(InstructionAccountType::Pda(idl_pda, _, _)) => {
+                self.resolving.push(account.account_name.clone());
+                let address = self.pda_address(&account_ident, &account_id, idl_pda);
+                self.resolving.pop();
+                address
+            }
+            Some(InstructionAccountType::Ata(idl_pda, _, _)) => {
+                self.resolving.push(account.account_name.clone());
+                let address = self.ata_address(&account_ident, &account_id, idl_pda);
+                self.resolving.pop();
+                address
+            }
+            Some(InstructionAccountType::Constant(address, _, _)) => {
+                return Some(parse_quote!(pubkey!(#address)))
+            }
+            Some(InstructionAccountType::Unknown) | None => return None,
+        };
+
+        self.statements
+            .push(parse_quote!(let #account_ident = #address;));
+        Some(parse_quote!(#account_ident))
+    }
+}
+
+// Binding of the AccountId of an optional account referenced by the seeds
+fn optional_account_id(account_name: &syn::Ident) -> syn::Ident {
+    format_ident!("{}_id", account_name)
+}
+
+fn arg_seed(
+    path: &str,
+    instruction: &IdlInstruction,
+    instructions_accounts: &HashMap<String, InstructionAccount>,
+) -> syn::Expr {
+    // fields of custom types (e.g. `params.id`) cannot be resolved without the type definitions
+    let argument = match instruction.args.iter().find(|arg| arg.name == path) {
+        Some(argument) => argument,
+        None => return parse_quote!(todo!()),
+    };
+    let argument_ident = format_ident!("{}", argument.name);
+
+    match &argument.ty {
+        IdlType::U8
+        | IdlType::I8
+        | IdlType::U16
+        | IdlType::I16
+        | IdlType::U32
+        | IdlType::I32
+        | IdlType::U64
+        | IdlType::I64
+        | IdlType::U128
+        | IdlType::I128 => parse_quote!(&self.data.#argument_ident.to_le_bytes()),
+        IdlType::Bool => parse_quote!(&[self.data.#argument_ident as u8]),
+        IdlType::String => parse_quote!(self.data.#argument_ident.as_bytes()),
+        IdlType::Bytes => parse_quote!(&self.data.#argument_ident),
+        IdlType::Vec(inner) | IdlType::Array(inner, _) if **inner == IdlType::U8 => {
+            parse_quote!(&self.data.#argument_ident)
+        }
+        // Public Keys are stored as AccountIds within the instruction data
+        IdlType::Pubkey | IdlType::PublicKey => {
+            let pubkey = resolve_pubkey(
+                &argument.name,
+                &parse_quote!(self.data.#argument_ident),
+                instructions_accounts,
+            );
+            parse_quote!(#pubkey.as_ref())
+        }
+        _ => parse_quote!(todo!()),
     }
 }
 
@@ -4,21 +4,24 @@ use syn::{parse_quote, Block};
 
 use trident_idl_spec::{IdlField, IdlInstruction, IdlType};
 
-use crate::instruction_account::InstructionAccount;
+use crate::instruction_account::{FuzzAccountsType, InstructionAccount};
 
 pub(crate) fn get_data(
     instruction: &IdlInstruction,
-    _instructions_accounts: &HashMap<String, InstructionAccount>,
+    instructions_accounts: &HashMap<String, InstructionAccount>,
 ) -> Vec<syn::Block> {
     let mut args_implementations = vec![];
 
     for argument in &instruction.args {
-        match argument.ty {
-            IdlType::Pubkey => {
-                process_pubkey(argument, &mut args_implementations);
+        match &argument.ty {
+            IdlType::Pubkey | IdlType::PublicKey => {
+                process_pubkey(argument, instructions_accounts, &mut args_implementations);
             }
-            IdlType::PublicKey => {
-                process_pubkey(argument, &mut args_implementations);
+            IdlType::Option(inner) if is_pubkey(inner) => {
+                process_optional_pubkey(argument, instructions_accounts, &mut args_implementations);
+            }
+            IdlType::Vec(inner) if is_pubkey(inner) => {
+                process_pubkey_vec(argument, instructions_accounts, &mut args_implementations);
             }
             _ => {
                 process_argument(argument, &mut args_implementations);
@@ -29,13 +32,85 @@ pub(crate) fn get_data(
     args_implementations
 }
 
-fn process_pubkey(argument: &IdlField, arguments_implementation: &mut Vec<Block>) {
-    let argument_name = &argument.name;
-    let argument_ident = format_ident!("{}", argument_name);
+/// Expression resolving the AccountId of a Public Key argument to the Public Key,
+/// the Account Storage is matched by the argument name, otherwise the address is
+/// picked from the pool of known addresses
+pub(crate) fn resolve_pubkey(
+    argument_name: &str,
+    account_id: &syn::Expr,
+    instructions_accounts: &HashMap<String, InstructionAccount>,
+) -> syn::Expr {
+    match matching_storage(argument_name, instructions_accounts) {
+        Some((storage, FuzzAccountsType::Keypair)) => {
+            parse_quote!(fuzz_accounts.#storage.get(#account_id).pubkey())
+        }
+        Some((storage, _)) => parse_quote!(fuzz_accounts.#storage.get(#account_id)),
+        None => parse_quote!(fuzz_accounts.pick_address(#account_id)),
+    }
+}
+
+fn process_pubkey(
+    argument: &IdlField,
+    instructions_accounts: &HashMap<String, InstructionAccount>,
+    arguments_implementation: &mut Vec<Block>,
+) {
+    let argument_ident = format_ident!("{}", argument.name);
+
+    let pubkey = resolve_pubkey(
+        &argument.name,
+        &parse_quote!(self.data.#argument_ident),
+        instructions_accounts,
+    );
+
+    let argument_implementation = parse_quote!({
+        let #argument_ident: Pubkey = #pubkey;
+        args.extend(borsh::to_vec(&#argument_ident).unwrap());
+    });
+
+    arguments_implementation.push(argument_implementation);
+}
+
+fn process_optional_pubkey(
+    argument: &IdlField,
+    instructions_accounts: &HashMap<String, InstructionAccount>,
+    arguments_implementation: &mut Vec<Block>,
+) {
+    let argument_ident = format_ident!("{}", argument.name);
+
+    let pubkey = resolve_pubkey(
+        &argument.name,
+        &parse_quote!(account_id),
+        instructions_accounts,
+    );
 
-    // TODO: maybe generated more code so it is easier to fill
     let argument_implementation = parse_quote!({
-        let #argument_ident: Pubkey = todo!();
+        let #argument_ident: Option<Pubkey> = self.data.#argument_ident.map(|account_id| #pubkey);
+        args.extend(borsh::to_vec(&#argument_ident).unwrap());
+    });
+
+    arguments_implementation.push(argument_implementation);
+}
+
+fn process_pubkey_vec(
+    argument: &IdlField,
+    instructions_accounts: &HashMap<String, InstructionAccount>,
+    arguments_implementation: &mut Vec<Block>,
+) {
+    let argument_ident = format_ident!("{}", argument.name);
+
+    let pubkey = resolve_pubkey(
+        &argument.name,
+        &parse_quote!(*account_id),
+        instructions_accounts,
+    );
+
+    let argument_implementation = parse_quote!({
+        let #argument_ident: Vec<Pubkey> = self
+            .data
+            .#argument_ident
+            .iter()
+            .map(|account_id| #pubkey)
+            .collect();
         args.extend(borsh::to_vec(&#argument_ident).unwrap());
     });
 
@@ -52,3 +127,33 @@ fn process_argument(argument: &IdlField, arguments_implementation: &mut Vec<Bloc
     });
     arguments_implementation.push(other_arg);
 }
+
+fn is_pubkey(idl_type: &IdlType) -> bool {
+    matches!(idl_type, IdlType::Pubkey | IdlType::PublicKey)
+}
+
+// Account Storage with the name of the argument, or the name without common affixes
+// e.g. `new_authority` or `authority_key` are matched with the `authority` storage
+fn matching_storage(
+    argument_name: &str,
+    instructions_accounts: &HashMap<String, InstructionAccount>,
+) -> Option<(syn::Ident, FuzzAccountsType)> {
+    let stripped = argument_name.strip_prefix("new_").unwrap_or(argument_name);
+    let stripped = ["_pubkey", "_key", "_address"]
+        .iter()
+        .find_map(|suffix| stripped.strip_suffix(suffix))
+        .unwrap_or(stripped);
+
+    [argument_name, stripped].iter().find_map(|name| {
+        let account = instructions_accounts.get(*name)?;
+        match account.get_fuzz_accounts_type() {
+            // only these have an Account Storage the Public Key can be obtained from
+            account_type @ (FuzzAccountsType::Keypair
+            | FuzzAccountsType::Pda
+            | FuzzAccountsType::Ata) => {
+                Some((format_ident!("{}", account.account_name), account_type))
+            }
+            FuzzAccountsType::Constant | FuzzAccountsType::Unknown => None,
+        }
+    })
+}
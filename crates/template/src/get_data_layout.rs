@@ -0,0 +1,237 @@
+use proc_macro2::Literal;
+use std::collections::HashMap;
+use syn::parse_quote;
+
+use trident_idl_spec::{
+    DefinedType, Idl, IdlArrayLen, IdlDefinedFields, IdlGenericArg, IdlInstruction,
+    IdlSerialization, IdlType, IdlTypeDef, IdlTypeDefGeneric, IdlTypeDefTy,
+};
+
+// Recursive types cannot be laid out, the layout is unknown below this depth
+const MAX_DEPTH: usize = 32;
+
+// Borsh layout of an argument, generated as `DataType` of the fuzz test
+#[derive(Clone)]
+enum ArgLayout {
+    Fixed(usize),
+    Option(Box<ArgLayout>),
+    Vec(Box<ArgLayout>),
+    Array(Box<ArgLayout>, usize),
+    Struct(Vec<ArgLayout>),
+    Enum(Vec<Vec<ArgLayout>>),
+    Unknown,
+}
+
+// Generic argument of a defined type
+#[derive(Clone)]
+enum GenericValue {
+    Type(ArgLayout),
+    Len(Option<usize>),
+}
+
+/// Layouts of the Instruction arguments, `None` if the data have no enum tags or length prefixes
+pub(crate) fn get_data_layout(instruction: &IdlInstruction, idl: &Idl) -> Option<Vec<syn::Expr>> {
+    let layouts: Vec<ArgLayout> = instruction
+        .args
+        .iter()
+        .map(|argument| arg_layout(&argument.ty, idl, &HashMap::new(), 0))
+        .collect();
+
+    if !layouts.iter().any(ArgLayout::has_offsets) {
+        return None;
+    }
+    Some(layouts.iter().map(ArgLayout::to_expr).collect())
+}
+
+fn arg_layout(
+    idl_type: &IdlType,
+    idl: &Idl,
+    generics: &HashMap<String, GenericValue>,
+    depth: usize,
+) -> ArgLayout {
+    if depth > MAX_DEPTH {
+        return ArgLayout::Unknown;
+    }
+
+    match idl_type {
+        IdlType::Bool | IdlType::U8 | IdlType::I8 => ArgLayout::Fixed(1),
+        IdlType::U16 | IdlType::I16 => ArgLayout::Fixed(2),
+        IdlType::U32 | IdlType::I32 | IdlType::F32 => ArgLayout::Fixed(4),
+        IdlType::U64 | IdlType::I64 | IdlType::F64 => ArgLayout::Fixed(8),
+        IdlType::U128 | IdlType::I128 => ArgLayout::Fixed(16),
+        IdlType::U256 | IdlType::I256 | IdlType::Pubkey | IdlType::PublicKey => {
+            ArgLayout::Fixed(32)
+        }
+        IdlType::Bytes | IdlType::String => ArgLayout::Vec(Box::new(ArgLayout::Fixed(1))),
+        IdlType::Option(inner) => {
+            ArgLayout::Option(Box::new(arg_layout(inner, idl, generics, depth + 1)))
+        }
+        IdlType::Vec(inner) => {
+            ArgLayout::Vec(Box::new(arg_layout(inner, idl, generics, depth + 1)))
+        }
+        IdlType::Array(inner, len) => {
+            let len = match len {
+                IdlArrayLen::Value(len) => Some(*len),
+                IdlArrayLen::Generic(name) => match generics.get(name) {
+                    Some(GenericValue::Len(len)) => *len,
+                    _ => None,
+                },
+            };
+            match len {
+                Some(len) => ArgLayout::array(arg_layout(inner, idl, generics, depth + 1), len),
+                None => ArgLayout::Unknown,
+            }
+        }
+        IdlType::Generic(name) => match generics.get(name) {
+            Some(GenericValue::Type(layout)) => layout.clone(),
+            _ => ArgLayout::Unknown,
+        },
+        IdlType::Defined(defined) => {
+            let (name, generic_args) = match defined {
+                DefinedType::Simple(name) => (name, &[][..]),
+                DefinedType::Complex { name, generics } => (name, &generics[..]),
+            };
+            match idl.types.iter().find(|type_def| &type_def.name == name) {
+                Some(type_def) => {
+                    let type_generics =
+                        defined_generics(type_def, generic_args, idl, generics, depth);
+                    type_def_layout(type_def, idl, &type_generics, depth + 1)
+                }
+                None => ArgLayout::Unknown,
+            }
+        }
+        _ => ArgLayout::Unknown,
+    }
+}
+
+// Generic arguments of the defined type resolved in the context of the type using it
+fn defined_generics(
+    type_def: &IdlTypeDef,
+    generic_args: &[IdlGenericArg],
+    idl: &Idl,
+    generics: &HashMap<String, GenericValue>,
+    depth: usize,
+) -> HashMap<String, GenericValue> {
+    type_def
+        .generics
+        .iter()
+        .zip(generic_args)
+        .map(|(generic, generic_arg)| match (generic, generic_arg) {
+            (IdlTypeDefGeneric::Type { name }, IdlGenericArg::Type { ty }) => (
+                name.clone(),
+                GenericValue::Type(arg_layout(ty, idl, generics, depth + 1)),
+            ),
+            (IdlTypeDefGeneric::Const { name, .. }, IdlGenericArg::Const { value }) => {
+                (name.clone(), GenericValue::Len(value.parse().ok()))
+            }
+            (IdlTypeDefGeneric::Type { name }, _) => {
+                (name.clone(), GenericValue::Type(ArgLayout::Unknown))
+            }
+            (IdlTypeDefGeneric::Const { name, .. }, _) => (name.clone(), GenericValue::Len(None)),
+        })
+        .collect()
+}
+
+fn type_def_layout(
+    type_def: &IdlTypeDef,
+    idl: &Idl,
+    generics: &HashMap<String, GenericValue>,
+    depth: usize,
+) -> ArgLayout {
+    if type_def.serialization != IdlSerialization::Borsh {
+        return ArgLayout::Unknown;
+    }
+
+    let fields_layout = |fields: &Option<IdlDefinedFields>| -> Vec<ArgLayout> {
+        match fields {
+            Some(IdlDefinedFields::Named(fields)) => fields
+                .iter()
+                .map(|field| arg_layout(&field.ty, idl, generics, depth))
+                .collect(),
+            Some(IdlDefinedFields::Tuple(types)) => types
+                .iter()
+                .map(|ty| arg_layout(ty, idl, generics, depth))
+                .collect(),
+            None => vec![],
+        }
+    };
+
+    match &type_def.ty {
+        IdlTypeDefTy::Struct { fields } => ArgLayout::structure(fields_layout(fields)),
+        IdlTypeDefTy::Enum { variants } => ArgLayout::Enum(
+            variants
+                .iter()
+                .map(|variant| fields_layout(&variant.fields))
+                .collect(),
+        ),
+        IdlTypeDefTy::Type { alias } => arg_layout(alias, idl, generics, depth),
+    }
+}
+
+impl ArgLayout {
+    // Fixed size items are laid out as a single fixed size value
+    fn array(item: ArgLayout, len: usize) -> Self {
+        match item {
+            ArgLayout::Fixed(size) => ArgLayout::Fixed(size * len),
+            item => ArgLayout::Array(Box::new(item), len),
+        }
+    }
+
+    // Fixed size fields are laid out as a single fixed size value
+    fn structure(fields: Vec<ArgLayout>) -> Self {
+        let fixed: Option<usize> = fields
+            .iter()
+            .map(|field| match field {
+                ArgLayout::Fixed(size) => Some(*size),
+                _ => None,
+            })
+            .sum();
+        match fixed {
+            Some(size) => ArgLayout::Fixed(size),
+            None => ArgLayout::Struct(fields),
+        }
+    }
+
+    fn has_offsets(&self) -> bool {
+        match self {
+            ArgLayout::Option(_) | ArgLayout::Vec(_) | ArgLayout::Enum(_) => true,
+            ArgLayout::Array(item, _) => item.has_offsets(),
+            ArgLayout::Struct(fields) => fields.iter().any(ArgLayout::has_offsets),
+            ArgLayout::Fixed(_) | ArgLayout::Unknown => false,
+        }
+    }
+
+    fn to_expr(&self) -> syn::Expr {
+        match self {
+            ArgLayout::Fixed(size) => {
+                let size = Literal::usize_unsuffixed(*size);
+                parse_quote!(DataType::Fixed(#size))
+            }
+            ArgLayout::Option(inner) => {
+                let inner = inner.to_expr();
+                parse_quote!(DataType::Option(Box::new(#inner)))
+            }
+            ArgLayout::Vec(item) => {
+                let item = item.to_expr();
+                parse_quote!(DataType::Vec(Box::new(#item)))
+            }
+            ArgLayout::Array(item, len) => {
+                let item = item.to_expr();
+                let len = Literal::usize_unsuffixed(*len);
+                parse_quote!(DataType::Array(Box::new(#item), #len))
+            }
+            ArgLayout::Struct(fields) => {
+                let fields = fields.iter().map(ArgLayout::to_expr);
+                parse_quote!(DataType::Struct(vec![#(#fields),*]))
+            }
+            ArgLayout::Enum(variants) => {
+                let variants = variants.iter().map(|fields| {
+                    let fields = fields.iter().map(ArgLayout::to_expr);
+                    quote::quote!(vec![#(#fields),*])
+                });
+                parse_quote!(DataType::Enum(vec![#(#variants),*]))
+            }
+            ArgLayout::Unknown => parse_quote!(DataType::Unknown),
+        }
+    }
+}
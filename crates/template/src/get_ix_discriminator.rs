@@ -1,13 +1,16 @@
 use sha2::{Digest, Sha256};
 
 use convert_case::{Case, Casing};
-use trident_idl_spec::IdlInstruction;
+use trident_idl_spec::{Idl, IdlInstruction};
+
+use crate::idl_origin::is_anchor_idl;
 
 pub const SIGHASH_GLOBAL_NAMESPACE: &str = "global";
 
-pub(crate) fn process_discriminator(instruction: &IdlInstruction) -> Vec<u8> {
-    // if discriminator is not provided, generate it
-    if instruction.discriminator.is_empty() {
+pub(crate) fn process_discriminator(idl: &Idl, instruction: &IdlInstruction) -> Vec<u8> {
+    // if discriminator is not provided, generate it,
+    // instructions of non-Anchor programs might have no discriminator at all
+    if instruction.discriminator.is_empty() && is_anchor_idl(idl) {
         let ix_name_snake_case = instruction.name.to_case(Case::Snake);
         gen_discriminator(SIGHASH_GLOBAL_NAMESPACE, &ix_name_snake_case).to_vec()
     } else {
@@ -16,7 +19,7 @@ pub(crate) fn process_discriminator(instruction: &IdlInstruction) -> Vec<u8> {
     }
 }
 
-fn gen_discriminator(namespace: &str, name: &str) -> [u8; 8] {
+pub(crate) fn gen_discriminator(namespace: &str, name: &str) -> [u8; 8] {
     let preimage = format!("{namespace}:{name}");
 
     let mut hasher = Sha256::new();
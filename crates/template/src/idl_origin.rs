@@ -0,0 +1,12 @@
+use trident_idl_spec::Idl;
+
+/// Spec of IDLs converted from Shank IDLs
+pub const SHANK_IDL_SPEC: &str = "shank";
+/// Spec of IDLs converted from Codama IDLs
+pub const CODAMA_IDL_SPEC: &str = "codama";
+
+/// IDLs of non-Anchor programs are converted into the Anchor IDL format, but keep the spec
+/// of the original format, as the programs have no sighash discriminators and no `entry`
+pub fn is_anchor_idl(idl: &Idl) -> bool {
+    !matches!(idl.metadata.spec.as_str(), SHANK_IDL_SPEC | CODAMA_IDL_SPEC)
+}
@@ -1,6 +1,19 @@
 use std::collections::{HashMap, HashSet};
 
-use trident_idl_spec::IdlPda;
+use trident_idl_spec::{IdlInstructionAccount, IdlPda, IdlSeed};
+
+pub(crate) const ASSOCIATED_TOKEN_PROGRAM_ID: [u8; 32] = [
+    140, 151, 37, 143, 78, 36, 137, 241, 187, 61, 16, 41, 20, 142, 13, 131, 11, 90, 19, 153, 218,
+    255, 16, 132, 4, 142, 123, 216, 219, 233, 248, 89,
+];
+pub(crate) const TOKEN_PROGRAM_ID: [u8; 32] = [
+    6, 221, 246, 225, 215, 101, 161, 147, 217, 203, 225, 70, 206, 235, 121, 172, 28, 180, 133, 237,
+    95, 91, 55, 145, 58, 140, 245, 133, 126, 255, 0, 169,
+];
+pub(crate) const TOKEN_2022_PROGRAM_ID: [u8; 32] = [
+    6, 221, 246, 225, 238, 117, 143, 222, 24, 66, 93, 188, 228, 108, 205, 218, 182, 26, 252, 77,
+    131, 185, 13, 39, 254, 189, 249, 40, 216, 161, 139, 252,
+];
 
 pub(crate) struct InstructionAccount {
     // account name
@@ -21,6 +34,8 @@ pub(crate) enum InstructionAccountType {
     Keypair(bool, bool),
     // writable | signer (PDA cannot be signer, but keep it simple)
     Pda(IdlPda, bool, bool),
+    // writable | signer (ATA cannot be signer, but keep it simple)
+    Ata(IdlPda, bool, bool),
     // writable | signer
     Constant(String, bool, bool),
 }
@@ -33,6 +48,7 @@ pub(crate) enum FuzzAccountsType {
     Unknown,
     Keypair,
     Pda,
+    Ata,
     Constant,
 }
 
@@ -70,8 +86,25 @@ impl From<&InstructionAccountType> for FuzzAccountsType {
         match account_type {
             InstructionAccountType::Keypair(_, _) => FuzzAccountsType::Keypair,
             InstructionAccountType::Pda(_, _, _) => FuzzAccountsType::Pda,
+            InstructionAccountType::Ata(_, _, _) => FuzzAccountsType::Ata,
             InstructionAccountType::Constant(_, _, _) => FuzzAccountsType::Constant,
             InstructionAccountType::Unknown => FuzzAccountsType::Unknown,
         }
     }
 }
+
+// The associated token account is a PDA of the associated token program
+// with the wallet, token program and mint as seeds
+pub(crate) fn is_associated_token_account(idl_pda: &IdlPda) -> bool {
+    let is_ata_program = match &idl_pda.program {
+        Some(IdlSeed::Const(program)) => program.value == ASSOCIATED_TOKEN_PROGRAM_ID,
+        _ => false,
+    };
+    is_ata_program && idl_pda.seeds.len() == 3
+}
+
+// Accounts with constant address are not part of the generated Accounts struct,
+// so they cannot be optional
+pub(crate) fn is_optional_account(idl_account: &IdlInstructionAccount) -> bool {
+    idl_account.optional && idl_account.address.is_none()
+}
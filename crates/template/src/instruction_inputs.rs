@@ -4,10 +4,12 @@ use syn::{parse_quote, FnArg};
 
 use trident_idl_spec::IdlInstructionAccounts;
 use trident_idl_spec::{
-    idl_type_to_syn_type, Idl, IdlField, IdlInstruction, IdlInstructionAccount,
-    IdlInstructionAccountItem,
+    Idl, IdlField, IdlInstruction, IdlInstructionAccount, IdlInstructionAccountItem,
 };
 
+use crate::instruction_account::is_optional_account;
+use crate::syn_types::idl_type_to_syn_type;
+
 // Generate input structures for each instruction
 pub(crate) fn get_instruction_inputs(idl: &Idl) -> Vec<syn::ItemStruct> {
     let _program_name = idl.metadata.name.to_case(Case::UpperCamel);
@@ -35,7 +37,8 @@ pub(crate) fn get_instruction_inputs(idl: &Idl) -> Vec<syn::ItemStruct> {
                 #[derive(Arbitrary, Debug)]
                 pub struct #instruction_name_ident {
                      pub accounts: #instruction_accounts_name,
-                     pub data: #instruction_data_name
+                     pub data: #instruction_data_name,
+                     pub remaining_accounts: Vec<RemainingAccount>
                 }
             };
 
@@ -51,6 +54,7 @@ pub(crate) fn get_instruction_inputs(idl: &Idl) -> Vec<syn::ItemStruct> {
                 /// To do this, redefine the type in the fuzz test and implement the `From` trait
                 /// to convert it into the type defined in the program.
                 /// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#custom-data-types
+                #[fuzz_constraints]
                 #[derive(Arbitrary, Debug, BorshDeserialize, BorshSerialize)]
                 pub struct #instruction_data_name {
                      #(pub #data),*
@@ -105,7 +109,10 @@ fn process_single_account(
     // So it will not be generated as a parameter.
     if idl_instruction_account.address.is_none() {
         let name = format_ident!("{}", idl_instruction_account.name);
-        let account: syn::FnArg = parse_quote!(#name: AccountId);
+        let account: syn::FnArg = match is_optional_account(idl_instruction_account) {
+            true => parse_quote!(#name: Option<AccountId>),
+            false => parse_quote!(#name: AccountId),
+        };
         account_parameters.push(account);
     }
 }
@@ -124,7 +131,7 @@ fn process_instruction_argument(argument: &IdlField, arguments: &mut Vec<FnArg>)
     let arg_name = format_ident!("{}", argument.name);
 
     // convert type to syn type
-    let (arg_type, _is_custom) = idl_type_to_syn_type(&argument.ty, 0, true);
+    let arg_type = idl_type_to_syn_type(&argument.ty, true);
 
     let parameter: syn::FnArg = parse_quote!(#arg_name: #arg_type);
 
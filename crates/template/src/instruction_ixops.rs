@@ -6,8 +6,8 @@ use syn::parse_quote;
 use trident_idl_spec::Idl;
 
 use crate::{
-    get_accounts::get_accounts, get_data::get_data, instruction_account::InstructionAccount,
-    process_discriminator, process_program_id,
+    get_accounts::get_accounts, get_data::get_data, get_data_layout::get_data_layout,
+    instruction_account::InstructionAccount, process_discriminator, process_program_id,
 };
 
 // Generate implementation of IxOps trait for each instruction
@@ -21,10 +21,21 @@ pub(crate) fn get_instruction_ixops(
             let instruction_name = instruction.name.to_case(Case::UpperCamel);
             let instruction_name_ident: syn::Ident = format_ident!("{}", &instruction_name);
 
-            let instruction_discriminator = process_discriminator(instruction);
+            let instruction_discriminator = process_discriminator(idl, instruction);
             let program_id = process_program_id(idl);
             let data = get_data(instruction, instructions_accounts);
             let accounts = get_accounts(instruction, instructions_accounts);
+            let data_layout = get_data_layout(instruction, idl).map(|layouts| -> syn::ImplItemFn {
+                parse_quote! {
+                    /// Definition of the layouts of the Instruction arguments, the enum tags
+                    /// and length prefixes are mutated if `data_mutations` is enabled in Trident.toml.
+                    /// Update the layouts if you customize the data in `get_data`.
+                    /// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-data-layout
+                    fn get_data_layout(&self, data: &[u8]) -> DataLayout {
+                        DataLayout::from_data(data, &[#(#layouts),*])
+                    }
+                }
+            });
 
             let doc_comment = format!(
                 "IxOps implementation for `{}` with all required functions.",
@@ -66,6 +77,7 @@ pub(crate) fn get_instruction_ixops(
                     /// `fuzz_accounts.account_name.get_or_create_account()`.
                     /// If no signers are required, leave the vector empty.
                     /// For AccountMetas use <program>::accounts::<corresponding_metas>
+                    /// The remaining accounts are picked from the `AddressPool`.
                     /// For more details, see: https://ackee.xyz/trident/docs/latest/features/fuzz-instructions/#get-accounts
                     fn get_accounts(
                         &self,
@@ -77,8 +89,14 @@ pub(crate) fn get_instruction_ixops(
 
                         #(#accounts)*
 
+                        account_metas.extend(
+                            fuzz_accounts.remaining_account_metas(&self.remaining_accounts),
+                        );
+
                         Ok((signers, account_metas))
                     }
+
+                    #data_layout
                 }
             };
 
@@ -2,12 +2,14 @@ mod custom_types;
 mod fuzz_accounts;
 mod get_accounts;
 mod get_data;
+mod get_data_layout;
 mod get_ix_discriminator;
 mod get_program_id;
 mod instruction_account;
 mod instruction_inputs;
 mod instruction_ixops;
 mod instruction_variants;
+mod syn_types;
 
 use custom_types::*;
 use fuzz_accounts::*;
@@ -17,5 +19,8 @@ use instruction_inputs::*;
 use instruction_ixops::*;
 use instruction_variants::*;
 
+pub mod dictionary_generator;
 pub mod fuzz_instructions_generator;
+pub mod fuzz_instructions_refresh;
+pub mod idl_origin;
 pub mod test_fuzz_generator;
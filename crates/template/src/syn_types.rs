@@ -0,0 +1,87 @@
+use quote::format_ident;
+use syn::parse_quote;
+use trident_idl_spec::{DefinedType, IdlArrayLen, IdlGenericArg, IdlType, IdlTypeDefGeneric};
+
+// Extends `trident_idl_spec::idl_type_to_syn_type` with generic arguments of defined types
+// and generic array lengths, other types are converted by `trident_idl_spec`
+pub(crate) fn idl_type_to_syn_type(idl_type: &IdlType, pubkey_as_account_id: bool) -> syn::Type {
+    if !is_generic(idl_type) {
+        let (syn_type, _is_custom) =
+            trident_idl_spec::idl_type_to_syn_type(idl_type, 0, pubkey_as_account_id);
+        return syn_type;
+    }
+
+    match idl_type {
+        IdlType::Defined(DefinedType::Complex { name, generics }) => {
+            let type_ident = format_ident!("{}", name);
+            let generic_args: Vec<syn::GenericArgument> = generics
+                .iter()
+                .map(|generic| match generic {
+                    IdlGenericArg::Type { ty } => {
+                        let syn_type = idl_type_to_syn_type(ty, pubkey_as_account_id);
+                        syn::GenericArgument::Type(syn_type)
+                    }
+                    IdlGenericArg::Const { value } => {
+                        let value: syn::Expr =
+                            syn::parse_str(value).expect("Invalid const generic argument");
+                        syn::GenericArgument::Const(value)
+                    }
+                })
+                .collect();
+            parse_quote!(#type_ident<#(#generic_args),*>)
+        }
+        IdlType::Option(inner) => {
+            let inner = idl_type_to_syn_type(inner, pubkey_as_account_id);
+            parse_quote!(Option<#inner>)
+        }
+        IdlType::Vec(inner) => {
+            let inner = idl_type_to_syn_type(inner, pubkey_as_account_id);
+            parse_quote!(Vec<#inner>)
+        }
+        IdlType::Array(inner, len) => {
+            let inner = idl_type_to_syn_type(inner, pubkey_as_account_id);
+            match len {
+                IdlArrayLen::Generic(len) => {
+                    let len = format_ident!("{}", len);
+                    parse_quote!([#inner; #len])
+                }
+                IdlArrayLen::Value(len) => parse_quote!([#inner; #len]),
+            }
+        }
+        _ => unreachable!("Only complex defined types, options, vectors and arrays can be generic"),
+    }
+}
+
+// Generic parameters of the type definition, e.g. `<T, const N: usize>`
+pub(crate) fn idl_generics_to_syn_generics(generics: &[IdlTypeDefGeneric]) -> syn::Generics {
+    let params: Vec<syn::GenericParam> = generics
+        .iter()
+        .map(|generic| match generic {
+            IdlTypeDefGeneric::Type { name } => {
+                let name = format_ident!("{}", name);
+                parse_quote!(#name)
+            }
+            IdlTypeDefGeneric::Const { name, ty } => {
+                let name = format_ident!("{}", name);
+                let ty: syn::Type = syn::parse_str(ty).expect("Invalid const generic type");
+                parse_quote!(const #name: #ty)
+            }
+        })
+        .collect();
+
+    if params.is_empty() {
+        syn::Generics::default()
+    } else {
+        parse_quote!(<#(#params),*>)
+    }
+}
+
+// The type has generic arguments or a generic array length
+fn is_generic(idl_type: &IdlType) -> bool {
+    match idl_type {
+        IdlType::Defined(DefinedType::Complex { generics, .. }) => !generics.is_empty(),
+        IdlType::Option(inner) | IdlType::Vec(inner) => is_generic(inner),
+        IdlType::Array(inner, len) => matches!(len, IdlArrayLen::Generic(_)) || is_generic(inner),
+        _ => false,
+    }
+}
@@ -2,15 +2,24 @@ use quote::{format_ident, ToTokens};
 use syn::parse_quote;
 use trident_idl_spec::Idl;
 
-pub fn generate_source_code(_idl_instructions: &[Idl], lib_names: &[String]) -> String {
+use crate::idl_origin::is_anchor_idl;
+
+// `program_ids` are the lib names of the programs with the corresponding program IDs
+pub fn generate_source_code(idls: &[Idl], program_ids: &[(String, String)]) -> String {
     let mut use_statements: Vec<syn::ItemUse> = Vec::new();
 
     let mut programs: Vec<syn::Stmt> = Vec::new();
 
     let mut input_array: Vec<syn::Ident> = Vec::new();
 
-    for program in lib_names {
-        let (use_statement, program, program_variable) = process_program_entries(program);
+    for (lib_name, program_id) in program_ids {
+        // programs without an IDL are expected to be Anchor programs
+        let is_anchor = idls
+            .iter()
+            .find(|idl| idl.metadata.name.replace('-', "_") == lib_name.replace('-', "_"))
+            .map_or(true, is_anchor_idl);
+        let (use_statement, program, program_variable) =
+            process_program_entries(lib_name, program_id, is_anchor);
         // add to the use statements
         use_statements.push(use_statement);
         // add to the programs
@@ -47,10 +56,10 @@ pub fn generate_source_code(_idl_instructions: &[Idl], lib_names: &[String]) ->
             #(#programs)*
 
             let config = TridentConfig::new();
-            let mut client = TridentSVM::new_client(&[ #(#input_array),* ], &config);
+            let mut client = TridentClient::new_client(&[ #(#input_array),* ], &config);
             fuzz_trident!(
                 fuzz_ix: FuzzInstruction,
-                |fuzz_data: InstructionsSequence, client: TridentSVM, config: TridentConfig|
+                |fuzz_data: InstructionsSequence, client: TridentClient, config: TridentConfig|
             );
         }
     };
@@ -58,21 +67,31 @@ pub fn generate_source_code(_idl_instructions: &[Idl], lib_names: &[String]) ->
     test_fuzz_definition.into_token_stream().to_string()
 }
 
-fn process_program_entries(lib_name: &String) -> (syn::ItemUse, syn::Stmt, syn::Ident) {
+fn process_program_entries(
+    lib_name: &String,
+    program_id: &String,
+    is_anchor: bool,
+) -> (syn::ItemUse, syn::Stmt, syn::Ident) {
+    // Anchor generates the `entry` function, while non-Anchor programs
+    // are expected to expose their `process_instruction` function
+    let entry = match is_anchor {
+        true => format_ident!("entry"),
+        false => format_ident!("process_instruction"),
+    };
     // library name as identifier
     let library = format_ident!("{}", lib_name);
     // entry name as identifier
-    let library_entry = format_ident!("entry_{}", lib_name);
+    let library_entry = format_ident!("{}_{}", entry, lib_name);
     // variable name as identifier
     let variable_name = format_ident!("program_{}", library);
 
     // initial use statement
-    let use_statement = parse_quote!(use #library::entry as #library_entry;);
+    let use_statement = parse_quote!(use #library::#entry as #library_entry;);
 
     // program definition
     let program = parse_quote! {
         let #variable_name = ProgramEntrypoint::new(
-            pubkey!("fill corresponding program ID here"),
+            pubkey!(#program_id),
             None,
             processor!(#library_entry)
         );
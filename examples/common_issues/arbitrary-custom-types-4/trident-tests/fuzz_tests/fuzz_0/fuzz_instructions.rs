@@ -151,7 +151,7 @@ impl IxOps for Update {
 }
 /// Use AccountsStorage<T> where T can be one of:
 /// Keypair, PdaStore, TokenStore, MintStore, ProgramStore
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct FuzzAccounts {
     user: AccountsStorage<KeypairStore>,
     counter: AccountsStorage<KeypairStore>,
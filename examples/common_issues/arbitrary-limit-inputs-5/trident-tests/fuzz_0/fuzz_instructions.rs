@@ -399,7 +399,7 @@ impl IxOps for WithdrawUnlocked {
 }
 /// Check supported AccountsStorages at
 /// https://ackee.xyz/trident/docs/latest/features/account-storages/
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct FuzzAccounts {
     escrow_arbitrary_limit_inputs_5: AccountsStorage<PdaStore>,
     escrow_pda_authority_arbitrary_limit_inputs_5: AccountsStorage<PdaStore>,
@@ -27,6 +27,6 @@ fn main() {
         processor!(entry),
     );
     let config = TridentConfig::new();
-    let mut client = TridentSVM::new_client(&[program], &config);
-    fuzz_trident ! (fuzz_ix : FuzzInstruction , | fuzz_data : InstructionsSequence , client : TridentSVM , config : TridentConfig |);
+    let mut client = TridentClient::new_client(&[program], &config);
+    fuzz_trident ! (fuzz_ix : FuzzInstruction , | fuzz_data : InstructionsSequence , client : TridentClient , config : TridentConfig |);
 }
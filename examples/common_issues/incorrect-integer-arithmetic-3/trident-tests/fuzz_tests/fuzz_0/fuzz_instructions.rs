@@ -309,7 +309,7 @@ impl IxOps for WithdrawUnlocked {
 }
 /// Use AccountsStorage<T> where T can be one of:
 /// Keypair, PdaStore, TokenStore, MintStore, ProgramStore
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct FuzzAccounts {
     // No need to fuzz Token Program
     // token_program: AccountsStorage<ProgramStore>,
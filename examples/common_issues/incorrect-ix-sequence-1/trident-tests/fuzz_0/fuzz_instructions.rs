@@ -356,7 +356,7 @@ impl IxOps for Register {
 }
 /// Check supported AccountsStorages at
 /// https://ackee.xyz/trident/docs/latest/features/account-storages/
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct FuzzAccounts {
     author: AccountsStorage<KeypairStore>,
     investor: AccountsStorage<KeypairStore>,
@@ -207,7 +207,7 @@ impl IxOps for Withdraw {
 }
 /// Check supported AccountsStorages at
 /// https://ackee.xyz/trident/docs/latest/features/account-storages/
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct FuzzAccounts {
     author: AccountsStorage<KeypairStore>,
     escrow: AccountsStorage<PdaStore>,
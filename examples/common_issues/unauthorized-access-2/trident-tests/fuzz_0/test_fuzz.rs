@@ -25,6 +25,6 @@ fn main() {
         processor!(entry_unauthorized_access_2),
     );
     let config = Config::new();
-    let mut client = TridentSVM::new_client(&[program_unauthorized_access_2], &config);
+    let mut client = TridentClient::new_client(&[program_unauthorized_access_2], &config);
     fuzz_trident ! (fuzz_ix : FuzzInstruction , | fuzz_data : InstructionsSequence | { fuzz_iteration (fuzz_data , & config , & mut client) ; });
 }
@@ -161,7 +161,7 @@ impl IxOps for Update {
 }
 /// Check supported AccountsStorages at
 /// https://ackee.xyz/trident/docs/latest/features/account-storages/
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct FuzzAccounts {
     counter_: AccountsStorage<KeypairStore>,
     user_: AccountsStorage<KeypairStore>,
@@ -135,7 +135,7 @@ impl IxOps for Initialize {
 }
 /// Check supported AccountsStorages at
 /// https://ackee.xyz/trident/docs/latest/features/account-storages/
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct FuzzAccounts {
     metadata_account_cpi_metaplex_7: AccountsStorage<PdaStore>,
     mint_cpi_metaplex_7: AccountsStorage<KeypairStore>,
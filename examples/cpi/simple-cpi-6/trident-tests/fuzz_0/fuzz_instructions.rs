@@ -153,7 +153,7 @@ impl IxOps for InitializeCaller {
 }
 /// Check supported AccountsStorages at
 /// https://ackee.xyz/trident/docs/latest/features/account-storages/
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct FuzzAccounts {
     signer_callee: AccountsStorage<KeypairStore>,
     signer_caller: AccountsStorage<KeypairStore>,
@@ -34,6 +34,6 @@ fn main() {
         processor!(entry_caller),
     );
     let config = TridentConfig::new();
-    let mut client = TridentSVM::new_client(&[program_callee, program_caller], &config);
-    fuzz_trident ! (fuzz_ix : FuzzInstruction , | fuzz_data : InstructionsSequence , client : TridentSVM , config : TridentConfig |);
+    let mut client = TridentClient::new_client(&[program_callee, program_caller], &config);
+    fuzz_trident ! (fuzz_ix : FuzzInstruction , | fuzz_data : InstructionsSequence , client : TridentClient , config : TridentConfig |);
 }
@@ -95,7 +95,7 @@ impl IxOps for InitializeFn {
 }
 /// Check supported AccountsStorages at
 /// https://ackee.xyz/trident/docs/latest/features/account-storages/
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct FuzzAccounts {
     author_hello_world: AccountsStorage<KeypairStore>,
     hello_world_account_hello_world: AccountsStorage<PdaStore>,
@@ -27,6 +27,6 @@ fn main() {
         processor!(entry_hello_world),
     );
     let config = TridentConfig::new();
-    let mut client = TridentSVM::new_client(&[program_hello_world], &config);
-    fuzz_trident ! (fuzz_ix : FuzzInstruction , | fuzz_data : InstructionsSequence , client : TridentSVM , config : TridentConfig |);
+    let mut client = TridentClient::new_client(&[program_hello_world], &config);
+    fuzz_trident ! (fuzz_ix : FuzzInstruction , | fuzz_data : InstructionsSequence , client : TridentClient , config : TridentConfig |);
 }
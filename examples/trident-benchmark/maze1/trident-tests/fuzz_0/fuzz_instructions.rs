@@ -460,7 +460,7 @@ impl IxOps for MoveWest {
 }
 /// Check supported AccountsStorages at
 /// https://ackee.xyz/trident/docs/latest/features/account-storages/
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct FuzzAccounts {
     state: AccountsStorage<PdaStore>,
     state_author: AccountsStorage<KeypairStore>,
@@ -25,6 +25,6 @@ fn main() {
         processor!(entry_maze2),
     );
     let config = TridentConfig::new();
-    let mut client = TridentSVM::new_client(&[program_maze2], &config);
-    fuzz_trident ! (fuzz_ix : FuzzInstruction , | fuzz_data : InstructionsSequence , client : TridentSVM , config : TridentConfig |);
+    let mut client = TridentClient::new_client(&[program_maze2], &config);
+    fuzz_trident ! (fuzz_ix : FuzzInstruction , | fuzz_data : InstructionsSequence , client : TridentClient , config : TridentConfig |);
 }
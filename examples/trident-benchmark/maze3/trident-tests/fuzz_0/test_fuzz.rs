@@ -25,6 +25,6 @@ fn main() {
         processor!(entry_maze3),
     );
     let config = TridentConfig::new();
-    let mut client = TridentSVM::new_client(&[program_maze3], &config);
-    fuzz_trident ! (fuzz_ix : FuzzInstruction , | fuzz_data : InstructionsSequence , client : TridentSVM , config : TridentConfig |);
+    let mut client = TridentClient::new_client(&[program_maze3], &config);
+    fuzz_trident ! (fuzz_ix : FuzzInstruction , | fuzz_data : InstructionsSequence , client : TridentClient , config : TridentConfig |);
 }